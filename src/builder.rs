@@ -0,0 +1,206 @@
+use std::fmt;
+
+use chrono::{Local, Months, NaiveDate};
+
+use crate::models::{ClearedStatus, FlagColor, SaveSubTransaction, SaveTransaction};
+
+pub const MAX_MEMO_LENGTH: usize = 200;
+pub const MAX_IMPORT_ID_LENGTH: usize = 36;
+pub const MAX_PAYEE_NAME_LENGTH: usize = 50;
+pub const MAX_YEARS_IN_FUTURE: u32 = 5;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationProblem {
+    MissingAccountId,
+    InvalidDate { date: String },
+    DateTooFarInFuture { date: NaiveDate, latest: NaiveDate },
+    ConflictingPayee { subtransaction: Option<usize> },
+    MemoTooLong { subtransaction: Option<usize>, length: usize },
+    PayeeNameTooLong { subtransaction: Option<usize>, length: usize },
+    ImportIdTooLong { length: usize },
+    CategoryOnSplit,
+    SubtransactionSumMismatch { amount: i64, subtransaction_total: i64 }
+}
+
+impl fmt::Display for ValidationProblem {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let location = |subtransaction: &Option<usize>| match subtransaction {
+            Some(index) => format!("subtransaction {} ", index),
+            None => String::new()
+        };
+
+        match self {
+            ValidationProblem::MissingAccountId => write!(formatter, "account_id is required"),
+            ValidationProblem::InvalidDate { date } => write!(formatter, "date `{}` is not an ISO date", date),
+            ValidationProblem::DateTooFarInFuture { date, latest } => write!(formatter, "date {} is after {}, more than {} years out", date, latest, MAX_YEARS_IN_FUTURE),
+            ValidationProblem::ConflictingPayee { subtransaction } => write!(formatter, "{}payee_id and payee_name are mutually exclusive", location(subtransaction)),
+            ValidationProblem::MemoTooLong { subtransaction, length } => write!(formatter, "{}memo has {} characters, at most {} are allowed", location(subtransaction), length, MAX_MEMO_LENGTH),
+            ValidationProblem::PayeeNameTooLong { subtransaction, length } => write!(formatter, "{}payee_name has {} characters, at most {} are allowed", location(subtransaction), length, MAX_PAYEE_NAME_LENGTH),
+            ValidationProblem::ImportIdTooLong { length } => write!(formatter, "import_id has {} characters, at most {} are allowed", length, MAX_IMPORT_ID_LENGTH),
+            ValidationProblem::CategoryOnSplit => write!(formatter, "split transactions cannot have a category_id"),
+            ValidationProblem::SubtransactionSumMismatch { amount, subtransaction_total } => write!(formatter, "subtransactions add up to {} but the amount is {}", subtransaction_total, amount)
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    pub problems: Vec<ValidationProblem>
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let problems: Vec<String> = self.problems.iter().map(ValidationProblem::to_string).collect();
+        write!(formatter, "invalid transaction: {}", problems.join("; "))
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+// Checks `transaction` against the rules the API enforces, collecting every problem instead of stopping at the first.
+pub fn validate_save_transaction(transaction: &SaveTransaction, today: NaiveDate) -> Result<(), ValidationError> {
+    let mut problems: Vec<ValidationProblem> = vec![];
+
+    if transaction.account_id.is_empty() {
+        problems.push(ValidationProblem::MissingAccountId);
+    }
+
+    match NaiveDate::parse_from_str(&transaction.date, "%Y-%m-%d") {
+        Ok(date) => {
+            let latest: NaiveDate = today.checked_add_months(Months::new(MAX_YEARS_IN_FUTURE * 12)).unwrap_or(NaiveDate::MAX);
+            if date > latest {
+                problems.push(ValidationProblem::DateTooFarInFuture { date, latest });
+            }
+        },
+        Err(_) => problems.push(ValidationProblem::InvalidDate { date: transaction.date.clone() })
+    }
+
+    if transaction.payee_id.is_some() && transaction.payee_name.is_some() {
+        problems.push(ValidationProblem::ConflictingPayee { subtransaction: None });
+    }
+
+    check_lengths(&mut problems, None, transaction.memo.as_deref(), transaction.payee_name.as_deref());
+
+    if let Some(import_id) = &transaction.import_id {
+        let length: usize = import_id.chars().count();
+        if length > MAX_IMPORT_ID_LENGTH {
+            problems.push(ValidationProblem::ImportIdTooLong { length });
+        }
+    }
+
+    if let Some(subtransactions) = transaction.subtransactions.as_ref().filter(|subtransactions| !subtransactions.is_empty()) {
+        if transaction.category_id.is_some() {
+            problems.push(ValidationProblem::CategoryOnSplit);
+        }
+
+        let subtransaction_total: i64 = subtransactions.iter().map(|subtransaction| subtransaction.amount).sum();
+        if subtransaction_total != transaction.amount {
+            problems.push(ValidationProblem::SubtransactionSumMismatch { amount: transaction.amount, subtransaction_total });
+        }
+
+        for (index, subtransaction) in subtransactions.iter().enumerate() {
+            if subtransaction.payee_id.is_some() && subtransaction.payee_name.is_some() {
+                problems.push(ValidationProblem::ConflictingPayee { subtransaction: Some(index) });
+            }
+
+            check_lengths(&mut problems, Some(index), subtransaction.memo.as_deref(), subtransaction.payee_name.as_deref());
+        }
+    }
+
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(ValidationError { problems })
+    }
+}
+
+fn check_lengths(problems: &mut Vec<ValidationProblem>, subtransaction: Option<usize>, memo: Option<&str>, payee_name: Option<&str>) {
+    if let Some(length) = memo.map(|memo| memo.chars().count()).filter(|length| *length > MAX_MEMO_LENGTH) {
+        problems.push(ValidationProblem::MemoTooLong { subtransaction, length });
+    }
+
+    if let Some(length) = payee_name.map(|payee_name| payee_name.chars().count()).filter(|length| *length > MAX_PAYEE_NAME_LENGTH) {
+        problems.push(ValidationProblem::PayeeNameTooLong { subtransaction, length });
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SaveTransactionBuilder {
+    transaction: SaveTransaction
+}
+
+impl SaveTransactionBuilder {
+    pub fn new(account_id: &str, date: NaiveDate, amount: i64) -> SaveTransactionBuilder {
+        SaveTransactionBuilder {
+            transaction: SaveTransaction {
+                account_id:         account_id.to_string(),
+                date:               date.format("%Y-%m-%d").to_string(),
+                amount,
+                payee_id:           None,
+                payee_name:         None,
+                category_id:        None,
+                memo:               None,
+                cleared:            None,
+                approved:           None,
+                flag_color:         None,
+                import_id:          None,
+                subtransactions:    None
+            }
+        }
+    }
+
+    pub fn payee_id(mut self, payee_id: &str) -> SaveTransactionBuilder {
+        self.transaction.payee_id = Some(payee_id.to_string());
+        self
+    }
+
+    pub fn payee_name(mut self, payee_name: &str) -> SaveTransactionBuilder {
+        self.transaction.payee_name = Some(payee_name.to_string());
+        self
+    }
+
+    pub fn category_id(mut self, category_id: &str) -> SaveTransactionBuilder {
+        self.transaction.category_id = Some(category_id.to_string());
+        self
+    }
+
+    pub fn memo(mut self, memo: &str) -> SaveTransactionBuilder {
+        self.transaction.memo = Some(memo.to_string());
+        self
+    }
+
+    pub fn cleared(mut self, cleared: ClearedStatus) -> SaveTransactionBuilder {
+        self.transaction.cleared = Some(cleared);
+        self
+    }
+
+    pub fn approved(mut self, approved: bool) -> SaveTransactionBuilder {
+        self.transaction.approved = Some(approved);
+        self
+    }
+
+    pub fn flag_color(mut self, flag_color: FlagColor) -> SaveTransactionBuilder {
+        self.transaction.flag_color = Some(flag_color);
+        self
+    }
+
+    pub fn import_id(mut self, import_id: &str) -> SaveTransactionBuilder {
+        self.transaction.import_id = Some(import_id.to_string());
+        self
+    }
+
+    pub fn subtransaction(mut self, subtransaction: SaveSubTransaction) -> SaveTransactionBuilder {
+        self.transaction.subtransactions.get_or_insert_with(Vec::new).push(subtransaction);
+        self
+    }
+
+    pub fn build(self) -> Result<SaveTransaction, ValidationError> {
+        let today: NaiveDate = Local::now().date_naive();
+        self.build_as_of(today)
+    }
+
+    pub fn build_as_of(self, today: NaiveDate) -> Result<SaveTransaction, ValidationError> {
+        validate_save_transaction(&self.transaction, today)?;
+        Ok(self.transaction)
+    }
+}
@@ -0,0 +1,85 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+pub const REDACTED: &str = "[REDACTED]";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Interaction {
+    pub method:         String,
+    pub endpoint:       String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_body:   Option<String>,
+    pub status:         u16,
+    pub body:           String
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Cassette {
+    pub interactions: Vec<Interaction>
+}
+
+impl Cassette {
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Cassette> {
+        let contents: String = fs::read_to_string(path)?;
+        serde_json::from_str(&contents).map_err(io::Error::from)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let contents: String = serde_json::to_string_pretty(self).map_err(io::Error::from)?;
+        fs::write(path, contents)
+    }
+
+    pub(crate) fn record(&mut self, method: &str, endpoint: &str, request_body: Option<&str>, status: u16, body: &str, api_token: &str) {
+        self.interactions.push(Interaction {
+            method:         method.to_string(),
+            endpoint:       redact(endpoint, api_token),
+            request_body:   request_body.map(|request_body| redact(request_body, api_token)),
+            status,
+            body:           redact(body, api_token)
+        });
+    }
+}
+
+// Serves a cassette back in recording order. Every interaction is handed out once, so
+// repeated calls to the same endpoint replay the responses in the order they were captured.
+#[derive(Debug)]
+pub(crate) struct Replay {
+    cassette:   Cassette,
+    served:     Vec<bool>
+}
+
+impl Replay {
+    pub(crate) fn new(cassette: Cassette) -> Replay {
+        let served: Vec<bool> = vec![false; cassette.interactions.len()];
+        Replay { cassette, served }
+    }
+
+    pub(crate) fn next(&mut self, method: &str, endpoint: &str) -> reqwest::Response {
+        let position: usize = self.cassette.interactions
+            .iter()
+            .enumerate()
+            .position(|(index, interaction)| !self.served[index] && interaction.method == method && interaction.endpoint == endpoint)
+            .unwrap_or_else(|| panic!("Cassette has no unplayed interaction for `{} {}`.", method, endpoint));
+        self.served[position] = true;
+
+        let interaction: &Interaction = &self.cassette.interactions[position];
+        let response: http::Response<String> = http::Response::builder()
+            .status(interaction.status)
+            .header("Content-Type", "application/json; charset=utf-8")
+            .body(interaction.body.clone())
+            .expect("Cassette contains an invalid HTTP status.");
+
+        reqwest::Response::from(response)
+    }
+}
+
+fn redact(value: &str, api_token: &str) -> String {
+    if api_token.is_empty() {
+        value.to_string()
+    } else {
+        value.replace(api_token, REDACTED)
+    }
+}
@@ -0,0 +1,291 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use chrono::{Datelike, NaiveDate};
+use either::{Either, Left, Right};
+use regex::Regex;
+use serde::Deserialize;
+
+use crate::client::Client;
+use crate::models::{ErrorResponse, PatchTransactionsWrapper, SaveTransactionWithId, TransactionDetail};
+use crate::query::{TransactionFilter, TransactionQuery};
+
+// Every condition that is set has to hold. Amounts are inclusive milliunit bounds, so outflows
+// are negative, and days are inclusive days of the month.
+#[derive(Debug, Clone, Default)]
+pub struct CategoryRule {
+    pub name:           Option<String>,
+    pub payee_id:       Option<String>,
+    // Case-insensitive substring of the payee name or the imported payee name.
+    pub payee_name:     Option<String>,
+    pub min_amount:     Option<i64>,
+    pub max_amount:     Option<i64>,
+    pub memo:           Option<Regex>,
+    pub account_id:     Option<String>,
+    pub min_day:        Option<u32>,
+    pub max_day:        Option<u32>,
+    pub category_id:    String
+}
+
+impl CategoryRule {
+    pub fn new(category_id: &str) -> CategoryRule {
+        CategoryRule { category_id: category_id.to_string(), ..CategoryRule::default() }
+    }
+
+    pub fn matches(&self, transaction: &TransactionDetail) -> bool {
+        let payee_names: [Option<&str>; 2] = [transaction.payee_name.as_deref(), transaction.import_payee_name.as_deref()];
+        let day: Option<u32> = NaiveDate::parse_from_str(&transaction.date, "%Y-%m-%d").ok().map(|date| date.day());
+
+        self.payee_id.as_ref().map_or(true, |payee_id| transaction.payee_id.as_ref() == Some(payee_id))
+            && self.payee_name.as_ref().map_or(true, |pattern| {
+                let pattern: String = pattern.to_lowercase();
+                payee_names.iter().flatten().any(|name| name.to_lowercase().contains(&pattern))
+            })
+            && self.min_amount.map_or(true, |min_amount| transaction.amount >= min_amount)
+            && self.max_amount.map_or(true, |max_amount| transaction.amount <= max_amount)
+            && self.memo.as_ref().map_or(true, |memo| transaction.memo.as_deref().is_some_and(|text| memo.is_match(text)))
+            && self.account_id.as_ref().map_or(true, |account_id| transaction.account_id == *account_id)
+            && self.min_day.map_or(true, |min_day| day.is_some_and(|day| day >= min_day))
+            && self.max_day.map_or(true, |max_day| day.is_some_and(|day| day <= max_day))
+    }
+
+    fn has_condition(&self) -> bool {
+        self.payee_id.is_some() || self.payee_name.is_some() || self.min_amount.is_some() || self.max_amount.is_some()
+            || self.memo.is_some() || self.account_id.is_some() || self.min_day.is_some() || self.max_day.is_some()
+    }
+}
+
+#[derive(Debug)]
+pub enum CategoryRuleError {
+    Toml(String),
+    Regex { rule: usize, error: regex::Error },
+    InvalidRule { rule: usize, detail: &'static str }
+}
+
+impl fmt::Display for CategoryRuleError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CategoryRuleError::Toml(detail) => write!(formatter, "invalid category rules: {}", detail),
+            CategoryRuleError::Regex { rule, error } => write!(formatter, "rule {}: {}", rule, error),
+            CategoryRuleError::InvalidRule { rule, detail } => write!(formatter, "rule {}: {}", rule, detail)
+        }
+    }
+}
+
+impl std::error::Error for CategoryRuleError {}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RuleFile {
+    #[serde(default)]
+    rule: Vec<RuleDefinition>
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RuleDefinition {
+    name:           Option<String>,
+    payee_id:       Option<String>,
+    payee:          Option<String>,
+    min_amount:     Option<i64>,
+    max_amount:     Option<i64>,
+    memo:           Option<String>,
+    account_id:     Option<String>,
+    min_day:        Option<u32>,
+    max_day:        Option<u32>,
+    category_id:    String
+}
+
+// Assigns categories to uncategorized transactions, first matching rule wins. Rules are written as
+//
+//     [[rule]]
+//     name = "Rent"
+//     payee = "landlord"
+//     max_amount = -1000000
+//     min_day = 1
+//     max_day = 5
+//     category_id = "a1b2..."
+#[derive(Debug, Clone, Default)]
+pub struct Categorizer {
+    pub rules: Vec<CategoryRule>
+}
+
+impl Categorizer {
+    pub fn new() -> Categorizer {
+        Categorizer::default()
+    }
+
+    pub fn from_toml(rules: &str) -> Result<Categorizer, CategoryRuleError> {
+        let file: RuleFile = toml::from_str(rules).map_err(|error| CategoryRuleError::Toml(error.to_string()))?;
+
+        let rules: Vec<CategoryRule> = file.rule.into_iter().enumerate().map(|(index, definition)| {
+            let rule: usize = index + 1;
+            let memo: Option<Regex> = definition.memo
+                .map(|pattern| Regex::new(&pattern).map_err(|error| CategoryRuleError::Regex { rule, error }))
+                .transpose()?;
+            let category_rule: CategoryRule = CategoryRule {
+                name:           definition.name,
+                payee_id:       definition.payee_id,
+                payee_name:     definition.payee,
+                min_amount:     definition.min_amount,
+                max_amount:     definition.max_amount,
+                memo,
+                account_id:     definition.account_id,
+                min_day:        definition.min_day,
+                max_day:        definition.max_day,
+                category_id:    definition.category_id
+            };
+
+            if !category_rule.has_condition() {
+                return Err(CategoryRuleError::InvalidRule { rule, detail: "needs at least one condition" });
+            }
+            if category_rule.min_day.into_iter().chain(category_rule.max_day).any(|day| !(1..=31).contains(&day)) {
+                return Err(CategoryRuleError::InvalidRule { rule, detail: "days must be between 1 and 31" });
+            }
+
+            Ok(category_rule)
+        }).collect::<Result<Vec<CategoryRule>, CategoryRuleError>>()?;
+
+        Ok(Categorizer { rules })
+    }
+
+    pub fn rule(mut self, rule: CategoryRule) -> Categorizer {
+        self.rules.push(rule);
+        self
+    }
+
+    // Works out the change for every transaction a rule matches. Transactions that are split,
+    // transfers or already categorized are left alone.
+    pub fn plan(&self, transactions: &[TransactionDetail]) -> AuditReport {
+        let changes: Vec<CategoryChange> = transactions.iter()
+            .filter(|transaction| {
+                !transaction.deleted && transaction.category_id.is_none() && transaction.transfer_account_id.is_none() && transaction.subtransactions.is_empty()
+            })
+            .filter_map(|transaction| {
+                let (index, rule) = self.rules.iter().enumerate().find(|(_, rule)| rule.matches(transaction))?;
+                Some(CategoryChange {
+                    transaction_id: transaction.id.clone(),
+                    date:           transaction.date.clone(),
+                    amount:         transaction.amount,
+                    payee_name:     transaction.payee_name.clone(),
+                    rule:           index,
+                    rule_name:      rule.name.clone(),
+                    category_id:    rule.category_id.clone()
+                })
+            })
+            .collect();
+
+        AuditReport { changes, applied: false, server_knowledge: None }
+    }
+
+    // Fetches the budget's uncategorized transactions and categorizes them with one bulk PATCH.
+    pub async fn run(&self, client: &Client, budget_id: &str, since_date: Option<NaiveDate>) -> Either<AuditReport, ErrorResponse> {
+        let mut query: TransactionQuery = TransactionQuery::new(budget_id).filter(TransactionFilter::Uncategorized);
+        if let Some(since_date) = since_date {
+            query = query.since_date(since_date);
+        }
+
+        let transactions: Vec<TransactionDetail> = match client.get_transactions(&query).await {
+            Left(response) => response.data.transactions,
+            Right(error_response) => return Right(error_response)
+        };
+
+        let mut report: AuditReport = self.plan(&transactions);
+        if report.changes.is_empty() {
+            return Left(report);
+        }
+
+        let wrapper: PatchTransactionsWrapper = PatchTransactionsWrapper {
+            transactions: report.changes.iter().map(|change| SaveTransactionWithId {
+                id:             Some(change.transaction_id.clone()),
+                category_id:    Some(change.category_id.clone()),
+                ..SaveTransactionWithId::default()
+            }).collect()
+        };
+
+        match client.update_transactions(budget_id, &wrapper).await {
+            Left(response) => {
+                report.applied = true;
+                report.server_knowledge = Some(response.data.server_knowledge);
+                Left(report)
+            },
+            Right(error_response) => Right(error_response)
+        }
+    }
+}
+
+// Turns payee to category habits into rules: a payee gets a rule once it has at least
+// `min_occurrences` categorized transactions and `min_share` of them use the same category.
+pub fn learn_rules(history: &[TransactionDetail], min_occurrences: usize, min_share: f64) -> Vec<CategoryRule> {
+    let mut frequencies: HashMap<&str, (Option<&str>, HashMap<&str, usize>)> = HashMap::new();
+
+    for transaction in history {
+        let (payee_id, category_id) = match (&transaction.payee_id, &transaction.category_id) {
+            (Some(payee_id), Some(category_id)) if !transaction.deleted && transaction.transfer_account_id.is_none() => (payee_id, category_id),
+            _ => continue
+        };
+
+        let (payee_name, categories) = frequencies.entry(payee_id).or_default();
+        *payee_name = payee_name.or(transaction.payee_name.as_deref());
+        *categories.entry(category_id).or_default() += 1;
+    }
+
+    let mut rules: Vec<CategoryRule> = frequencies.into_iter().filter_map(|(payee_id, (payee_name, categories))| {
+        let total: usize = categories.values().sum();
+        let (category_id, count) = categories.into_iter().max_by(|left, right| left.1.cmp(&right.1).then(right.0.cmp(left.0)))?;
+        if total < min_occurrences || (count as f64) < min_share * total as f64 {
+            return None;
+        }
+
+        Some(CategoryRule {
+            name:       Some(format!("learned: {}", payee_name.unwrap_or(payee_id))),
+            payee_id:   Some(payee_id.to_string()),
+            ..CategoryRule::new(category_id)
+        })
+    }).collect();
+
+    rules.sort_by(|left, right| left.name.cmp(&right.name));
+    rules
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CategoryChange {
+    pub transaction_id: String,
+    pub date:           String,
+    pub amount:         i64,
+    pub payee_name:     Option<String>,
+    pub rule:           usize,
+    pub rule_name:      Option<String>,
+    pub category_id:    String
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditReport {
+    pub changes:            Vec<CategoryChange>,
+    // Whether the changes were sent, as opposed to a `plan`.
+    pub applied:            bool,
+    pub server_knowledge:   Option<i64>
+}
+
+impl fmt::Display for AuditReport {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for change in &self.changes {
+            let rule: String = match &change.rule_name {
+                Some(name) => format!("rule `{}`", name),
+                None => format!("rule {}", change.rule + 1)
+            };
+            writeln!(
+                formatter,
+                "{} {} {} {}: category -> {} ({})",
+                if self.applied { "changed" } else { "would change" },
+                change.transaction_id,
+                change.date,
+                change.payee_name.as_deref().unwrap_or("(no payee)"),
+                change.category_id,
+                rule
+            )?;
+        }
+
+        Ok(())
+    }
+}
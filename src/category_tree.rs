@@ -0,0 +1,183 @@
+use std::collections::HashMap;
+
+use crate::models::{BudgetDetail, Category, CategoryGroupWithCategories};
+
+pub const INTERNAL_MASTER_CATEGORY: &str = "Internal Master Category";
+pub const CREDIT_CARD_PAYMENTS: &str = "Credit Card Payments";
+pub const HIDDEN_CATEGORIES: &str = "Hidden Categories";
+
+// YNAB keeps a few groups of its own next to the ones the user creates. The internal master group
+// holds `Inflow: Ready to Assign` and `Uncategorized`, the credit card group one payment category
+// per card, and the hidden group every hidden category whose own group is gone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupKind {
+    User,
+    Internal,
+    CreditCardPayments,
+    Hidden
+}
+
+impl GroupKind {
+    fn of(name: &str) -> GroupKind {
+        match name {
+            INTERNAL_MASTER_CATEGORY => GroupKind::Internal,
+            CREDIT_CARD_PAYMENTS => GroupKind::CreditCardPayments,
+            HIDDEN_CATEGORIES => GroupKind::Hidden,
+            _ => GroupKind::User
+        }
+    }
+
+    // Where the YNAB app lists the group: card payments first, then the user's groups, then the
+    // groups it doesn't show.
+    fn rank(&self) -> u8 {
+        match self {
+            GroupKind::CreditCardPayments => 0,
+            GroupKind::User => 1,
+            GroupKind::Hidden => 2,
+            GroupKind::Internal => 3
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GroupNode<'a> {
+    pub id:         &'a str,
+    pub name:       &'a str,
+    pub hidden:     bool,
+    pub kind:       GroupKind,
+    pub categories: Vec<&'a Category>
+}
+
+impl<'a> GroupNode<'a> {
+    pub fn visible_categories(&self) -> impl Iterator<Item = &'a Category> + '_ {
+        self.categories.iter().copied().filter(|category| !category.hidden)
+    }
+}
+
+// Category groups with their categories, built from `get_categories` or `get_budget`. Deleted
+// groups and categories are left out. Hidden categories that YNAB files under `Hidden Categories`
+// are put back in the group `original_category_group_id` names, after that group's own categories,
+// so a category keeps its place whether or not it is hidden.
+#[derive(Debug, Clone)]
+pub struct CategoryTree<'a> {
+    groups:     Vec<GroupNode<'a>>,
+    positions:  HashMap<&'a str, (usize, usize)>
+}
+
+impl<'a> CategoryTree<'a> {
+    pub fn from_groups(category_groups: &'a [CategoryGroupWithCategories]) -> CategoryTree<'a> {
+        let groups: Vec<(GroupNode<'a>, Vec<&'a Category>)> = category_groups.iter()
+            .filter(|group| !group.deleted)
+            .map(|group| (group_node(&group.id, &group.name, group.hidden), group.categories.iter().collect()))
+            .collect();
+        CategoryTree::build(groups)
+    }
+
+    pub fn from_budget(budget: &'a BudgetDetail) -> CategoryTree<'a> {
+        let groups: Vec<(GroupNode<'a>, Vec<&'a Category>)> = budget.category_groups.iter()
+            .filter(|group| !group.deleted)
+            .map(|group| {
+                let categories: Vec<&Category> = budget.categories.iter().filter(|category| category.category_group_id == group.id).collect();
+                (group_node(&group.id, &group.name, group.hidden), categories)
+            })
+            .collect();
+        CategoryTree::build(groups)
+    }
+
+    fn build(groups: Vec<(GroupNode<'a>, Vec<&'a Category>)>) -> CategoryTree<'a> {
+        let mut groups: Vec<(GroupNode<'a>, Vec<&'a Category>)> = groups;
+        groups.sort_by_key(|(group, _)| group.kind.rank());
+
+        let indices: HashMap<&str, usize> = groups.iter().enumerate().map(|(position, (group, _))| (group.id, position)).collect();
+        let mut moved: Vec<(usize, &'a Category)> = vec![];
+        for (group, categories) in groups.iter_mut() {
+            for category in categories.iter().copied().filter(|category| !category.deleted) {
+                let original: Option<usize> = category.original_category_group_id.as_deref()
+                    .filter(|_| group.kind == GroupKind::Hidden)
+                    .and_then(|group_id| indices.get(group_id).copied());
+                match original {
+                    Some(position) => moved.push((position, category)),
+                    None => group.categories.push(category)
+                }
+            }
+        }
+
+        let mut groups: Vec<GroupNode<'a>> = groups.into_iter().map(|(group, _)| group).collect();
+        for (position, category) in moved {
+            groups[position].categories.push(category);
+        }
+
+        let mut positions: HashMap<&'a str, (usize, usize)> = HashMap::new();
+        for (group_position, group) in groups.iter().enumerate() {
+            for (category_position, category) in group.categories.iter().enumerate() {
+                positions.insert(&category.id, (group_position, category_position));
+            }
+        }
+
+        CategoryTree { groups, positions }
+    }
+
+    // Every group in the order the YNAB app lists them, followed by `Hidden Categories` and the
+    // internal master group.
+    pub fn groups(&self) -> &[GroupNode<'a>] {
+        &self.groups
+    }
+
+    // The groups the user created, without the ones YNAB keeps for itself.
+    pub fn user_groups(&self) -> impl Iterator<Item = &GroupNode<'a>> {
+        self.groups.iter().filter(|group| group.kind == GroupKind::User)
+    }
+
+    pub fn group(&self, group_id: &str) -> Option<&GroupNode<'a>> {
+        self.groups.iter().find(|group| group.id == group_id)
+    }
+
+    pub fn group_named(&self, name: &str) -> Option<&GroupNode<'a>> {
+        self.groups.iter().find(|group| group.name == name)
+    }
+
+    pub fn category(&self, category_id: &str) -> Option<&'a Category> {
+        self.positions.get(category_id).map(|(group, category)| self.groups[*group].categories[*category])
+    }
+
+    // The group a category is listed under, which for hidden categories is their original group.
+    pub fn group_of(&self, category_id: &str) -> Option<&GroupNode<'a>> {
+        self.positions.get(category_id).map(|(group, _)| &self.groups[*group])
+    }
+
+    // `Inflow: Ready to Assign`, where income is categorized.
+    pub fn ready_to_assign(&self) -> Option<&'a Category> {
+        self.groups.iter()
+            .filter(|group| group.kind == GroupKind::Internal)
+            .flat_map(|group| group.categories.iter().copied())
+            .find(|category| category.name.starts_with("Inflow:"))
+    }
+
+    // `Group/Category`, e.g. `Bills/Rent`.
+    pub fn path(&self, category_id: &str) -> Option<String> {
+        let group: &GroupNode = self.group_of(category_id)?;
+        Some(format!("{}/{}", group.name, self.category(category_id)?.name))
+    }
+
+    // Looks a category up by `Group/Category`. Names may contain slashes themselves, so every split
+    // point is tried. A path without a group finds the category if only one has that name.
+    pub fn find(&self, path: &str) -> Option<&'a Category> {
+        let by_path: Option<&Category> = path.match_indices('/').find_map(|(split, _)| {
+            let group: &GroupNode = self.group_named(&path[..split])?;
+            group.categories.iter().copied().find(|category| category.name == path[split + 1..])
+        });
+        if by_path.is_some() {
+            return by_path;
+        }
+
+        let mut named = self.groups.iter().flat_map(|group| group.categories.iter().copied()).filter(|category| category.name == path);
+        match (named.next(), named.next()) {
+            (Some(category), None) => Some(category),
+            _ => None
+        }
+    }
+}
+
+fn group_node<'a>(id: &'a str, name: &'a str, hidden: bool) -> GroupNode<'a> {
+    GroupNode { id, name, hidden, kind: GroupKind::of(name), categories: vec![] }
+}
@@ -1,534 +1,460 @@
+use std::sync::Mutex;
+
+use chrono::NaiveDate;
 use either::{Either, Left, Right};
+use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
+
+use crate::cassette::{Cassette, Replay};
+use crate::query::{TransactionFilter, TransactionQuery, TransactionScope};
 
 const API_BASE_URL: &str = "https://api.ynab.com/v1";
 
+// Everything outside of RFC 3986's unreserved characters gets escaped, so an id can never
+// introduce extra path segments, query parameters or fragments.
+const COMPONENT: &AsciiSet = &CONTROLS
+    .add(b' ').add(b'"').add(b'#').add(b'$').add(b'%').add(b'&').add(b'\'').add(b'(').add(b')')
+    .add(b'*').add(b'+').add(b',').add(b'/').add(b':').add(b';').add(b'<').add(b'=').add(b'>')
+    .add(b'?').add(b'@').add(b'[').add(b'\\').add(b']').add(b'^').add(b'`').add(b'{').add(b'|')
+    .add(b'}').add(b'!');
+
+pub(crate) struct Endpoint {
+    path:       String,
+    parameters: Vec<String>,
+    error:      Option<String>
+}
+
+impl Endpoint {
+    pub(crate) fn new(segment: &'static str) -> Endpoint {
+        Endpoint { path: String::new(), parameters: vec![], error: None }.segment(segment)
+    }
+
+    pub(crate) fn segment(mut self, segment: &'static str) -> Endpoint {
+        self.path.push('/');
+        self.path.push_str(segment);
+        self
+    }
+
+    pub(crate) fn id(mut self, name: &str, value: &str) -> Endpoint {
+        if value.is_empty() || value == "." || value == ".." || value.chars().any(char::is_control) {
+            self.reject(format!("`{}` is not a valid {}.", value, name));
+            return self;
+        }
+
+        self.path.push('/');
+        self.path.push_str(&utf8_percent_encode(value, COMPONENT).to_string());
+        self
+    }
+
+    pub(crate) fn month(self, value: &str) -> Endpoint {
+        if value == "current" || NaiveDate::parse_from_str(value, "%Y-%m-%d").is_ok() {
+            self.id("month", value)
+        } else {
+            let mut endpoint: Endpoint = self;
+            endpoint.reject(format!("`{}` is not a valid month, expected `current` or an ISO date.", value));
+            endpoint
+        }
+    }
+
+    pub(crate) fn parameter<V: ToString>(mut self, key: &'static str, value: Option<V>) -> Endpoint {
+        if let Some(some_value) = value {
+            self.parameters.push(format!("{}={}", key, utf8_percent_encode(&some_value.to_string(), COMPONENT)));
+        }
+
+        self
+    }
+
+    pub(crate) fn reject(&mut self, detail: String) {
+        if self.error.is_none() {
+            self.error = Some(detail);
+        }
+    }
+
+    pub(crate) fn build(self) -> Result<String, crate::models::ErrorResponse> {
+        if let Some(detail) = self.error {
+            return Err(crate::models::ErrorResponse {
+                error: crate::models::ErrorDetail {
+                    id:     "400".to_string(),
+                    name:   "bad_request".to_string(),
+                    detail
+                }
+            });
+        }
+
+        if self.parameters.is_empty() {
+            Ok(self.path)
+        } else {
+            Ok(format!("{}?{}", self.path, self.parameters.join("&")))
+        }
+    }
+}
+
+enum Transport {
+    Live,
+    Recording(Mutex<Cassette>),
+    Replaying(Mutex<Replay>)
+}
+
 pub struct Client {
     api_token: String,
-    client: reqwest::Client
+    base_url: String,
+    client: reqwest::Client,
+    transport: Transport
 }
 
 impl Client {
     pub fn new(api_token: String) -> Client {
+        Client::with_base_url(api_token, API_BASE_URL.to_string())
+    }
+
+    pub fn with_base_url(api_token: String, base_url: String) -> Client {
         let client: reqwest::Client = reqwest::Client::new();
-        Client { api_token, client }
+        Client { api_token, base_url, client, transport: Transport::Live }
     }
 
-    async fn get(&self, endpoint: &str) -> reqwest::Response {
-        self.client
-            .get(format!("{}{}", API_BASE_URL, endpoint))
-            .header("Authorization", format!("Bearer {}", self.api_token))
-            .send()
-            .await
-            .unwrap()
+    // Serves every request from `cassette` instead of the network.
+    pub fn replay(cassette: Cassette) -> Client {
+        let mut client: Client = Client::new(String::new());
+        client.transport = Transport::Replaying(Mutex::new(Replay::new(cassette)));
+        client
     }
 
-    // User
-    pub async fn get_user(&self) -> Either<crate::models::UserResponse, crate::models::ErrorResponse> {
-        let raw_response: reqwest::Response = self.get("/user").await;
+    // Captures every request/response pair from now on, with the API token redacted.
+    pub fn record(mut self) -> Client {
+        self.transport = Transport::Recording(Mutex::new(Cassette::default()));
+        self
+    }
 
-        if raw_response.status().is_success() {
-            if let Ok(user_response) = raw_response.json::<crate::models::UserResponse>().await {
-                return Left(user_response);
-            } else {
-                panic!("Returned data model is invalid for `get_user`.")
-            }
-        } else {
-            if let Ok(error_response) = raw_response.json::<crate::models::ErrorResponse>().await {
-                return Right(error_response);
-            } else {
-                panic!("Returned error model is invalid for `get_user`.")
-            }
+    pub fn cassette(&self) -> Option<Cassette> {
+        match &self.transport {
+            Transport::Recording(cassette) => Some(cassette.lock().unwrap().clone()),
+            _ => None
         }
     }
 
-    // Budgets
-    pub async fn get_budgets(&self, include_accounts: bool) -> Either<crate::models::BudgetSummaryResponse, crate::models::ErrorResponse> {
-        let raw_response: reqwest::Response = self.get(if include_accounts { "/budgets?include_accounts=true" } else { "/budgets" }).await;
+    async fn send(&self, method: reqwest::Method, endpoint: &str, body: Option<String>) -> reqwest::Response {
+        if let Transport::Replaying(replay) = &self.transport {
+            return replay.lock().unwrap().next(method.as_str(), endpoint);
+        }
 
-        if raw_response.status().is_success() {
-            match raw_response.json::<crate::models::BudgetSummaryResponse>().await {
-                Ok(budget_response) => return Left(budget_response),
-                Err(error) => panic!("Returned data model is invalid for `get_budgets`. Error: {:?}", error)
-            }
-        } else {
-            match raw_response.json::<crate::models::ErrorResponse>().await {
-                Ok(error_response) => return Right(error_response),
-                Err(error) => panic!("Returned error model is invalid for `get_budgets`. Error: {:?}", error)
-            }
+        let mut request: reqwest::RequestBuilder = self.client
+            .request(method.clone(), format!("{}{}", self.base_url, endpoint))
+            .header("Authorization", format!("Bearer {}", self.api_token));
+
+        if let Some(some_body) = &body {
+            request = request
+                .header("Content-Type", "application/json")
+                .body(some_body.clone());
+        }
+
+        let raw_response: reqwest::Response = request.send().await.unwrap();
+
+        match &self.transport {
+            Transport::Recording(cassette) => {
+                let status: reqwest::StatusCode = raw_response.status();
+                let response_body: String = raw_response.text().await.unwrap();
+                cassette.lock().unwrap().record(method.as_str(), endpoint, body.as_deref(), status.as_u16(), &response_body, &self.api_token);
+
+                let response: http::Response<String> = http::Response::builder()
+                    .status(status)
+                    .header("Content-Type", "application/json; charset=utf-8")
+                    .body(response_body)
+                    .unwrap();
+                reqwest::Response::from(response)
+            },
+            _ => raw_response
         }
     }
 
-    pub async fn get_budget(&self, budget_id: &str, last_knowledge_of_server: Option<i64>) -> Either<crate::models::BudgetDetailResponse, crate::models::ErrorResponse> {
-        let endpoint = match last_knowledge_of_server {
-            Some(last_knowledge) => format!("/budgets/{}?last_knowledge_of_server={}", budget_id, last_knowledge),
-            None => format!("/budgets/{}", budget_id)
+    async fn fetch<T: serde::de::DeserializeOwned>(&self, endpoint: Endpoint, method_name: &str) -> Either<T, crate::models::ErrorResponse> {
+        self.request::<T, ()>(reqwest::Method::GET, endpoint, None, method_name).await
+    }
+
+    // Invalid path segments or parameters are rejected locally with a `400 bad_request` error,
+    // mirroring the error model the API itself would return.
+    #[allow(clippy::needless_return)]
+    async fn request<T: serde::de::DeserializeOwned, B: serde::Serialize>(&self, method: reqwest::Method, endpoint: Endpoint, body: Option<&B>, method_name: &str) -> Either<T, crate::models::ErrorResponse> {
+        let endpoint: String = match endpoint.build() {
+            Ok(endpoint) => endpoint,
+            Err(error_response) => return Right(error_response)
         };
 
-        let raw_response: reqwest::Response = self.get(endpoint.as_str()).await;
+        let body: Option<String> = body.map(|some_body| {
+            serde_json::to_string(some_body).unwrap_or_else(|error| panic!("Request data model is invalid for `{}`. Error: {:?}", method_name, error))
+        });
+
+        let raw_response: reqwest::Response = self.send(method, endpoint.as_str(), body).await;
 
         if raw_response.status().is_success() {
-            match raw_response.json::<crate::models::BudgetDetailResponse>().await {
-                Ok(budget_response) => return Left(budget_response),
-                Err(error) => panic!("Returned data model is invalid for `get_budget`. Error: {:?}", error)
+            match raw_response.json::<T>().await {
+                Ok(response) => return Left(response),
+                Err(error) => panic!("Returned data model is invalid for `{}`. Error: {:?}", method_name, error)
             }
         } else {
             match raw_response.json::<crate::models::ErrorResponse>().await {
                 Ok(error_response) => return Right(error_response),
-                Err(error) => panic!("Returned error model is invalid for `get_budget`. Error: {:?}", error)
+                Err(error) => panic!("Returned error model is invalid for `{}`. Error: {:?}", method_name, error)
             }
         }
     }
 
-    pub async fn get_budget_settings(&self, budget_id: &str) -> Either<crate::models::BudgetSettingsResponse, crate::models::ErrorResponse> {
-        let endpoint = format!("/budgets/{}/settings", budget_id);
+    // User
+    pub async fn get_user(&self) -> Either<crate::models::UserResponse, crate::models::ErrorResponse> {
+        self.fetch(Endpoint::new("user"), "get_user").await
+    }
 
-        let raw_response: reqwest::Response = self.get(endpoint.as_str()).await;
+    // Budgets
+    pub async fn get_budgets(&self, include_accounts: bool) -> Either<crate::models::BudgetSummaryResponse, crate::models::ErrorResponse> {
+        let endpoint: Endpoint = Endpoint::new("budgets")
+            .parameter("include_accounts", if include_accounts { Some(true) } else { None });
 
-        if raw_response.status().is_success() {
-            match raw_response.json::<crate::models::BudgetSettingsResponse>().await {
-                Ok(settings_response) => return Left(settings_response),
-                Err(error) => panic!("Returned data model is invalid for `get_budget_settings`. Error: {:?}", error)
-            }
-        } else {
-            match raw_response.json::<crate::models::ErrorResponse>().await {
-                Ok(error_response) => return Right(error_response),
-                Err(error) => panic!("Returned error model is invalid for `get_budget_settings`. Error: {:?}", error)
-            }
-        }
+        self.fetch(endpoint, "get_budgets").await
+    }
+
+    pub async fn get_budget(&self, budget_id: &str, last_knowledge_of_server: Option<i64>) -> Either<crate::models::BudgetDetailResponse, crate::models::ErrorResponse> {
+        let endpoint: Endpoint = Endpoint::new("budgets")
+            .id("budget id", budget_id)
+            .parameter("last_knowledge_of_server", last_knowledge_of_server);
+
+        self.fetch(endpoint, "get_budget").await
+    }
+
+    pub async fn get_budget_settings(&self, budget_id: &str) -> Either<crate::models::BudgetSettingsResponse, crate::models::ErrorResponse> {
+        let endpoint: Endpoint = Endpoint::new("budgets")
+            .id("budget id", budget_id)
+            .segment("settings");
+
+        self.fetch(endpoint, "get_budget_settings").await
     }
 
     // Accounts
     pub async fn get_account_list(&self, budget_id: &str, last_knowledge_of_server: Option<i64>) -> Either<crate::models::AccountsResponse, crate::models::ErrorResponse> {
-        let endpoint = match last_knowledge_of_server {
-            Some(last_knowledge) => format!("/budgets/{}/accounts?last_knowledge_of_server={}", budget_id, last_knowledge),
-            None => format!("/budgets/{}/accounts", budget_id)
-        };
-
-        let raw_response: reqwest::Response = self.get(endpoint.as_str()).await;
+        let endpoint: Endpoint = Endpoint::new("budgets")
+            .id("budget id", budget_id)
+            .segment("accounts")
+            .parameter("last_knowledge_of_server", last_knowledge_of_server);
 
-        if raw_response.status().is_success() {
-            match raw_response.json::<crate::models::AccountsResponse>().await {
-                Ok(accounts_response) => return Left(accounts_response),
-                Err(error) => panic!("Returned data model is invalid for `get_account_list`. Error: {:?}", error)
-            }
-        } else {
-            match raw_response.json::<crate::models::ErrorResponse>().await {
-                Ok(error_response) => return Right(error_response),
-                Err(error) => panic!("Returned error model is invalid for `get_account_list`. Error: {:?}", error)
-            }
-        }
+        self.fetch(endpoint, "get_account_list").await
     }
 
     pub async fn get_account(&self, budget_id: &str, account_id: &str) -> Either<crate::models::AccountResponse, crate::models::ErrorResponse> {
-        let endpoint = format!("/budgets/{}/accounts/{}", budget_id, account_id);
-
-        let raw_response: reqwest::Response = self.get(endpoint.as_str()).await;
+        let endpoint: Endpoint = Endpoint::new("budgets")
+            .id("budget id", budget_id)
+            .segment("accounts")
+            .id("account id", account_id);
 
-        if raw_response.status().is_success() {
-            match raw_response.json::<crate::models::AccountResponse>().await {
-                Ok(account_response) => return Left(account_response),
-                Err(error) => panic!("Returned data model is invalid for `get_account`. Error: {:?}", error)
-            }
-        } else {
-            match raw_response.json::<crate::models::ErrorResponse>().await {
-                Ok(error_response) => return Right(error_response),
-                Err(error) => panic!("Returned error model is invalid for `get_account`. Error: {:?}", error)
-            }
-        }
+        self.fetch(endpoint, "get_account").await
     }
 
     // Categories
     pub async fn get_category_list(&self, budget_id: &str, last_knowledge_of_server: Option<i64>) -> Either<crate::models::CategoriesResponse, crate::models::ErrorResponse> {
-        let endpoint = match last_knowledge_of_server {
-            Some(last_knowledge) => format!("/budgets/{}/categories?last_knowledge_of_server={}", budget_id, last_knowledge),
-            None => format!("/budgets/{}/categories", budget_id)
-        };
-
-        let raw_response: reqwest::Response = self.get(endpoint.as_str()).await;
+        let endpoint: Endpoint = Endpoint::new("budgets")
+            .id("budget id", budget_id)
+            .segment("categories")
+            .parameter("last_knowledge_of_server", last_knowledge_of_server);
 
-        if raw_response.status().is_success() {
-            match raw_response.json::<crate::models::CategoriesResponse>().await {
-                Ok(categories_response) => return Left(categories_response),
-                Err(error) => panic!("Returned data model is invalid for `get_category_list`. Error: {:?}", error)
-            }
-        } else {
-            match raw_response.json::<crate::models::ErrorResponse>().await {
-                Ok(error_response) => return Right(error_response),
-                Err(error) => panic!("Returned error model is invalid for `get_category_list`. Error: {:?}", error)
-            }
-        }
+        self.fetch(endpoint, "get_category_list").await
     }
 
     pub async fn get_category(&self, budget_id: &str, category_id: &str) -> Either<crate::models::CategoryResponse, crate::models::ErrorResponse> {
-        let endpoint = format!("/budgets/{}/categories/{}", budget_id, category_id);
-
-        let raw_response: reqwest::Response = self.get(endpoint.as_str()).await;
+        let endpoint: Endpoint = Endpoint::new("budgets")
+            .id("budget id", budget_id)
+            .segment("categories")
+            .id("category id", category_id);
 
-        if raw_response.status().is_success() {
-            match raw_response.json::<crate::models::CategoryResponse>().await {
-                Ok(category_response) => return Left(category_response),
-                Err(error) => panic!("Returned data model is invalid for `get_category`. Error: {:?}", error)
-            }
-        } else {
-            match raw_response.json::<crate::models::ErrorResponse>().await {
-                Ok(error_response) => return Right(error_response),
-                Err(error) => panic!("Returned error model is invalid for `get_category`. Error: {:?}", error)
-            }
-        }
+        self.fetch(endpoint, "get_category").await
     }
 
     pub async fn get_category_for_month(&self, budget_id: &str, date: &str, category_id: &str) -> Either<crate::models::CategoryResponse, crate::models::ErrorResponse> {
-        let endpoint = format!("/budgets/{}/months/{}/categories/{}", budget_id, date, category_id);
-
-        let raw_response: reqwest::Response = self.get(endpoint.as_str()).await;
-
-        if raw_response.status().is_success() {
-            match raw_response.json::<crate::models::CategoryResponse>().await {
-                Ok(category_response) => return Left(category_response),
-                Err(error) => panic!("Returned data model is invalid for `get_category_for_month`. Error: {:?}", error)
-            }
-        } else {
-            match raw_response.json::<crate::models::ErrorResponse>().await {
-                Ok(error_response) => return Right(error_response),
-                Err(error) => panic!("Returned error model is invalid for `get_category_for_month`. Error: {:?}", error)
-            }
-        }
+        let endpoint: Endpoint = Endpoint::new("budgets")
+            .id("budget id", budget_id)
+            .segment("months")
+            .month(date)
+            .segment("categories")
+            .id("category id", category_id);
+
+        self.fetch(endpoint, "get_category_for_month").await
     }
 
     // Payees
     pub async fn get_payee_list(&self, budget_id: &str, last_knowledge_of_server: Option<i64>) -> Either<crate::models::PayeesResponse, crate::models::ErrorResponse> {
-        let endpoint = match last_knowledge_of_server {
-            Some(last_knowledge) => format!("/budgets/{}/payees?last_knowledge_of_server={}", budget_id, last_knowledge),
-            None => format!("/budgets/{}/payees", budget_id)
-        };
-
-        let raw_response: reqwest::Response = self.get(endpoint.as_str()).await;
+        let endpoint: Endpoint = Endpoint::new("budgets")
+            .id("budget id", budget_id)
+            .segment("payees")
+            .parameter("last_knowledge_of_server", last_knowledge_of_server);
 
-        if raw_response.status().is_success() {
-            match raw_response.json::<crate::models::PayeesResponse>().await {
-                Ok(payees_response) => return Left(payees_response),
-                Err(error) => panic!("Returned data model is invalid for `get_payee_list`. Error: {:?}", error)
-            }
-        } else {
-            match raw_response.json::<crate::models::ErrorResponse>().await {
-                Ok(error_response) => return Right(error_response),
-                Err(error) => panic!("Returned error model is invalid for `get_payee_list`. Error: {:?}", error)
-            }
-        }
+        self.fetch(endpoint, "get_payee_list").await
     }
 
     pub async fn get_payee(&self, budget_id: &str, payee_id: &str) -> Either<crate::models::PayeeResponse, crate::models::ErrorResponse> {
-        let endpoint = format!("/budgets/{}/payees/{}", budget_id, payee_id);
-
-        let raw_response: reqwest::Response = self.get(endpoint.as_str()).await;
+        let endpoint: Endpoint = Endpoint::new("budgets")
+            .id("budget id", budget_id)
+            .segment("payees")
+            .id("payee id", payee_id);
 
-        if raw_response.status().is_success() {
-            match raw_response.json::<crate::models::PayeeResponse>().await {
-                Ok(payee_response) => return Left(payee_response),
-                Err(error) => panic!("Returned data model is invalid for `get_payee`. Error: {:?}", error)
-            }
-        } else {
-            match raw_response.json::<crate::models::ErrorResponse>().await {
-                Ok(error_response) => return Right(error_response),
-                Err(error) => panic!("Returned error model is invalid for `get_payee`. Error: {:?}", error)
-            }
-        }
+        self.fetch(endpoint, "get_payee").await
     }
 
     // Payee Locations
     pub async fn get_payee_location_list(&self, budget_id: &str) -> Either<crate::models::PayeeLocationsResponse, crate::models::ErrorResponse> {
-        let endpoint = format!("/budgets/{}/payee_locations", budget_id);
-
-        let raw_response: reqwest::Response = self.get(endpoint.as_str()).await;
+        let endpoint: Endpoint = Endpoint::new("budgets")
+            .id("budget id", budget_id)
+            .segment("payee_locations");
 
-        if raw_response.status().is_success() {
-            match raw_response.json::<crate::models::PayeeLocationsResponse>().await {
-                Ok(payee_location_response) => return Left(payee_location_response),
-                Err(error) => panic!("Returned data model is invalid for `get_payee_location_list`. Error: {:?}", error)
-            }
-        } else {
-            match raw_response.json::<crate::models::ErrorResponse>().await {
-                Ok(error_response) => return Right(error_response),
-                Err(error) => panic!("Returned error model is invalid for `get_payee_location_list`. Error: {:?}", error)
-            }
-        }
+        self.fetch(endpoint, "get_payee_location_list").await
     }
 
     pub async fn get_payee_location(&self, budget_id: &str, payee_location_id: &str) -> Either<crate::models::PayeeLocationResponse, crate::models::ErrorResponse> {
-        let endpoint = format!("/budgets/{}/payee_locations/{}", budget_id, payee_location_id);
+        let endpoint: Endpoint = Endpoint::new("budgets")
+            .id("budget id", budget_id)
+            .segment("payee_locations")
+            .id("payee location id", payee_location_id);
 
-        let raw_response: reqwest::Response = self.get(endpoint.as_str()).await;
-
-        if raw_response.status().is_success() {
-            match raw_response.json::<crate::models::PayeeLocationResponse>().await {
-                Ok(payee_location_response) => return Left(payee_location_response),
-                Err(error) => panic!("Returned data model is invalid for `get_payee_location`. Error: {:?}", error)
-            }
-        } else {
-            match raw_response.json::<crate::models::ErrorResponse>().await {
-                Ok(error_response) => return Right(error_response),
-                Err(error) => panic!("Returned error model is invalid for `get_payee_location`. Error: {:?}", error)
-            }
-        }
+        self.fetch(endpoint, "get_payee_location").await
     }
 
     pub async fn get_payee_locations_for_payee(&self, budget_id: &str, payee_id: &str) -> Either<crate::models::PayeeLocationsResponse, crate::models::ErrorResponse> {
-        let endpoint = format!("/budgets/{}/payees/{}/payee_locations", budget_id, payee_id);
-
-        let raw_response: reqwest::Response = self.get(endpoint.as_str()).await;
+        let endpoint: Endpoint = Endpoint::new("budgets")
+            .id("budget id", budget_id)
+            .segment("payees")
+            .id("payee id", payee_id)
+            .segment("payee_locations");
 
-        if raw_response.status().is_success() {
-            match raw_response.json::<crate::models::PayeeLocationsResponse>().await {
-                Ok(payee_location_response) => return Left(payee_location_response),
-                Err(error) => panic!("Returned data model is invalid for `get_payee_location_for_payee`. Error: {:?}", error)
-            }
-        } else {
-            match raw_response.json::<crate::models::ErrorResponse>().await {
-                Ok(error_response) => return Right(error_response),
-                Err(error) => panic!("Returned error model is invalid for `get_payee_location_for_payee`. Error: {:?}", error)
-            }
-        }
+        self.fetch(endpoint, "get_payee_locations_for_payee").await
     }
 
 
     // Months
     pub async fn get_month_list(&self, budget_id: &str, last_knowledge_of_server: Option<i64>) -> Either<crate::models::MonthSummariesResponse, crate::models::ErrorResponse> {
-        let endpoint = match last_knowledge_of_server {
-            Some(last_knowledge) => format!("/budgets/{}/months?last_knowledge_of_server={}", budget_id, last_knowledge),
-            None => format!("/budgets/{}/months", budget_id)
-        };
-
-        let raw_response: reqwest::Response = self.get(endpoint.as_str()).await;
+        let endpoint: Endpoint = Endpoint::new("budgets")
+            .id("budget id", budget_id)
+            .segment("months")
+            .parameter("last_knowledge_of_server", last_knowledge_of_server);
 
-        if raw_response.status().is_success() {
-            match raw_response.json::<crate::models::MonthSummariesResponse>().await {
-                Ok(months_response) => return Left(months_response),
-                Err(error) => panic!("Returned data model is invalid for `get_month_list`. Error: {:?}", error)
-            }
-        } else {
-            match raw_response.json::<crate::models::ErrorResponse>().await {
-                Ok(error_response) => return Right(error_response),
-                Err(error) => panic!("Returned error model is invalid for `get_month_list`. Error: {:?}", error)
-            }
-        }
+        self.fetch(endpoint, "get_month_list").await
     }
 
     pub async fn get_month(&self, budget_id: &str, date: &str) -> Either<crate::models::MonthDetailResponse, crate::models::ErrorResponse> {
-        let endpoint = format!("/budgets/{}/months/{}", budget_id, date);
-
-        let raw_response: reqwest::Response = self.get(endpoint.as_str()).await;
+        let endpoint: Endpoint = Endpoint::new("budgets")
+            .id("budget id", budget_id)
+            .segment("months")
+            .month(date);
 
-        if raw_response.status().is_success() {
-            match raw_response.json::<crate::models::MonthDetailResponse>().await {
-                Ok(month_response) => return Left(month_response),
-                Err(error) => panic!("Returned data model is invalid for `get_month`. Error: {:?}", error)
-            }
-        } else {
-            match raw_response.json::<crate::models::ErrorResponse>().await {
-                Ok(error_response) => return Right(error_response),
-                Err(error) => panic!("Returned error model is invalid for `get_month`. Error: {:?}", error)
-            }
-        }
+        self.fetch(endpoint, "get_month").await
     }
 
     // Transactions
-    pub async fn get_transaction_list(&self, budget_id: &str, since_date: Option<&str>, transaction_type: Option<&str>, last_knowledge_of_server: Option<i64>) -> Either<crate::models::TransactionsResponse, crate::models::ErrorResponse> {
-        let mut parameters: Vec<String> = vec![];
-
-        if let Some(some_since_date) = since_date {
-            parameters.push(format!("since_date={}", some_since_date));
+    // Payee scoped queries return hybrid rows, so they go through `get_hybrid_transactions`.
+    pub async fn get_transactions(&self, query: &TransactionQuery) -> Either<crate::models::TransactionsResponse, crate::models::ErrorResponse> {
+        let mut endpoint: Endpoint = query.endpoint();
+        if let TransactionScope::Payee(_) = query.scope {
+            endpoint.reject("Payee scoped queries return hybrid transactions, use `get_hybrid_transactions`.".to_string());
         }
 
-        if let Some(some_transaction_type) = transaction_type {
-            parameters.push(format!("type={}", some_transaction_type));
-        }
+        self.fetch(endpoint, "get_transactions").await
+    }
 
-        if let Some(some_last_knowledge_of_server) = last_knowledge_of_server {
-            parameters.push(format!("last_knowledge_of_server={}", some_last_knowledge_of_server));
+    pub async fn get_hybrid_transactions(&self, query: &TransactionQuery) -> Either<crate::models::HybridTransactionsResponse, crate::models::ErrorResponse> {
+        let mut endpoint: Endpoint = query.endpoint();
+        if !matches!(query.scope, TransactionScope::Payee(_)) {
+            endpoint.reject("Only payee scoped queries return hybrid transactions, use `get_transactions`.".to_string());
         }
 
-        let mut endpoint: String = format!("/budgets/{}/transactions", budget_id);
-        if parameters.len() > 0 {
-            endpoint = format!("/budgets/{}/transactions?{}", budget_id, parameters.join("&"));
-        }
+        self.fetch(endpoint, "get_hybrid_transactions").await
+    }
 
-        let raw_response: reqwest::Response = self.get(endpoint.as_str()).await;
+    pub async fn get_transaction_list(&self, budget_id: &str, since_date: Option<&str>, transaction_type: Option<&str>, last_knowledge_of_server: Option<i64>) -> Either<crate::models::TransactionsResponse, crate::models::ErrorResponse> {
+        let endpoint: Endpoint = list_endpoint(TransactionQuery::new(budget_id), since_date, transaction_type, last_knowledge_of_server);
 
-        if raw_response.status().is_success() {
-            match raw_response.json::<crate::models::TransactionsResponse>().await {
-                Ok(transactions_response) => return Left(transactions_response),
-                Err(error) => panic!("Returned data model is invalid for `get_transaction_list`. Error: {:?}", error)
-            }
-        } else {
-            match raw_response.json::<crate::models::ErrorResponse>().await {
-                Ok(error_response) => return Right(error_response),
-                Err(error) => panic!("Returned error model is invalid for `get_transaction_list`. Error: {:?}", error)
-            }
-        }
+        self.fetch(endpoint, "get_transaction_list").await
     }
 
-    pub async fn get_transaction(&self, budget_id: &str, transaction_id: &str) -> Either<crate::models::TransactionResponse, crate::models::ErrorResponse> {
-        let endpoint = format!("/budgets/{}/transactions/{}", budget_id, transaction_id);
+    pub async fn create_transactions(&self, budget_id: &str, transactions: &crate::models::PostTransactionsWrapper) -> Either<crate::models::SaveTransactionsResponse, crate::models::ErrorResponse> {
+        let endpoint: Endpoint = Endpoint::new("budgets")
+            .id("budget id", budget_id)
+            .segment("transactions");
 
-        let raw_response: reqwest::Response = self.get(endpoint.as_str()).await;
-
-        if raw_response.status().is_success() {
-            match raw_response.json::<crate::models::TransactionResponse>().await {
-                Ok(transaction_response) => return Left(transaction_response),
-                Err(error) => panic!("Returned data model is invalid for `get_transaction`. Error: {:?}", error)
-            }
-        } else {
-            match raw_response.json::<crate::models::ErrorResponse>().await {
-                Ok(error_response) => return Right(error_response),
-                Err(error) => panic!("Returned error model is invalid for `get_transaction`. Error: {:?}", error)
-            }
-        }
+        self.request(reqwest::Method::POST, endpoint, Some(transactions), "create_transactions").await
     }
 
-    pub async fn get_transaction_list_for_account(&self, budget_id: &str, account_id: &str, since_date: Option<&str>, transaction_type: Option<&str>, last_knowledge_of_server: Option<i64>) -> Either<crate::models::TransactionsResponse, crate::models::ErrorResponse> {
-        let mut parameters: Vec<String> = vec![];
-
-        if let Some(some_since_date) = since_date {
-            parameters.push(format!("since_date={}", some_since_date));
-        }
+    pub async fn update_transactions(&self, budget_id: &str, transactions: &crate::models::PatchTransactionsWrapper) -> Either<crate::models::SaveTransactionsResponse, crate::models::ErrorResponse> {
+        let endpoint: Endpoint = Endpoint::new("budgets")
+            .id("budget id", budget_id)
+            .segment("transactions");
 
-        if let Some(some_transaction_type) = transaction_type {
-            parameters.push(format!("type={}", some_transaction_type));
-        }
+        self.request(reqwest::Method::PATCH, endpoint, Some(transactions), "update_transactions").await
+    }
 
-        if let Some(some_last_knowledge_of_server) = last_knowledge_of_server {
-            parameters.push(format!("last_knowledge_of_server={}", some_last_knowledge_of_server));
-        }
+    pub async fn get_transaction(&self, budget_id: &str, transaction_id: &str) -> Either<crate::models::TransactionResponse, crate::models::ErrorResponse> {
+        let endpoint: Endpoint = Endpoint::new("budgets")
+            .id("budget id", budget_id)
+            .segment("transactions")
+            .id("transaction id", transaction_id);
 
-        let mut endpoint: String = format!("/budgets/{}/accounts/{}/transactions", budget_id, account_id);
-        if parameters.len() > 0 {
-            endpoint = format!("/budgets/{}/accounts/{}/transactions?{}", budget_id, account_id, parameters.join("&"));
-        }
+        self.fetch(endpoint, "get_transaction").await
+    }
 
-        let raw_response: reqwest::Response = self.get(endpoint.as_str()).await;
+    pub async fn get_transaction_list_for_account(&self, budget_id: &str, account_id: &str, since_date: Option<&str>, transaction_type: Option<&str>, last_knowledge_of_server: Option<i64>) -> Either<crate::models::TransactionsResponse, crate::models::ErrorResponse> {
+        let endpoint: Endpoint = list_endpoint(TransactionQuery::new(budget_id).account(account_id), since_date, transaction_type, last_knowledge_of_server);
 
-        if raw_response.status().is_success() {
-            match raw_response.json::<crate::models::TransactionsResponse>().await {
-                Ok(transactions_response) => return Left(transactions_response),
-                Err(error) => panic!("Returned data model is invalid for `get_transaction_list_for_account`. Error: {:?}", error)
-            }
-        } else {
-            match raw_response.json::<crate::models::ErrorResponse>().await {
-                Ok(error_response) => return Right(error_response),
-                Err(error) => panic!("Returned error model is invalid for `get_transaction_list_for_account`. Error: {:?}", error)
-            }
-        }
+        self.fetch(endpoint, "get_transaction_list_for_account").await
     }
 
     pub async fn get_transaction_list_for_category(&self, budget_id: &str, category_id: &str, since_date: Option<&str>, transaction_type: Option<&str>, last_knowledge_of_server: Option<i64>) -> Either<crate::models::TransactionsResponse, crate::models::ErrorResponse> {
-        let mut parameters: Vec<String> = vec![];
+        let endpoint: Endpoint = list_endpoint(TransactionQuery::new(budget_id).category(category_id), since_date, transaction_type, last_knowledge_of_server);
 
-        if let Some(some_since_date) = since_date {
-            parameters.push(format!("since_date={}", some_since_date));
-        }
-
-        if let Some(some_transaction_type) = transaction_type {
-            parameters.push(format!("type={}", some_transaction_type));
-        }
-
-        if let Some(some_last_knowledge_of_server) = last_knowledge_of_server {
-            parameters.push(format!("last_knowledge_of_server={}", some_last_knowledge_of_server));
-        }
-
-        let mut endpoint: String = format!("/budgets/{}/categories/{}/transactions", budget_id, category_id);
-        if parameters.len() > 0 {
-            endpoint = format!("/budgets/{}/categories/{}/transactions?{}", budget_id, category_id, parameters.join("&"));
-        }
-
-        let raw_response: reqwest::Response = self.get(endpoint.as_str()).await;
-
-        if raw_response.status().is_success() {
-            match raw_response.json::<crate::models::TransactionsResponse>().await {
-                Ok(transactions_response) => return Left(transactions_response),
-                Err(error) => panic!("Returned data model is invalid for `get_transaction_list_for_category`. Error: {:?}", error)
-            }
-        } else {
-            match raw_response.json::<crate::models::ErrorResponse>().await {
-                Ok(error_response) => return Right(error_response),
-                Err(error) => panic!("Returned error model is invalid for `get_transaction_list_for_category`. Error: {:?}", error)
-            }
-        }
+        self.fetch(endpoint, "get_transaction_list_for_category").await
     }
 
     pub async fn get_transaction_list_for_payee(&self, budget_id: &str, payee_id: &str, since_date: Option<&str>, transaction_type: Option<&str>, last_knowledge_of_server: Option<i64>) -> Either<crate::models::HybridTransactionsResponse, crate::models::ErrorResponse> {
-        let mut parameters: Vec<String> = vec![];
-
-        if let Some(some_since_date) = since_date {
-            parameters.push(format!("since_date={}", some_since_date));
-        }
-
-        if let Some(some_transaction_type) = transaction_type {
-            parameters.push(format!("type={}", some_transaction_type));
-        }
-
-        if let Some(some_last_knowledge_of_server) = last_knowledge_of_server {
-            parameters.push(format!("last_knowledge_of_server={}", some_last_knowledge_of_server));
-        }
+        let endpoint: Endpoint = list_endpoint(TransactionQuery::new(budget_id).payee(payee_id), since_date, transaction_type, last_knowledge_of_server);
 
-        let mut endpoint: String = format!("/budgets/{}/payees/{}/transactions", budget_id, payee_id);
-        if parameters.len() > 0 {
-            endpoint = format!("/budgets/{}/payees/{}/transactions?{}", budget_id, payee_id, parameters.join("&"));
-        }
-
-        let raw_response: reqwest::Response = self.get(endpoint.as_str()).await;
-
-        if raw_response.status().is_success() {
-            match raw_response.json::<crate::models::HybridTransactionsResponse>().await {
-                Ok(transactions_response) => return Left(transactions_response),
-                Err(error) => panic!("Returned data model is invalid for `get_transaction_list_for_payee`. Error: {:?}", error)
-            }
-        } else {
-            match raw_response.json::<crate::models::ErrorResponse>().await {
-                Ok(error_response) => return Right(error_response),
-                Err(error) => panic!("Returned error model is invalid for `get_transaction_list_for_payee`. Error: {:?}", error)
-            }
-        }
+        self.fetch(endpoint, "get_transaction_list_for_payee").await
     }
 
     // Scheduled Transactions
     pub async fn get_scheduled_transaction_list(&self, budget_id: &str, last_knowledge_of_server: Option<i64>) -> Either<crate::models::ScheduledTransactionsResponse, crate::models::ErrorResponse> {
-        let endpoint = match last_knowledge_of_server {
-            Some(last_knowledge) => format!("/budgets/{}/scheduled_transactions?last_knowledge_of_server={}", budget_id, last_knowledge),
-            None => format!("/budgets/{}/scheduled_transactions", budget_id)
-        };
+        let endpoint: Endpoint = Endpoint::new("budgets")
+            .id("budget id", budget_id)
+            .segment("scheduled_transactions")
+            .parameter("last_knowledge_of_server", last_knowledge_of_server);
 
-        let raw_response: reqwest::Response = self.get(endpoint.as_str()).await;
-
-        if raw_response.status().is_success() {
-            match raw_response.json::<crate::models::ScheduledTransactionsResponse>().await {
-                Ok(st_response) => return Left(st_response),
-                Err(error) => panic!("Returned data model is invalid for `get_scheduled_transaction_list`. Error: {:?}", error)
-            }
-        } else {
-            match raw_response.json::<crate::models::ErrorResponse>().await {
-                Ok(error_response) => return Right(error_response),
-                Err(error) => panic!("Returned error model is invalid for `get_scheduled_transaction_list`. Error: {:?}", error)
-            }
-        }
+        self.fetch(endpoint, "get_scheduled_transaction_list").await
     }
 
     pub async fn get_scheduled_transaction(&self, budget_id: &str, scheduled_transaction_id: &str) -> Either<crate::models::ScheduledTransactionResponse, crate::models::ErrorResponse> {
-        let endpoint = format!("/budgets/{}/scheduled_transactions/{}", budget_id, scheduled_transaction_id);
+        let endpoint: Endpoint = Endpoint::new("budgets")
+            .id("budget id", budget_id)
+            .segment("scheduled_transactions")
+            .id("scheduled transaction id", scheduled_transaction_id);
 
-        let raw_response: reqwest::Response = self.get(endpoint.as_str()).await;
+        self.fetch(endpoint, "get_scheduled_transaction").await
+    }
+}
 
-        if raw_response.status().is_success() {
-            match raw_response.json::<crate::models::ScheduledTransactionResponse>().await {
-                Ok(st_response) => return Left(st_response),
-                Err(error) => panic!("Returned data model is invalid for `get_payee`. Error: {:?}", error)
-            }
-        } else {
-            match raw_response.json::<crate::models::ErrorResponse>().await {
-                Ok(error_response) => return Right(error_response),
-                Err(error) => panic!("Returned error model is invalid for `get_payee`. Error: {:?}", error)
-            }
+// Builds the positional list methods' endpoints through `TransactionQuery`, so both take the same
+// path. Values that don't parse are rejected like any other invalid input.
+fn list_endpoint(mut query: TransactionQuery, since_date: Option<&str>, transaction_type: Option<&str>, last_knowledge_of_server: Option<i64>) -> Endpoint {
+    let mut problems: Vec<String> = vec![];
+
+    if let Some(some_since_date) = since_date {
+        match NaiveDate::parse_from_str(some_since_date, "%Y-%m-%d") {
+            Ok(date) => query.since_date = Some(date),
+            Err(_) => problems.push(format!("`{}` is not a valid since_date, expected an ISO date.", some_since_date))
+        }
+    }
+
+    if let Some(some_transaction_type) = transaction_type {
+        match [TransactionFilter::Uncategorized, TransactionFilter::Unapproved].into_iter().find(|filter| filter.as_str() == some_transaction_type) {
+            Some(filter) => query.filter = Some(filter),
+            None => problems.push(format!("`{}` is not a valid type, expected `uncategorized` or `unapproved`.", some_transaction_type))
         }
     }
 
-    
-}
\ No newline at end of file
+    query.last_knowledge_of_server = last_knowledge_of_server;
+
+    let mut endpoint: Endpoint = query.endpoint();
+    for detail in problems {
+        endpoint.reject(detail);
+    }
+    endpoint
+}
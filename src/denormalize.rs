@@ -0,0 +1,98 @@
+use crate::index::BudgetIndex;
+use crate::models::{
+    BudgetDetail, ScheduledSubTransaction, ScheduledTransactionDetail, ScheduledTransactionSummary, SubTransaction, TransactionDetail,
+    TransactionSummary
+};
+
+// `get_budget` returns transactions and subtransactions as separate flat lists without names. These
+// rebuild the shapes `get_transaction_list` and `get_scheduled_transactions` return: account, payee
+// and category names filled in and subtransactions nested under their parent. Deleted entries are
+// left out, as the list endpoints leave them out.
+pub fn transaction_details(budget: &BudgetDetail) -> Vec<TransactionDetail> {
+    let index: BudgetIndex = BudgetIndex::new(budget);
+    budget.transactions.iter()
+        .filter(|transaction| !transaction.deleted)
+        .map(|transaction| transaction_detail(&index, transaction))
+        .collect()
+}
+
+pub fn scheduled_transaction_details(budget: &BudgetDetail) -> Vec<ScheduledTransactionDetail> {
+    let index: BudgetIndex = BudgetIndex::new(budget);
+    budget.scheduled_transactions.iter()
+        .filter(|scheduled| !scheduled.deleted)
+        .map(|scheduled| scheduled_transaction_detail(&index, scheduled))
+        .collect()
+}
+
+fn account_name(index: &BudgetIndex, account_id: &str) -> String {
+    index.account(account_id).map(|account| account.name.clone()).unwrap_or_default()
+}
+
+fn payee_name(index: &BudgetIndex, payee_id: Option<&str>) -> Option<String> {
+    payee_id.and_then(|payee_id| index.payee(payee_id)).map(|payee| payee.name.clone())
+}
+
+fn category_name(index: &BudgetIndex, category_id: Option<&str>) -> Option<String> {
+    category_id.and_then(|category_id| index.category(category_id)).map(|category| category.name.clone())
+}
+
+fn transaction_detail(index: &BudgetIndex, transaction: &TransactionSummary) -> TransactionDetail {
+    let subtransactions: Vec<SubTransaction> = index.subtransactions_of(&transaction.id).iter()
+        .filter(|subtransaction| !subtransaction.deleted)
+        .map(|subtransaction| SubTransaction {
+            payee_name:     subtransaction.payee_name.clone().or_else(|| payee_name(index, subtransaction.payee_id.as_deref())),
+            category_name:  subtransaction.category_name.clone().or_else(|| category_name(index, subtransaction.category_id.as_deref())),
+            ..(*subtransaction).clone()
+        })
+        .collect();
+
+    TransactionDetail {
+        id:                         transaction.id.clone(),
+        date:                       transaction.date.clone(),
+        amount:                     transaction.amount,
+        memo:                       transaction.memo.clone(),
+        cleared:                    transaction.cleared.clone(),
+        approved:                   transaction.approved,
+        flag_color:                 transaction.flag_color.clone(),
+        account_id:                 transaction.account_id.clone(),
+        payee_id:                   transaction.payee_id.clone(),
+        category_id:                transaction.category_id.clone(),
+        transfer_account_id:        transaction.transfer_account_id.clone(),
+        matched_transaction_id:     transaction.matched_transaction_id.clone(),
+        import_id:                  transaction.import_id.clone(),
+        import_payee_name:          transaction.import_payee_name.clone(),
+        import_payee_name_original: transaction.import_payee_name_original.clone(),
+        debt_transaction_type:      transaction.debt_transaction_type.clone(),
+        deleted:                    transaction.deleted,
+        account_name:               account_name(index, &transaction.account_id),
+        payee_name:                 payee_name(index, transaction.payee_id.as_deref()),
+        category_name:              category_name(index, transaction.category_id.as_deref()),
+        subtransactions
+    }
+}
+
+fn scheduled_transaction_detail(index: &BudgetIndex, scheduled: &ScheduledTransactionSummary) -> ScheduledTransactionDetail {
+    let subtransactions: Vec<ScheduledSubTransaction> = index.scheduled_subtransactions_of(&scheduled.id).iter()
+        .filter(|subtransaction| !subtransaction.deleted)
+        .map(|subtransaction| (*subtransaction).clone())
+        .collect();
+
+    ScheduledTransactionDetail {
+        id:                     scheduled.id.clone(),
+        date_first:             scheduled.date_first.clone(),
+        date_next:              scheduled.date_next.clone(),
+        frequency:              scheduled.frequency.clone(),
+        amount:                 scheduled.amount,
+        memo:                   scheduled.memo.clone(),
+        flag_color:             scheduled.flag_color.clone(),
+        account_id:             scheduled.account_id.clone(),
+        payee_id:               scheduled.payee_id.clone(),
+        category_id:            scheduled.category_id.clone(),
+        transfer_account_id:    scheduled.transfer_account_id.clone(),
+        deleted:                scheduled.deleted,
+        account_name:           account_name(index, &scheduled.account_id),
+        payee_name:             payee_name(index, scheduled.payee_id.as_deref()),
+        category_name:          category_name(index, scheduled.category_id.as_deref()),
+        subtransactions
+    }
+}
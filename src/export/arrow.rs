@@ -0,0 +1,226 @@
+use std::io::Write;
+use std::sync::Arc;
+
+use ::arrow::array::{ArrayRef, BooleanArray, Date32Array, Decimal128Array, StringArray};
+use ::arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use ::arrow::error::ArrowError;
+use ::arrow::record_batch::RecordBatch;
+use ::parquet::arrow::ArrowWriter;
+use ::parquet::errors::ParquetError;
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use crate::models::{Account, Category, MonthDetail, TransactionDetail};
+
+// Amounts are decimals with three places, so milliunits carry over exactly and precision 18
+// keeps them in Parquet INT64 columns.
+pub const AMOUNT_PRECISION: u8 = 18;
+pub const AMOUNT_SCALE: i8 = 3;
+
+fn amount_field(name: &str, nullable: bool) -> Field {
+    Field::new(name, DataType::Decimal128(AMOUNT_PRECISION, AMOUNT_SCALE), nullable)
+}
+
+fn amounts(values: impl IntoIterator<Item = Option<i64>>) -> Result<ArrayRef, ArrowError> {
+    let array: Decimal128Array = values.into_iter().map(|value| value.map(i128::from)).collect::<Decimal128Array>()
+        .with_precision_and_scale(AMOUNT_PRECISION, AMOUNT_SCALE)?;
+    Ok(Arc::new(array))
+}
+
+fn strings<'a>(values: impl IntoIterator<Item = Option<&'a str>>) -> ArrayRef {
+    Arc::new(values.into_iter().collect::<StringArray>())
+}
+
+fn booleans(values: impl IntoIterator<Item = bool>) -> ArrayRef {
+    Arc::new(values.into_iter().map(Some).collect::<BooleanArray>())
+}
+
+// Days since 1970-01-01, as Arrow's date32 counts them.
+fn date32(date: &str) -> Result<i32, ArrowError> {
+    let parsed: NaiveDate = NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_| ArrowError::ParseError(format!("`{}` is not an ISO date", date)))?;
+    Ok((parsed - NaiveDate::default()).num_days() as i32)
+}
+
+fn dates(values: &[&str]) -> Result<ArrayRef, ArrowError> {
+    let days: Vec<i32> = values.iter().map(|value| date32(value)).collect::<Result<Vec<i32>, ArrowError>>()?;
+    Ok(Arc::new(Date32Array::from(days)))
+}
+
+// The API's spelling of an enum value, e.g. `uncleared` or `creditCard`.
+fn api_name<T: Serialize>(value: &T) -> Option<String> {
+    serde_json::to_value(value).ok().and_then(|value| value.as_str().map(str::to_string))
+}
+
+pub fn transactions_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        Field::new("id", DataType::Utf8, false),
+        Field::new("parent_id", DataType::Utf8, true),
+        Field::new("date", DataType::Date32, false),
+        Field::new("account_id", DataType::Utf8, false),
+        Field::new("account_name", DataType::Utf8, false),
+        Field::new("payee_id", DataType::Utf8, true),
+        Field::new("payee_name", DataType::Utf8, true),
+        Field::new("category_id", DataType::Utf8, true),
+        Field::new("category_name", DataType::Utf8, true),
+        Field::new("transfer_account_id", DataType::Utf8, true),
+        Field::new("memo", DataType::Utf8, true),
+        amount_field("amount", false),
+        Field::new("cleared", DataType::Utf8, false),
+        Field::new("approved", DataType::Boolean, false),
+        Field::new("flag_color", DataType::Utf8, true),
+        Field::new("import_id", DataType::Utf8, true)
+    ]))
+}
+
+// One row per transaction, with splits replaced by a row per subtransaction that points back at
+// the split through `parent_id`, so amounts can be summed directly. Deleted transactions are left
+// out.
+pub fn transactions_batch(transactions: &[TransactionDetail]) -> Result<RecordBatch, ArrowError> {
+    struct Row<'r> {
+        transaction:            &'r TransactionDetail,
+        id:                     &'r str,
+        parent_id:              Option<&'r str>,
+        payee_id:               Option<&'r str>,
+        payee_name:             Option<&'r str>,
+        category_id:            Option<&'r str>,
+        category_name:          Option<&'r str>,
+        transfer_account_id:    Option<&'r str>,
+        memo:                   Option<&'r str>,
+        amount:                 i64
+    }
+
+    let mut rows: Vec<Row> = vec![];
+    for transaction in transactions.iter().filter(|transaction| !transaction.deleted) {
+        let parent: Row = Row {
+            transaction,
+            id:                     &transaction.id,
+            parent_id:              None,
+            payee_id:               transaction.payee_id.as_deref(),
+            payee_name:             transaction.payee_name.as_deref(),
+            category_id:            transaction.category_id.as_deref(),
+            category_name:          transaction.category_name.as_deref(),
+            transfer_account_id:    transaction.transfer_account_id.as_deref(),
+            memo:                   transaction.memo.as_deref(),
+            amount:                 transaction.amount
+        };
+
+        let subtransactions: Vec<_> = transaction.subtransactions.iter().filter(|subtransaction| !subtransaction.deleted).collect();
+        if subtransactions.is_empty() {
+            rows.push(parent);
+            continue;
+        }
+
+        rows.extend(subtransactions.into_iter().map(|subtransaction| Row {
+            id:                     &subtransaction.id,
+            parent_id:              Some(&transaction.id),
+            payee_id:               subtransaction.payee_id.as_deref().or(parent.payee_id),
+            payee_name:             subtransaction.payee_name.as_deref().or(parent.payee_name),
+            category_id:            subtransaction.category_id.as_deref(),
+            category_name:          subtransaction.category_name.as_deref(),
+            transfer_account_id:    subtransaction.transfer_account_id.as_deref(),
+            memo:                   subtransaction.memo.as_deref().or(parent.memo),
+            amount:                 subtransaction.amount,
+            ..parent
+        }));
+    }
+
+    let cleared: Vec<Option<String>> = rows.iter().map(|row| api_name(&row.transaction.cleared)).collect();
+    let flag_colors: Vec<Option<String>> = rows.iter().map(|row| row.transaction.flag_color.as_ref().and_then(api_name)).collect();
+    let date_values: Vec<&str> = rows.iter().map(|row| row.transaction.date.as_str()).collect();
+
+    RecordBatch::try_new(transactions_schema(), vec![
+        strings(rows.iter().map(|row| Some(row.id))),
+        strings(rows.iter().map(|row| row.parent_id)),
+        dates(&date_values)?,
+        strings(rows.iter().map(|row| Some(row.transaction.account_id.as_str()))),
+        strings(rows.iter().map(|row| Some(row.transaction.account_name.as_str()))),
+        strings(rows.iter().map(|row| row.payee_id)),
+        strings(rows.iter().map(|row| row.payee_name)),
+        strings(rows.iter().map(|row| row.category_id)),
+        strings(rows.iter().map(|row| row.category_name)),
+        strings(rows.iter().map(|row| row.transfer_account_id)),
+        strings(rows.iter().map(|row| row.memo)),
+        amounts(rows.iter().map(|row| Some(row.amount)))?,
+        strings(cleared.iter().map(Option::as_deref)),
+        booleans(rows.iter().map(|row| row.transaction.approved)),
+        strings(flag_colors.iter().map(Option::as_deref)),
+        strings(rows.iter().map(|row| row.transaction.import_id.as_deref()))
+    ])
+}
+
+pub fn month_categories_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        Field::new("month", DataType::Date32, false),
+        Field::new("category_id", DataType::Utf8, false),
+        Field::new("category_group_id", DataType::Utf8, false),
+        Field::new("category_group_name", DataType::Utf8, true),
+        Field::new("name", DataType::Utf8, false),
+        Field::new("hidden", DataType::Boolean, false),
+        amount_field("budgeted", false),
+        amount_field("activity", false),
+        amount_field("balance", false),
+        amount_field("goal_target", true)
+    ]))
+}
+
+// One row per category and month, from `MonthDetail.categories`. Deleted months and categories
+// are left out.
+pub fn month_categories_batch(months: &[MonthDetail]) -> Result<RecordBatch, ArrowError> {
+    let rows: Vec<(&MonthDetail, &Category)> = months.iter()
+        .filter(|month| !month.deleted)
+        .flat_map(|month| month.categories.iter().filter(|category| !category.deleted).map(move |category| (month, category)))
+        .collect();
+    let month_values: Vec<&str> = rows.iter().map(|(month, _)| month.month.as_str()).collect();
+
+    RecordBatch::try_new(month_categories_schema(), vec![
+        dates(&month_values)?,
+        strings(rows.iter().map(|(_, category)| Some(category.id.as_str()))),
+        strings(rows.iter().map(|(_, category)| Some(category.category_group_id.as_str()))),
+        strings(rows.iter().map(|(_, category)| category.category_group_name.as_deref())),
+        strings(rows.iter().map(|(_, category)| Some(category.name.as_str()))),
+        booleans(rows.iter().map(|(_, category)| category.hidden)),
+        amounts(rows.iter().map(|(_, category)| Some(category.budgeted)))?,
+        amounts(rows.iter().map(|(_, category)| Some(category.activity)))?,
+        amounts(rows.iter().map(|(_, category)| Some(category.balance)))?,
+        amounts(rows.iter().map(|(_, category)| category.goal_target))?
+    ])
+}
+
+pub fn accounts_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        Field::new("id", DataType::Utf8, false),
+        Field::new("name", DataType::Utf8, false),
+        Field::new("type", DataType::Utf8, false),
+        Field::new("on_budget", DataType::Boolean, false),
+        Field::new("closed", DataType::Boolean, false),
+        amount_field("balance", false),
+        amount_field("cleared_balance", false),
+        amount_field("uncleared_balance", false),
+        Field::new("note", DataType::Utf8, true)
+    ]))
+}
+
+// Deleted accounts are left out, closed ones kept.
+pub fn accounts_batch(accounts: &[Account]) -> Result<RecordBatch, ArrowError> {
+    let accounts: Vec<&Account> = accounts.iter().filter(|account| !account.deleted).collect();
+    let types: Vec<Option<String>> = accounts.iter().map(|account| api_name(&account.r#type)).collect();
+
+    RecordBatch::try_new(accounts_schema(), vec![
+        strings(accounts.iter().map(|account| Some(account.id.as_str()))),
+        strings(accounts.iter().map(|account| Some(account.name.as_str()))),
+        strings(types.iter().map(Option::as_deref)),
+        booleans(accounts.iter().map(|account| account.on_budget)),
+        booleans(accounts.iter().map(|account| account.closed)),
+        amounts(accounts.iter().map(|account| Some(account.balance)))?,
+        amounts(accounts.iter().map(|account| Some(account.cleared_balance)))?,
+        amounts(accounts.iter().map(|account| Some(account.uncleared_balance)))?,
+        strings(accounts.iter().map(|account| account.note.as_deref()))
+    ])
+}
+
+// Writes `batch` as a single Parquet file with the default writer properties.
+pub fn write_parquet<W: Write + Send>(batch: &RecordBatch, writer: W) -> Result<W, ParquetError> {
+    let mut arrow_writer: ArrowWriter<W> = ArrowWriter::try_new(writer, batch.schema(), None)?;
+    arrow_writer.write(batch)?;
+    arrow_writer.into_inner()
+}
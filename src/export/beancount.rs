@@ -0,0 +1,152 @@
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+
+use crate::export::{account_path, budget_entries, color_name, detail_entries, format_amount, live_accounts, payee_name, postings, Entry, Posting};
+use crate::models::{Account, BudgetDetail, ClearedStatus, TransactionDetail};
+
+// Writes Beancount ledgers that pass `bean-check`. Every account a posting touches is opened on
+// the date of its first transaction, closed YNAB accounts are closed after their last one, and
+// YNAB ids are kept as `ynab-id` metadata on transactions and split postings. Flags become tags
+// such as `#flag-red`, cleared and reconciled transactions get `*` and uncleared ones `!`.
+pub struct BeancountExporter<'a> {
+    budget: &'a BudgetDetail
+}
+
+// First and last posting date of an account, and the YNAB account behind it if there is one.
+struct AccountSpan<'a> {
+    opened:     &'a str,
+    last_used:  &'a str,
+    account:    Option<&'a Account>
+}
+
+impl<'a> BeancountExporter<'a> {
+    pub fn new(budget: &'a BudgetDetail) -> BeancountExporter<'a> {
+        BeancountExporter { budget }
+    }
+
+    // Also opens the budget's accounts that have no transactions, dated by the first transaction.
+    pub fn write_budget<W: Write>(&self, writer: W) -> io::Result<()> {
+        let entries: Vec<Entry> = budget_entries(self.budget);
+        self.write_ledger(&entries, true, writer)
+    }
+
+    pub fn write_transactions<W: Write>(&self, transactions: &[TransactionDetail], writer: W) -> io::Result<()> {
+        let entries: Vec<Entry> = detail_entries(self.budget, transactions);
+        self.write_ledger(&entries, false, writer)
+    }
+
+    fn write_ledger<W: Write>(&self, entries: &[Entry], all_accounts: bool, mut writer: W) -> io::Result<()> {
+        let first_date: &str = entries.first().map(|entry| entry.date).unwrap_or(&self.budget.first_month);
+        let mut spans: BTreeMap<String, AccountSpan> = BTreeMap::new();
+
+        for entry in entries {
+            for posting in postings(self.budget, entry) {
+                let account: Option<&Account> = posting.account_id.and_then(|account_id| self.budget.accounts.iter().find(|account| account.id == account_id));
+                let span: &mut AccountSpan = spans.entry(account_name(&posting.path))
+                    .or_insert(AccountSpan { opened: entry.date, last_used: entry.date, account });
+                span.last_used = entry.date;
+            }
+        }
+        if all_accounts {
+            for account in live_accounts(self.budget) {
+                spans.entry(account_name(&account_path(self.budget, &account.id)))
+                    .or_insert(AccountSpan { opened: first_date, last_used: first_date, account: Some(account) });
+            }
+        }
+
+        writeln!(writer, "option \"operating_currency\" \"{}\"\n", self.budget.currency_format.iso_code)?;
+
+        let mut opens: Vec<(&str, &String)> = spans.iter().map(|(name, span)| (span.opened, name)).collect();
+        opens.sort();
+        for (date, name) in opens {
+            writeln!(writer, "{} open {} {}", date, name, self.budget.currency_format.iso_code)?;
+            if let Some(account) = spans[name].account {
+                writeln!(writer, "  ynab-id: {}", quoted(&account.id))?;
+            }
+        }
+        writeln!(writer)?;
+
+        for entry in entries {
+            self.write_entry(entry, &mut writer)?;
+        }
+
+        let mut closes: Vec<(&str, &String)> = spans.iter()
+            .filter(|(_, span)| span.account.is_some_and(|account| account.closed))
+            .map(|(name, span)| (span.last_used, name))
+            .collect();
+        closes.sort();
+        for (date, name) in closes {
+            writeln!(writer, "{} close {}", date, name)?;
+        }
+
+        Ok(())
+    }
+
+    fn write_entry<W: Write>(&self, entry: &Entry, writer: &mut W) -> io::Result<()> {
+        let flag: &str = match entry.cleared {
+            ClearedStatus::Cleared | ClearedStatus::Reconciled => "*",
+            ClearedStatus::Uncleared => "!"
+        };
+        write!(writer, "{} {}", entry.date, flag)?;
+        if let Some(payee) = payee_name(self.budget, entry.payee_id) {
+            write!(writer, " {}", quoted(payee))?;
+        }
+        write!(writer, " {}", quoted(entry.memo.unwrap_or("")))?;
+        if let Some(flag_color) = entry.flag_color {
+            write!(writer, " #flag-{}", color_name(flag_color))?;
+        }
+        writeln!(writer)?;
+        writeln!(writer, "  ynab-id: {}", quoted(entry.id))?;
+
+        for posting in postings(self.budget, entry) {
+            self.write_posting(&posting, writer)?;
+        }
+        writeln!(writer)
+    }
+
+    fn write_posting<W: Write>(&self, posting: &Posting, writer: &mut W) -> io::Result<()> {
+        let currency = &self.budget.currency_format;
+        writeln!(
+            writer,
+            "  {:<40}  {} {}",
+            account_name(&posting.path),
+            format_amount(posting.amount, currency.decimal_digits),
+            currency.iso_code
+        )?;
+        if let Some(id) = posting.id {
+            writeln!(writer, "    ynab-id: {}", quoted(id))?;
+        }
+        if let Some(memo) = posting.memo {
+            writeln!(writer, "    memo: {}", quoted(memo))?;
+        }
+
+        Ok(())
+    }
+}
+
+// Beancount account components start with a capital letter or digit and otherwise hold only
+// letters, digits and dashes, so `Dining Out` becomes `Dining-Out`.
+fn account_name(path: &[String]) -> String {
+    path.iter().map(|part| {
+        let mut component: String = String::new();
+        for character in part.chars() {
+            if character.is_alphanumeric() {
+                component.push(character);
+            } else if !component.is_empty() && !component.ends_with('-') {
+                component.push('-');
+            }
+        }
+        let component: &str = component.trim_end_matches('-');
+
+        let mut characters = component.chars();
+        match characters.next() {
+            Some(first) => first.to_uppercase().chain(characters).collect(),
+            None => "X".to_string()
+        }
+    }).collect::<Vec<String>>().join(":")
+}
+
+fn quoted(text: &str) -> String {
+    let text: String = text.lines().map(str::trim).collect::<Vec<&str>>().join(" ");
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
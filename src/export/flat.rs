@@ -0,0 +1,376 @@
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+use serde_json::{Map, Value};
+
+use crate::export::{color_name, format_currency, format_date};
+use crate::models::{
+    BudgetDetail, CategoryGroupWithCategories, ClearedStatus, CurrencyFormat, DateFormat, FlagColor, HybridTransaction, TransactionDetail
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlatColumn {
+    Id,
+    // The split's transaction id on subtransaction rows.
+    ParentId,
+    Date,
+    Account,
+    Payee,
+    CategoryGroup,
+    Category,
+    Memo,
+    Amount,
+    Outflow,
+    Inflow,
+    // The unformatted amount, a number in JSON Lines.
+    Milliunits,
+    Cleared,
+    Approved,
+    Flag,
+    ImportId
+}
+
+impl FlatColumn {
+    pub fn name(self) -> &'static str {
+        match self {
+            FlatColumn::Id => "id",
+            FlatColumn::ParentId => "parent_id",
+            FlatColumn::Date => "date",
+            FlatColumn::Account => "account",
+            FlatColumn::Payee => "payee",
+            FlatColumn::CategoryGroup => "category_group",
+            FlatColumn::Category => "category",
+            FlatColumn::Memo => "memo",
+            FlatColumn::Amount => "amount",
+            FlatColumn::Outflow => "outflow",
+            FlatColumn::Inflow => "inflow",
+            FlatColumn::Milliunits => "milliunits",
+            FlatColumn::Cleared => "cleared",
+            FlatColumn::Approved => "approved",
+            FlatColumn::Flag => "flag",
+            FlatColumn::ImportId => "import_id"
+        }
+    }
+}
+
+// The columns of YNAB's own register export.
+pub const DEFAULT_COLUMNS: [FlatColumn; 10] = [
+    FlatColumn::Account,
+    FlatColumn::Flag,
+    FlatColumn::Date,
+    FlatColumn::Payee,
+    FlatColumn::CategoryGroup,
+    FlatColumn::Category,
+    FlatColumn::Memo,
+    FlatColumn::Outflow,
+    FlatColumn::Inflow,
+    FlatColumn::Cleared
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SplitMode {
+    // One row per subtransaction.
+    #[default]
+    Explode,
+    // One row per transaction, naming every category of the split.
+    Aggregate
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FlatFormat {
+    #[default]
+    Csv,
+    JsonLines
+}
+
+// Writes transactions as spreadsheet rows, with amounts and dates in the budget's own formats and
+// category group names filled in from the budget's categories.
+pub struct FlatExporter<'a> {
+    currency_format:    &'a CurrencyFormat,
+    date_format:        &'a DateFormat,
+    // Category id to category group name.
+    category_groups:    HashMap<&'a str, &'a str>,
+    columns:            Vec<FlatColumn>,
+    split_mode:         SplitMode,
+    format:             FlatFormat
+}
+
+impl<'a> FlatExporter<'a> {
+    pub fn new(currency_format: &'a CurrencyFormat, date_format: &'a DateFormat) -> FlatExporter<'a> {
+        FlatExporter {
+            currency_format,
+            date_format,
+            category_groups:    HashMap::new(),
+            columns:            DEFAULT_COLUMNS.to_vec(),
+            split_mode:         SplitMode::default(),
+            format:             FlatFormat::default()
+        }
+    }
+
+    pub fn for_budget(budget: &'a BudgetDetail) -> FlatExporter<'a> {
+        let mut exporter: FlatExporter = FlatExporter::new(&budget.currency_format, &budget.date_format);
+        for category in &budget.categories {
+            let group_name: Option<&str> = budget.category_groups.iter()
+                .find(|group| group.id == category.category_group_id)
+                .map(|group| group.name.as_str())
+                .or(category.category_group_name.as_deref());
+            if let Some(group_name) = group_name {
+                exporter.category_groups.insert(&category.id, group_name);
+            }
+        }
+
+        exporter
+    }
+
+    // Resolves category group names from `get_categories`.
+    pub fn category_groups(mut self, category_groups: &'a [CategoryGroupWithCategories]) -> FlatExporter<'a> {
+        for group in category_groups {
+            for category in &group.categories {
+                self.category_groups.insert(&category.id, &group.name);
+            }
+        }
+        self
+    }
+
+    pub fn columns(mut self, columns: &[FlatColumn]) -> FlatExporter<'a> {
+        self.columns = columns.to_vec();
+        self
+    }
+
+    pub fn split_mode(mut self, split_mode: SplitMode) -> FlatExporter<'a> {
+        self.split_mode = split_mode;
+        self
+    }
+
+    pub fn format(mut self, format: FlatFormat) -> FlatExporter<'a> {
+        self.format = format;
+        self
+    }
+
+    // Starts a stream of rows, writing the CSV header straight away.
+    pub fn writer<W: Write>(&self, writer: W) -> io::Result<FlatWriter<'_, W>> {
+        let output: Output<W> = match self.format {
+            FlatFormat::Csv => {
+                let mut csv_writer: ::csv::Writer<W> = ::csv::Writer::from_writer(writer);
+                csv_writer.write_record(self.columns.iter().map(|column| column.name()))?;
+                Output::Csv(Box::new(csv_writer))
+            },
+            FlatFormat::JsonLines => Output::JsonLines(writer)
+        };
+
+        Ok(FlatWriter { exporter: self, output })
+    }
+
+    pub fn write_transactions<W: Write>(&self, transactions: &[TransactionDetail], writer: W) -> io::Result<W> {
+        let mut flat_writer: FlatWriter<W> = self.writer(writer)?;
+        for transaction in transactions {
+            flat_writer.write_transaction(transaction)?;
+        }
+        flat_writer.finish()
+    }
+
+    pub fn write_hybrid_transactions<W: Write>(&self, transactions: &[HybridTransaction], writer: W) -> io::Result<W> {
+        let mut flat_writer: FlatWriter<W> = self.writer(writer)?;
+        for transaction in transactions {
+            flat_writer.write_hybrid_transaction(transaction)?;
+        }
+        flat_writer.finish()
+    }
+
+    fn category_group(&self, category_id: Option<&str>) -> Option<String> {
+        category_id.and_then(|category_id| self.category_groups.get(category_id)).map(|group_name| group_name.to_string())
+    }
+
+    fn cell(&self, column: FlatColumn, row: &Row) -> Cell {
+        let text = |value: Option<&str>| value.filter(|value| !value.is_empty()).map(|value| Cell::Text(value.to_string())).unwrap_or(Cell::Empty);
+
+        match column {
+            FlatColumn::Id => Cell::Text(row.id.to_string()),
+            FlatColumn::ParentId => text(row.parent_id),
+            FlatColumn::Date => Cell::Text(format_date(row.date, self.date_format)),
+            FlatColumn::Account => Cell::Text(row.account.to_string()),
+            FlatColumn::Payee => text(row.payee),
+            FlatColumn::CategoryGroup => text(row.category_group.as_deref()),
+            FlatColumn::Category => text(row.category.as_deref()),
+            FlatColumn::Memo => text(row.memo),
+            FlatColumn::Amount => Cell::Text(format_currency(row.amount, self.currency_format)),
+            FlatColumn::Outflow if row.amount < 0 => Cell::Text(format_currency(-row.amount, self.currency_format)),
+            FlatColumn::Outflow => Cell::Empty,
+            FlatColumn::Inflow if row.amount >= 0 => Cell::Text(format_currency(row.amount, self.currency_format)),
+            FlatColumn::Inflow => Cell::Empty,
+            FlatColumn::Milliunits => Cell::Number(row.amount),
+            FlatColumn::Cleared => Cell::Text(match row.cleared {
+                ClearedStatus::Cleared => "cleared",
+                ClearedStatus::Uncleared => "uncleared",
+                ClearedStatus::Reconciled => "reconciled"
+            }.to_string()),
+            FlatColumn::Approved => Cell::Bool(row.approved),
+            FlatColumn::Flag => text(row.flag_color.map(color_name)),
+            FlatColumn::ImportId => text(row.import_id)
+        }
+    }
+}
+
+enum Output<W: Write> {
+    Csv(Box<::csv::Writer<W>>),
+    JsonLines(W)
+}
+
+pub struct FlatWriter<'e, W: Write> {
+    exporter:   &'e FlatExporter<'e>,
+    output:     Output<W>
+}
+
+impl<W: Write> FlatWriter<'_, W> {
+    // Deleted transactions and subtransactions are left out.
+    pub fn write_transaction(&mut self, transaction: &TransactionDetail) -> io::Result<()> {
+        if transaction.deleted {
+            return Ok(());
+        }
+
+        let parent: Row = Row {
+            id:                 &transaction.id,
+            parent_id:          None,
+            date:               &transaction.date,
+            account:            &transaction.account_name,
+            payee:              transaction.payee_name.as_deref(),
+            category_group:     self.exporter.category_group(transaction.category_id.as_deref()),
+            category:           transaction.category_name.clone(),
+            memo:               transaction.memo.as_deref(),
+            amount:             transaction.amount,
+            cleared:            &transaction.cleared,
+            approved:           transaction.approved,
+            flag_color:         transaction.flag_color.as_ref(),
+            import_id:          transaction.import_id.as_deref()
+        };
+
+        let subtransactions: Vec<_> = transaction.subtransactions.iter().filter(|subtransaction| !subtransaction.deleted).collect();
+        if subtransactions.is_empty() {
+            return self.write_row(&parent);
+        }
+
+        match self.exporter.split_mode {
+            SplitMode::Explode => {
+                for subtransaction in subtransactions {
+                    self.write_row(&Row {
+                        id:             &subtransaction.id,
+                        parent_id:      Some(&transaction.id),
+                        payee:          subtransaction.payee_name.as_deref().or(parent.payee),
+                        category_group: self.exporter.category_group(subtransaction.category_id.as_deref()),
+                        category:       subtransaction.category_name.clone(),
+                        memo:           subtransaction.memo.as_deref().filter(|memo| !memo.is_empty()).or(parent.memo),
+                        amount:         subtransaction.amount,
+                        ..parent.clone()
+                    })?;
+                }
+                Ok(())
+            },
+            SplitMode::Aggregate => {
+                let joined = |names: Vec<Option<String>>| -> Option<String> {
+                    let mut distinct: Vec<String> = vec![];
+                    for name in names.into_iter().flatten() {
+                        if !distinct.contains(&name) {
+                            distinct.push(name);
+                        }
+                    }
+                    Some(distinct.join("; ")).filter(|joined| !joined.is_empty())
+                };
+
+                self.write_row(&Row {
+                    category_group: joined(subtransactions.iter().map(|subtransaction| self.exporter.category_group(subtransaction.category_id.as_deref())).collect()),
+                    category:       joined(subtransactions.iter().map(|subtransaction| subtransaction.category_name.clone()).collect()),
+                    ..parent
+                })
+            }
+        }
+    }
+
+    // Hybrid rows are already flat, so they are written as they come whatever the split mode.
+    pub fn write_hybrid_transaction(&mut self, transaction: &HybridTransaction) -> io::Result<()> {
+        if transaction.deleted {
+            return Ok(());
+        }
+
+        self.write_row(&Row {
+            id:                 &transaction.id,
+            parent_id:          transaction.parent_transaction_id.as_deref(),
+            date:               &transaction.date,
+            account:            &transaction.account_name,
+            payee:              transaction.payee_name.as_deref(),
+            category_group:     self.exporter.category_group(transaction.category_id.as_deref()),
+            category:           transaction.category_name.clone(),
+            memo:               transaction.memo.as_deref(),
+            amount:             transaction.amount,
+            cleared:            &transaction.cleared,
+            approved:           transaction.approved,
+            flag_color:         transaction.flag_color.as_ref(),
+            import_id:          transaction.import_id.as_deref()
+        })
+    }
+
+    // Flushes and hands back the underlying writer.
+    pub fn finish(self) -> io::Result<W> {
+        match self.output {
+            Output::Csv(csv_writer) => csv_writer.into_inner().map_err(|error| error.into_error()),
+            Output::JsonLines(mut writer) => {
+                writer.flush()?;
+                Ok(writer)
+            }
+        }
+    }
+
+    fn write_row(&mut self, row: &Row) -> io::Result<()> {
+        let cells = self.exporter.columns.iter().map(|column| (*column, self.exporter.cell(*column, row)));
+
+        match &mut self.output {
+            Output::Csv(csv_writer) => {
+                let record: Vec<String> = cells.map(|(_, cell)| match cell {
+                    Cell::Text(text) => text,
+                    Cell::Number(number) => number.to_string(),
+                    Cell::Bool(value) => value.to_string(),
+                    Cell::Empty => String::new()
+                }).collect();
+                csv_writer.write_record(&record)?;
+            },
+            Output::JsonLines(writer) => {
+                let object: Map<String, Value> = cells.map(|(column, cell)| {
+                    let value: Value = match cell {
+                        Cell::Text(text) => Value::String(text),
+                        Cell::Number(number) => Value::from(number),
+                        Cell::Bool(value) => Value::Bool(value),
+                        Cell::Empty => Value::Null
+                    };
+                    (column.name().to_string(), value)
+                }).collect();
+                serde_json::to_writer(&mut *writer, &object)?;
+                writeln!(writer)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
+struct Row<'r> {
+    id:             &'r str,
+    parent_id:      Option<&'r str>,
+    date:           &'r str,
+    account:        &'r str,
+    payee:          Option<&'r str>,
+    category_group: Option<String>,
+    category:       Option<String>,
+    memo:           Option<&'r str>,
+    amount:         i64,
+    cleared:        &'r ClearedStatus,
+    approved:       bool,
+    flag_color:     Option<&'r FlagColor>,
+    import_id:      Option<&'r str>
+}
+
+enum Cell {
+    Text(String),
+    Number(i64),
+    Bool(bool),
+    Empty
+}
@@ -0,0 +1,113 @@
+use std::io::{self, Write};
+
+use crate::export::{account_path, budget_entries, detail_entries, format_amount, live_accounts, payee_name, postings, Entry};
+use crate::models::{Account, BudgetDetail, ClearedStatus, TransactionDetail};
+
+// Writes ledger-cli / hledger journals. Accounts become `Assets:<Name>` or `Liabilities:<Name>`,
+// categories `Expenses:<Group>:<Category>` and the inflow category `Income:Ready to Assign`.
+// Splits are written as one posting per subtransaction and every transfer appears once.
+pub struct LedgerExporter<'a> {
+    budget:             &'a BudgetDetail,
+    balance_assertions: bool
+}
+
+impl<'a> LedgerExporter<'a> {
+    pub fn new(budget: &'a BudgetDetail) -> LedgerExporter<'a> {
+        LedgerExporter { budget, balance_assertions: true }
+    }
+
+    // Whether `write_budget` ends with a balance assertion per account, on by default.
+    pub fn balance_assertions(mut self, balance_assertions: bool) -> LedgerExporter<'a> {
+        self.balance_assertions = balance_assertions;
+        self
+    }
+
+    pub fn write_budget<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let entries: Vec<Entry> = budget_entries(self.budget);
+        self.write_entries(&entries, &mut writer)?;
+
+        if self.balance_assertions {
+            if let Some(last_date) = entries.last().map(|entry| entry.date) {
+                for account in live_accounts(self.budget) {
+                    self.write_assertion(account, last_date, &mut writer)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // Exports the result of a transaction query. `transfer_transaction_id` is taken from the
+    // budget where it can be, so the budget doesn't have to contain these transactions. No
+    // balance assertions are written since the query is unlikely to cover whole accounts.
+    pub fn write_transactions<W: Write>(&self, transactions: &[TransactionDetail], mut writer: W) -> io::Result<()> {
+        let entries: Vec<Entry> = detail_entries(self.budget, transactions);
+        self.write_entries(&entries, &mut writer)
+    }
+
+    fn write_entries<W: Write>(&self, entries: &[Entry], writer: &mut W) -> io::Result<()> {
+        for entry in entries {
+            let state: &str = match entry.cleared {
+                ClearedStatus::Cleared | ClearedStatus::Reconciled => "*",
+                ClearedStatus::Uncleared => "!"
+            };
+            writeln!(writer, "{} {} {}", entry.date, state, payee_name(self.budget, entry.payee_id).unwrap_or(""))?;
+            if let Some(memo) = entry.memo.filter(|memo| !memo.is_empty()) {
+                writeln!(writer, "    ; {}", single_line(memo))?;
+            }
+            writeln!(writer, "    ; ynab-id: {}", entry.id)?;
+
+            for posting in postings(self.budget, entry) {
+                self.write_posting(writer, &posting.path, posting.amount, posting.memo)?;
+            }
+            writeln!(writer)?;
+        }
+
+        Ok(())
+    }
+
+    fn write_posting<W: Write>(&self, writer: &mut W, path: &[String], milliunits: i64, memo: Option<&str>) -> io::Result<()> {
+        write!(writer, "    {:<40}  {}", account_name(path), self.amount(milliunits))?;
+        match memo.filter(|memo| !memo.is_empty()) {
+            Some(memo) => writeln!(writer, "  ; {}", single_line(memo)),
+            None => writeln!(writer)
+        }
+    }
+
+    // YNAB's cleared balance leaves out uncleared transactions while a journal assertion covers
+    // every posting, so accounts with uncleared activity only get a note.
+    fn write_assertion<W: Write>(&self, account: &Account, date: &str, writer: &mut W) -> io::Result<()> {
+        let name: String = account_name(&account_path(self.budget, &account.id));
+        if account.uncleared_balance != 0 {
+            return writeln!(
+                writer,
+                "; {} cleared balance {} not asserted, {} uncleared\n",
+                name,
+                self.amount(account.cleared_balance),
+                self.amount(account.uncleared_balance)
+            );
+        }
+
+        writeln!(writer, "{} * Balance assertion", date)?;
+        writeln!(writer, "    {:<40}  {} = {}", name, self.amount(0), self.amount(account.cleared_balance))?;
+        writeln!(writer)
+    }
+
+    fn amount(&self, milliunits: i64) -> String {
+        let currency = &self.budget.currency_format;
+        format!("{} {}", format_amount(milliunits, currency.decimal_digits), currency.iso_code)
+    }
+}
+
+// Colons separate account levels and two spaces end the account name, so neither can stay
+// inside a name.
+fn account_name(path: &[String]) -> String {
+    path.iter()
+        .map(|part| part.replace(':', "-").split_whitespace().collect::<Vec<&str>>().join(" "))
+        .collect::<Vec<String>>()
+        .join(":")
+}
+
+fn single_line(text: &str) -> String {
+    text.lines().map(str::trim).collect::<Vec<&str>>().join(" ")
+}
@@ -0,0 +1,276 @@
+use std::collections::HashSet;
+
+use chrono::NaiveDate;
+
+use crate::models::{Account, AccountType, BudgetDetail, ClearedStatus, CurrencyFormat, DateFormat, FlagColor, SubTransaction, TransactionDetail, TransactionSummary};
+
+#[cfg(feature = "arrow")]
+pub mod arrow;
+pub mod beancount;
+pub mod flat;
+pub mod ledger;
+
+// One transaction as the exporters see it, whether it came from a full budget or from a
+// transaction query.
+pub(crate) struct Entry<'a> {
+    pub(crate) id:                      &'a str,
+    pub(crate) date:                    &'a str,
+    pub(crate) amount:                  i64,
+    pub(crate) memo:                    Option<&'a str>,
+    pub(crate) cleared:                 &'a ClearedStatus,
+    pub(crate) flag_color:              Option<&'a FlagColor>,
+    pub(crate) account_id:              &'a str,
+    pub(crate) payee_id:                Option<&'a str>,
+    pub(crate) category_id:             Option<&'a str>,
+    pub(crate) transfer_account_id:     Option<&'a str>,
+    pub(crate) transfer_transaction_id: Option<&'a str>,
+    pub(crate) subtransactions:         Vec<&'a SubTransaction>
+}
+
+impl<'a> Entry<'a> {
+    fn from_summary(budget: &'a BudgetDetail, transaction: &'a TransactionSummary) -> Entry<'a> {
+        Entry {
+            id:                         &transaction.id,
+            date:                       &transaction.date,
+            amount:                     transaction.amount,
+            memo:                       transaction.memo.as_deref(),
+            cleared:                    &transaction.cleared,
+            flag_color:                 transaction.flag_color.as_ref(),
+            account_id:                 &transaction.account_id,
+            payee_id:                   transaction.payee_id.as_deref(),
+            category_id:                transaction.category_id.as_deref(),
+            transfer_account_id:        transaction.transfer_account_id.as_deref(),
+            transfer_transaction_id:    transaction.transfer_transaction_id.as_deref(),
+            subtransactions:            budget.subtransactions.iter().filter(|subtransaction| subtransaction.transaction_id == transaction.id && !subtransaction.deleted).collect()
+        }
+    }
+
+    fn from_detail(transaction: &'a TransactionDetail, transfer_transaction_id: Option<&'a str>) -> Entry<'a> {
+        Entry {
+            id:                         &transaction.id,
+            date:                       &transaction.date,
+            amount:                     transaction.amount,
+            memo:                       transaction.memo.as_deref(),
+            cleared:                    &transaction.cleared,
+            flag_color:                 transaction.flag_color.as_ref(),
+            account_id:                 &transaction.account_id,
+            payee_id:                   transaction.payee_id.as_deref(),
+            category_id:                transaction.category_id.as_deref(),
+            transfer_account_id:        transaction.transfer_account_id.as_deref(),
+            transfer_transaction_id,
+            subtransactions:            transaction.subtransactions.iter().filter(|subtransaction| !subtransaction.deleted).collect()
+        }
+    }
+}
+
+// Every live transaction of `budget`, by date, with each transfer kept on one side only.
+pub(crate) fn budget_entries(budget: &BudgetDetail) -> Vec<Entry<'_>> {
+    let entries: Vec<Entry> = budget.transactions.iter()
+        .filter(|transaction| !transaction.deleted)
+        .map(|transaction| Entry::from_summary(budget, transaction))
+        .collect();
+
+    without_transfer_duplicates(entries)
+}
+
+// Query results don't carry `transfer_transaction_id`, so it is looked up in `budget`, or
+// failing that, paired with the mirrored transaction among `transactions`.
+pub(crate) fn detail_entries<'a>(budget: &'a BudgetDetail, transactions: &'a [TransactionDetail]) -> Vec<Entry<'a>> {
+    let entries: Vec<Entry> = transactions.iter()
+        .filter(|transaction| !transaction.deleted)
+        .map(|transaction| {
+            let transfer_transaction_id: Option<&str> = transaction.transfer_account_id.as_ref().and_then(|transfer_account_id| {
+                budget.transactions.iter()
+                    .find(|summary| summary.id == transaction.id)
+                    .and_then(|summary| summary.transfer_transaction_id.as_deref())
+                    .or_else(|| transactions.iter().find(|other| {
+                        other.account_id == *transfer_account_id
+                            && other.transfer_account_id.as_ref() == Some(&transaction.account_id)
+                            && other.date == transaction.date
+                            && other.amount == -transaction.amount
+                    }).map(|other| other.id.as_str()))
+            });
+            Entry::from_detail(transaction, transfer_transaction_id)
+        })
+        .collect();
+
+    without_transfer_duplicates(entries)
+}
+
+fn without_transfer_duplicates(mut entries: Vec<Entry<'_>>) -> Vec<Entry<'_>> {
+    entries.sort_by(|left, right| left.date.cmp(right.date).then(left.id.cmp(right.id)));
+
+    // A transfer out of a split always stays with the split, which carries the other postings.
+    let split_transfers: HashSet<&str> = entries.iter()
+        .flat_map(|entry| entry.subtransactions.iter())
+        .filter(|subtransaction| subtransaction.transfer_account_id.is_some())
+        .map(|subtransaction| subtransaction.id.as_str())
+        .collect();
+
+    let mut mirrored: HashSet<&str> = HashSet::new();
+    entries.into_iter().filter(|entry| {
+        if mirrored.contains(entry.id) || entry.transfer_transaction_id.is_some_and(|id| split_transfers.contains(id)) {
+            return false;
+        }
+
+        mirrored.extend(entry.transfer_transaction_id);
+        mirrored.extend(entry.subtransactions.iter().filter_map(|subtransaction| subtransaction.transfer_transaction_id.as_deref()));
+        true
+    }).collect()
+}
+
+// One side of an entry. The first posting is always the entry's own account, followed by the
+// category or transfer account, or one posting per subtransaction for a split.
+pub(crate) struct Posting<'a> {
+    pub(crate) path:        Vec<String>,
+    pub(crate) account_id:  Option<&'a str>,
+    pub(crate) amount:      i64,
+    pub(crate) memo:        Option<&'a str>,
+    pub(crate) id:          Option<&'a str>
+}
+
+pub(crate) fn postings<'a>(budget: &BudgetDetail, entry: &Entry<'a>) -> Vec<Posting<'a>> {
+    let mut postings: Vec<Posting> = vec![
+        Posting { path: account_path(budget, entry.account_id), account_id: Some(entry.account_id), amount: entry.amount, memo: None, id: None }
+    ];
+
+    if entry.subtransactions.is_empty() {
+        postings.push(Posting {
+            path:       match entry.transfer_account_id {
+                Some(transfer_account_id) => account_path(budget, transfer_account_id),
+                None => category_path(budget, entry.category_id)
+            },
+            account_id: entry.transfer_account_id,
+            amount:     -entry.amount,
+            memo:       None,
+            id:         None
+        });
+    }
+
+    postings.extend(entry.subtransactions.iter().map(|subtransaction| Posting {
+        path:       match &subtransaction.transfer_account_id {
+            Some(transfer_account_id) => account_path(budget, transfer_account_id),
+            None => category_path(budget, subtransaction.category_id.as_deref())
+        },
+        account_id: subtransaction.transfer_account_id.as_deref(),
+        amount:     -subtransaction.amount,
+        memo:       subtransaction.memo.as_deref().filter(|memo| !memo.is_empty()),
+        id:         Some(subtransaction.id.as_str())
+    }));
+
+    postings
+}
+
+pub(crate) fn account_root(account_type: &AccountType) -> &'static str {
+    if account_type.is_liability() { "Liabilities" } else { "Assets" }
+}
+
+// `Assets:Checking` style path of an account, before any format specific cleanup.
+pub(crate) fn account_path(budget: &BudgetDetail, account_id: &str) -> Vec<String> {
+    match budget.accounts.iter().find(|account| account.id == account_id) {
+        Some(account) => vec![account_root(&account.r#type).to_string(), account.name.clone()],
+        None => vec!["Assets".to_string(), "Unknown".to_string(), account_id.to_string()]
+    }
+}
+
+// `Expenses:<Group>:<Category>`, with the internal inflow category as income.
+pub(crate) fn category_path(budget: &BudgetDetail, category_id: Option<&str>) -> Vec<String> {
+    let category = match category_id.and_then(|category_id| budget.categories.iter().find(|category| category.id == category_id)) {
+        Some(category) => category,
+        None => return vec!["Expenses".to_string(), "Uncategorized".to_string()]
+    };
+
+    let group_name: String = budget.category_groups.iter()
+        .find(|group| group.id == category.category_group_id)
+        .map(|group| group.name.clone())
+        .or_else(|| category.category_group_name.clone())
+        .unwrap_or_default();
+
+    if group_name == "Internal Master Category" {
+        return match category.name.strip_prefix("Inflow:") {
+            Some(name) => vec!["Income".to_string(), name.trim().to_string()],
+            None => vec!["Expenses".to_string(), category.name.clone()]
+        };
+    }
+
+    vec!["Expenses".to_string(), group_name, category.name.clone()]
+}
+
+pub(crate) fn payee_name<'a>(budget: &'a BudgetDetail, payee_id: Option<&str>) -> Option<&'a str> {
+    payee_id.and_then(|payee_id| budget.payees.iter().find(|payee| payee.id == payee_id)).map(|payee| payee.name.as_str())
+}
+
+pub(crate) fn live_accounts(budget: &BudgetDetail) -> impl Iterator<Item = &Account> {
+    budget.accounts.iter().filter(|account| !account.deleted)
+}
+
+// Writes milliunits with the currency's decimal digits, keeping any extra precision the
+// amount has so that postings still balance.
+pub(crate) fn format_amount(milliunits: i64, decimal_digits: i32) -> String {
+    let sign: &str = if milliunits < 0 { "-" } else { "" };
+    let magnitude: u64 = milliunits.unsigned_abs();
+    let mut fraction: String = format!("{:03}", magnitude % 1000);
+    let decimal_digits: usize = decimal_digits.clamp(0, 3) as usize;
+
+    while fraction.len() > decimal_digits && fraction.ends_with('0') {
+        fraction.pop();
+    }
+
+    if fraction.is_empty() {
+        format!("{}{}", sign, magnitude / 1000)
+    } else {
+        format!("{}{}.{}", sign, magnitude / 1000, fraction)
+    }
+}
+
+// Formats milliunits the way the budget displays them, e.g. `-1.234,56 €` or `-$1,234.56`,
+// rounding half away from zero to the currency's decimal digits.
+pub fn format_currency(milliunits: i64, currency_format: &CurrencyFormat) -> String {
+    let decimal_digits: u32 = currency_format.decimal_digits.clamp(0, 3) as u32;
+    let scale: u64 = 10u64.pow(3 - decimal_digits);
+    let units: u64 = (milliunits.unsigned_abs() + scale / 2) / scale;
+    let divisor: u64 = 10u64.pow(decimal_digits);
+
+    let integer: String = (units / divisor).to_string();
+    let mut grouped: String = String::new();
+    for (index, digit) in integer.chars().enumerate() {
+        if index > 0 && (integer.len() - index) % 3 == 0 {
+            grouped.push_str(&currency_format.group_separator);
+        }
+        grouped.push(digit);
+    }
+    if decimal_digits > 0 {
+        grouped.push_str(&currency_format.decimal_separator);
+        grouped.push_str(&format!("{:0width$}", units % divisor, width = decimal_digits as usize));
+    }
+
+    let sign: &str = if milliunits < 0 && units > 0 { "-" } else { "" };
+    match (currency_format.display_symbol, currency_format.symbol_first) {
+        (true, true) => format!("{}{}{}", sign, currency_format.currency_symbol, grouped),
+        (true, false) => format!("{}{} {}", sign, grouped, currency_format.currency_symbol),
+        (false, _) => format!("{}{}", sign, grouped)
+    }
+}
+
+// Formats an ISO date with a YNAB date format such as `DD.MM.YYYY` or `MM/DD/YYYY`. Dates that
+// aren't ISO dates are returned unchanged.
+pub fn format_date(date: &str, date_format: &DateFormat) -> String {
+    let parsed: NaiveDate = match NaiveDate::parse_from_str(date, "%Y-%m-%d") {
+        Ok(parsed) => parsed,
+        Err(_) => return date.to_string()
+    };
+    let pattern: String = date_format.format.replace('%', "%%").replace("YYYY", "%Y").replace("YY", "%y").replace("MM", "%m").replace("DD", "%d");
+
+    parsed.format(&pattern).to_string()
+}
+
+pub(crate) fn color_name(flag_color: &FlagColor) -> &'static str {
+    match flag_color {
+        FlagColor::Red => "red",
+        FlagColor::Orange => "orange",
+        FlagColor::Yellow => "yellow",
+        FlagColor::Green => "green",
+        FlagColor::Blue => "blue",
+        FlagColor::Purple => "purple"
+    }
+}
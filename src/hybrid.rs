@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+
+use crate::models::{HybridTransaction, SubTransaction, TransactionDetail, TransactionType};
+
+// `get_transaction_list_for_payee` and friends return `HybridTransaction`s, where each split line is
+// a row of its own pointing at its parent through `parent_transaction_id`. The other list endpoints
+// return `TransactionDetail`s with the split lines nested. These convert between the two.
+
+// Rows for the parent and then each split line. Split lines without a payee of their own carry the
+// parent's, as the API returns them.
+pub fn flatten_transaction_detail(transaction: &TransactionDetail) -> Vec<HybridTransaction> {
+    let parent: HybridTransaction = HybridTransaction {
+        id:                         transaction.id.clone(),
+        date:                       transaction.date.clone(),
+        amount:                     transaction.amount,
+        memo:                       transaction.memo.clone(),
+        cleared:                    transaction.cleared.clone(),
+        approved:                   transaction.approved,
+        flag_color:                 transaction.flag_color.clone(),
+        account_id:                 transaction.account_id.clone(),
+        payee_id:                   transaction.payee_id.clone(),
+        category_id:                transaction.category_id.clone(),
+        transfer_account_id:        transaction.transfer_account_id.clone(),
+        matched_transaction_id:     transaction.matched_transaction_id.clone(),
+        import_id:                  transaction.import_id.clone(),
+        import_payee_name:          transaction.import_payee_name.clone(),
+        import_payee_name_original: transaction.import_payee_name_original.clone(),
+        debt_transaction_type:      transaction.debt_transaction_type.clone(),
+        deleted:                    transaction.deleted,
+        r#type:                     TransactionType::Transaction,
+        parent_transaction_id:      None,
+        account_name:               transaction.account_name.clone(),
+        payee_name:                 transaction.payee_name.clone(),
+        category_name:              transaction.category_name.clone()
+    };
+
+    let subtransactions = transaction.subtransactions.iter().map(|subtransaction| {
+        let (payee_id, payee_name) = match subtransaction.payee_id {
+            Some(_) => (subtransaction.payee_id.clone(), subtransaction.payee_name.clone()),
+            None => (transaction.payee_id.clone(), transaction.payee_name.clone())
+        };
+        HybridTransaction {
+            id:                         subtransaction.id.clone(),
+            amount:                     subtransaction.amount,
+            memo:                       subtransaction.memo.clone(),
+            payee_id,
+            category_id:                subtransaction.category_id.clone(),
+            transfer_account_id:        subtransaction.transfer_account_id.clone(),
+            matched_transaction_id:     None,
+            import_id:                  None,
+            import_payee_name:          None,
+            import_payee_name_original: None,
+            debt_transaction_type:      None,
+            deleted:                    subtransaction.deleted,
+            r#type:                     TransactionType::SubTransaction,
+            parent_transaction_id:      Some(transaction.id.clone()),
+            payee_name,
+            category_name:              subtransaction.category_name.clone(),
+            ..parent.clone()
+        }
+    }).collect::<Vec<HybridTransaction>>();
+
+    std::iter::once(parent).chain(subtransactions).collect()
+}
+
+pub fn flatten_transaction_details(transactions: &[TransactionDetail]) -> Vec<HybridTransaction> {
+    transactions.iter().flat_map(flatten_transaction_detail).collect()
+}
+
+// Nests split rows under their parent row, in the order the parents first appear. A split line
+// that repeats its parent's payee gets none of its own, as in `TransactionDetail`.
+//
+// The payee endpoint returns only the split lines that match the payee when the parent doesn't, so
+// a split row's parent may be missing. Such rows are nested under a stand-in parent with the
+// parent's id, the date, account, status and flag every row of a transaction shares, and the sum
+// of the rows' amounts. Its payee, category and memo are unknown and left empty.
+pub fn group_hybrid_transactions(transactions: &[HybridTransaction]) -> Vec<TransactionDetail> {
+    let mut details: Vec<TransactionDetail> = vec![];
+    let mut positions: HashMap<&str, usize> = HashMap::new();
+    let mut stand_ins: Vec<usize> = vec![];
+
+    for row in transactions.iter().filter(|row| matches!(row.r#type, TransactionType::Transaction)) {
+        positions.insert(&row.id, details.len());
+        details.push(parent_detail(row));
+    }
+
+    for row in transactions {
+        let parent_id: &str = match (&row.r#type, row.parent_transaction_id.as_deref()) {
+            (TransactionType::SubTransaction, Some(parent_id)) => parent_id,
+            _ => continue
+        };
+        let position: usize = *positions.entry(parent_id).or_insert_with(|| {
+            stand_ins.push(details.len());
+            details.push(TransactionDetail {
+                id:                     parent_id.to_string(),
+                amount:                 0,
+                memo:                   None,
+                payee_id:               None,
+                category_id:            None,
+                transfer_account_id:    None,
+                payee_name:             None,
+                category_name:          None,
+                ..parent_detail(row)
+            });
+            details.len() - 1
+        });
+
+        let parent: &mut TransactionDetail = &mut details[position];
+        let repeats_payee: bool = parent.payee_id.is_some() && parent.payee_id == row.payee_id;
+        parent.subtransactions.push(SubTransaction {
+            id:                         row.id.clone(),
+            transaction_id:             parent_id.to_string(),
+            amount:                     row.amount,
+            memo:                       row.memo.clone(),
+            payee_id:                   row.payee_id.clone().filter(|_| !repeats_payee),
+            payee_name:                 row.payee_name.clone().filter(|_| !repeats_payee),
+            category_id:                row.category_id.clone(),
+            category_name:              row.category_name.clone(),
+            transfer_account_id:        row.transfer_account_id.clone(),
+            transfer_transaction_id:    None,
+            deleted:                    row.deleted
+        });
+    }
+
+    for position in stand_ins {
+        let stand_in: &mut TransactionDetail = &mut details[position];
+        stand_in.amount = stand_in.subtransactions.iter().filter(|subtransaction| !subtransaction.deleted).map(|subtransaction| subtransaction.amount).sum();
+    }
+
+    // Parent rows can come after their split rows, so the order is fixed up by first appearance.
+    let first_seen: HashMap<&str, usize> = transactions.iter().enumerate().rev()
+        .map(|(position, row)| (row.parent_transaction_id.as_deref().unwrap_or(&row.id), position))
+        .collect();
+    details.sort_by_key(|detail| first_seen.get(detail.id.as_str()).copied().unwrap_or(usize::MAX));
+    details
+}
+
+fn parent_detail(row: &HybridTransaction) -> TransactionDetail {
+    TransactionDetail {
+        id:                         row.id.clone(),
+        date:                       row.date.clone(),
+        amount:                     row.amount,
+        memo:                       row.memo.clone(),
+        cleared:                    row.cleared.clone(),
+        approved:                   row.approved,
+        flag_color:                 row.flag_color.clone(),
+        account_id:                 row.account_id.clone(),
+        payee_id:                   row.payee_id.clone(),
+        category_id:                row.category_id.clone(),
+        transfer_account_id:        row.transfer_account_id.clone(),
+        matched_transaction_id:     row.matched_transaction_id.clone(),
+        import_id:                  row.import_id.clone(),
+        import_payee_name:          row.import_payee_name.clone(),
+        import_payee_name_original: row.import_payee_name_original.clone(),
+        debt_transaction_type:      row.debt_transaction_type.clone(),
+        deleted:                    row.deleted,
+        account_name:               row.account_name.clone(),
+        payee_name:                 row.payee_name.clone(),
+        category_name:              row.category_name.clone(),
+        subtransactions:            vec![]
+    }
+}
@@ -0,0 +1,120 @@
+use std::fmt;
+use std::io::{self, Read};
+
+use chrono::NaiveDate;
+
+use crate::import::markup::{parse_elements, Element};
+use crate::import::parse_milliunits;
+use crate::import::statement::{BankStatement, EntryStatus, StatementEntry};
+
+#[derive(Debug)]
+pub enum CamtImportError {
+    Read(io::Error),
+    NotCamt,
+    MissingField { field: &'static str },
+    InvalidDate { value: String },
+    InvalidAmount { value: String }
+}
+
+impl fmt::Display for CamtImportError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CamtImportError::Read(error) => write!(formatter, "could not read camt.053: {}", error),
+            CamtImportError::NotCamt => write!(formatter, "input has no BkToCstmrStmt element"),
+            CamtImportError::MissingField { field } => write!(formatter, "entry is missing {}", field),
+            CamtImportError::InvalidDate { value } => write!(formatter, "`{}` is not an ISO date", value),
+            CamtImportError::InvalidAmount { value } => write!(formatter, "`{}` is not an amount", value)
+        }
+    }
+}
+
+impl std::error::Error for CamtImportError {}
+
+// Reads every `Stmt` of an ISO 20022 camt.053 bank-to-customer statement. Any schema version
+// works, since only elements shared by all of them are used.
+pub fn parse_camt053<R: Read>(mut reader: R) -> Result<Vec<BankStatement>, CamtImportError> {
+    let mut bytes: Vec<u8> = vec![];
+    reader.read_to_end(&mut bytes).map_err(CamtImportError::Read)?;
+    let contents: String = String::from_utf8_lossy(&bytes).into_owned();
+
+    let root: Element = parse_elements(&contents);
+    let mut documents: Vec<&Element> = vec![];
+    root.find_all(&["BkToCstmrStmt"], &mut documents);
+    if documents.is_empty() {
+        return Err(CamtImportError::NotCamt);
+    }
+
+    documents.into_iter().flat_map(|document| document.children("Stmt")).map(statement).collect()
+}
+
+fn statement(statement: &Element) -> Result<BankStatement, CamtImportError> {
+    let account: Option<&Element> = statement.descendant(&["Acct", "Id"]);
+
+    Ok(BankStatement {
+        id:         statement.text("Id").map(str::to_string),
+        account:    account.and_then(|account| account.text("IBAN").or_else(|| account.path_text(&["Othr", "Id"]))).map(str::to_string),
+        currency:   statement.path_text(&["Acct", "Ccy"]).map(str::to_string),
+        entries:    statement.children("Ntry").map(entry).collect::<Result<Vec<StatementEntry>, CamtImportError>>()?
+    })
+}
+
+fn entry(entry: &Element) -> Result<StatementEntry, CamtImportError> {
+    let magnitude: &str = entry.text("Amt").ok_or(CamtImportError::MissingField { field: "Amt" })?;
+    let magnitude: i64 = parse_milliunits(magnitude, '.').ok_or_else(|| CamtImportError::InvalidAmount { value: magnitude.to_string() })?;
+    let debit: bool = match entry.text("CdtDbtInd") {
+        Some("DBIT") => true,
+        Some("CRDT") => false,
+        _ => return Err(CamtImportError::MissingField { field: "CdtDbtInd" })
+    };
+
+    // camt.053.001.02 writes the status as text, later versions wrap it in `Cd`.
+    let status: EntryStatus = match entry.text("Sts").or_else(|| entry.path_text(&["Sts", "Cd"])) {
+        Some("BOOK") | None => EntryStatus::Booked,
+        Some(_) => EntryStatus::Pending
+    };
+
+    let value_date: Option<NaiveDate> = entry.child("ValDt").map(date).transpose()?;
+    let booking_date: NaiveDate = match entry.child("BookgDt").map(date).transpose()? {
+        Some(booking_date) => booking_date,
+        None => value_date.ok_or(CamtImportError::MissingField { field: "BookgDt" })?
+    };
+
+    let details: Option<&Element> = entry.descendant(&["NtryDtls", "TxDtls"]);
+    let reference: Option<&str> = entry.text("AcctSvcrRef")
+        .or_else(|| entry.text("NtryRef"))
+        .or_else(|| details.and_then(|details| details.path_text(&["Refs", "AcctSvcrRef"])));
+
+    // The counterparty is whoever is on the other side: the creditor of a debit, the debtor of a credit.
+    let party: &str = if debit { "Cdtr" } else { "Dbtr" };
+    let counterparty: Option<&str> = details
+        .and_then(|details| details.descendant(&["RltdPties", party]))
+        .and_then(|party| party.text("Nm").or_else(|| party.path_text(&["Pty", "Nm"])));
+
+    let unstructured: Vec<&str> = details
+        .and_then(|details| details.child("RmtInf"))
+        .map(|remittance| remittance.children("Ustrd").filter_map(|line| line.text.as_deref()).collect())
+        .unwrap_or_default();
+    let remittance: Option<String> = if unstructured.is_empty() {
+        entry.text("AddtlNtryInf").or_else(|| details.and_then(|details| details.text("AddtlTxInf"))).map(str::to_string)
+    } else {
+        Some(unstructured.join(" "))
+    };
+
+    Ok(StatementEntry {
+        reference:      reference.filter(|reference| *reference != "NOTPROVIDED").map(str::to_string),
+        status,
+        booking_date,
+        value_date,
+        amount:         if debit { -magnitude } else { magnitude },
+        counterparty:   counterparty.map(str::to_string),
+        remittance
+    })
+}
+
+// Dates come as `<Dt>2023-06-12</Dt>` or `<DtTm>2023-06-12T10:15:00+02:00</DtTm>`.
+fn date(element: &Element) -> Result<NaiveDate, CamtImportError> {
+    let value: &str = element.text("Dt").or_else(|| element.text("DtTm")).ok_or(CamtImportError::MissingField { field: "Dt" })?;
+    value.get(..10)
+        .and_then(|day| NaiveDate::parse_from_str(day, "%Y-%m-%d").ok())
+        .ok_or_else(|| CamtImportError::InvalidDate { value: value.to_string() })
+}
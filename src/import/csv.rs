@@ -0,0 +1,230 @@
+use std::fmt;
+use std::io::Read;
+
+use chrono::NaiveDate;
+use serde::Deserialize;
+
+use crate::builder::{MAX_MEMO_LENGTH, MAX_PAYEE_NAME_LENGTH};
+use crate::client::Client;
+use crate::import::{parse_milliunits, truncate, BatchImporter, ImportIdGenerator, ImportOutcome};
+use crate::models::{ClearedStatus, SaveTransaction};
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub enum Column {
+    Index(usize),
+    Name(String)
+}
+
+impl fmt::Display for Column {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Column::Index(index) => write!(formatter, "column {}", index),
+            Column::Name(name) => write!(formatter, "column `{}`", name)
+        }
+    }
+}
+
+// Describes how a bank lays out its CSV export. Columns are either zero based indices or
+// header names, e.g.
+//
+//     skip_rows = 4
+//     delimiter = ";"
+//     date_column = "Buchungstag"
+//     date_format = "%d.%m.%Y"
+//     amount_column = "Betrag"
+//     decimal_separator = ","
+//     payee_column = "Auftraggeber / Begünstigter"
+//     memo_column = "Verwendungszweck"
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CsvProfile {
+    #[serde(default = "default_delimiter")]
+    pub delimiter:          char,
+    #[serde(default)]
+    pub skip_rows:          usize,
+    #[serde(default = "default_has_header")]
+    pub has_header:         bool,
+    pub date_column:        Column,
+    pub date_format:        String,
+    pub amount_column:      Option<Column>,
+    pub debit_column:       Option<Column>,
+    pub credit_column:      Option<Column>,
+    #[serde(default = "default_decimal_separator")]
+    pub decimal_separator:  char,
+    #[serde(default)]
+    pub negate_amounts:     bool,
+    pub payee_column:       Option<Column>,
+    pub memo_column:        Option<Column>,
+    #[serde(default = "default_cleared")]
+    pub cleared:            ClearedStatus
+}
+
+fn default_delimiter() -> char {
+    ','
+}
+
+fn default_has_header() -> bool {
+    true
+}
+
+fn default_decimal_separator() -> char {
+    '.'
+}
+
+fn default_cleared() -> ClearedStatus {
+    ClearedStatus::Cleared
+}
+
+impl CsvProfile {
+    pub fn from_toml(profile: &str) -> Result<CsvProfile, CsvImportError> {
+        let profile: CsvProfile = toml::from_str(profile).map_err(|error| CsvImportError::Profile(error.to_string()))?;
+
+        if !profile.delimiter.is_ascii() {
+            return Err(CsvImportError::Profile(format!("delimiter `{}` is not an ASCII character", profile.delimiter)));
+        }
+
+        if profile.amount_column.is_none() && profile.debit_column.is_none() && profile.credit_column.is_none() {
+            return Err(CsvImportError::Profile("one of amount_column, debit_column or credit_column is required".to_string()));
+        }
+
+        Ok(profile)
+    }
+}
+
+#[derive(Debug)]
+pub enum CsvImportError {
+    Profile(String),
+    Read(::csv::Error),
+    UnknownColumn(Column),
+    MissingValue { line: u64, column: Column },
+    InvalidDate { line: u64, value: String },
+    InvalidAmount { line: u64, value: String }
+}
+
+impl fmt::Display for CsvImportError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CsvImportError::Profile(detail) => write!(formatter, "invalid CSV profile: {}", detail),
+            CsvImportError::Read(error) => write!(formatter, "could not read CSV: {}", error),
+            CsvImportError::UnknownColumn(column) => write!(formatter, "{} does not exist", column),
+            CsvImportError::MissingValue { line, column } => write!(formatter, "line {}: {} is empty", line, column),
+            CsvImportError::InvalidDate { line, value } => write!(formatter, "line {}: `{}` does not match the date format", line, value),
+            CsvImportError::InvalidAmount { line, value } => write!(formatter, "line {}: `{}` is not an amount", line, value)
+        }
+    }
+}
+
+impl std::error::Error for CsvImportError {}
+
+impl From<::csv::Error> for CsvImportError {
+    fn from(error: ::csv::Error) -> CsvImportError {
+        CsvImportError::Read(error)
+    }
+}
+
+struct Columns {
+    date:   usize,
+    amount: Option<usize>,
+    debit:  Option<usize>,
+    credit: Option<usize>,
+    payee:  Option<usize>,
+    memo:   Option<usize>
+}
+
+fn resolve(column: &Column, headers: Option<&::csv::StringRecord>) -> Result<usize, CsvImportError> {
+    match column {
+        Column::Index(index) => Ok(*index),
+        Column::Name(name) => headers
+            .and_then(|headers| headers.iter().position(|header| header.trim().eq_ignore_ascii_case(name.trim())))
+            .ok_or_else(|| CsvImportError::UnknownColumn(column.clone()))
+    }
+}
+
+fn resolve_optional(column: &Option<Column>, headers: Option<&::csv::StringRecord>) -> Result<Option<usize>, CsvImportError> {
+    column.as_ref().map(|column| resolve(column, headers)).transpose()
+}
+
+// Reads a bank statement into transactions for `account_id`, with import ids assigned in file order.
+pub fn read_transactions<R: Read>(mut reader: R, profile: &CsvProfile, account_id: &str) -> Result<Vec<SaveTransaction>, CsvImportError> {
+    let mut contents: String = String::new();
+    reader.read_to_string(&mut contents).map_err(|error| CsvImportError::Read(error.into()))?;
+    // Excel starts its UTF-8 exports with a byte order mark, which would end up in the first header.
+    let contents: &str = contents.strip_prefix('\u{feff}').unwrap_or(&contents);
+
+    let body: String = contents.lines().skip(profile.skip_rows).collect::<Vec<&str>>().join("\n");
+    let mut csv_reader = ::csv::ReaderBuilder::new()
+        .delimiter(profile.delimiter as u8)
+        .has_headers(profile.has_header)
+        .flexible(true)
+        .from_reader(body.as_bytes());
+
+    let headers: Option<::csv::StringRecord> = if profile.has_header { Some(csv_reader.headers()?.clone()) } else { None };
+    let columns: Columns = Columns {
+        date:   resolve(&profile.date_column, headers.as_ref())?,
+        amount: resolve_optional(&profile.amount_column, headers.as_ref())?,
+        debit:  resolve_optional(&profile.debit_column, headers.as_ref())?,
+        credit: resolve_optional(&profile.credit_column, headers.as_ref())?,
+        payee:  resolve_optional(&profile.payee_column, headers.as_ref())?,
+        memo:   resolve_optional(&profile.memo_column, headers.as_ref())?
+    };
+
+    let mut generator: ImportIdGenerator = ImportIdGenerator::new();
+    let mut transactions: Vec<SaveTransaction> = vec![];
+
+    for record in csv_reader.records() {
+        let record: ::csv::StringRecord = record?;
+        if record.iter().all(|field| field.trim().is_empty()) {
+            continue;
+        }
+
+        let line: u64 = record.position().map_or(0, |position| position.line()) + profile.skip_rows as u64;
+        let field = |index: Option<usize>| index.and_then(|index| record.get(index)).map(str::trim).filter(|value| !value.is_empty());
+
+        let raw_date: &str = field(Some(columns.date)).ok_or_else(|| CsvImportError::MissingValue { line, column: profile.date_column.clone() })?;
+        let date: NaiveDate = NaiveDate::parse_from_str(raw_date, &profile.date_format)
+            .map_err(|_| CsvImportError::InvalidDate { line, value: raw_date.to_string() })?;
+
+        let parse = |value: &str| parse_milliunits(value, profile.decimal_separator).ok_or_else(|| CsvImportError::InvalidAmount { line, value: value.to_string() });
+        let mut amount: i64 = match field(columns.amount) {
+            Some(amount) => parse(amount)?,
+            None => match (field(columns.debit).map(parse).transpose()?, field(columns.credit)) {
+                (Some(debit), _) if debit != 0 => -debit.abs(),
+                (_, Some(credit)) => parse(credit)?.abs(),
+                (Some(_), None) => 0,
+                (None, None) => {
+                    let column: Column = profile.amount_column.clone().or_else(|| profile.debit_column.clone()).or_else(|| profile.credit_column.clone()).unwrap();
+                    return Err(CsvImportError::MissingValue { line, column });
+                }
+            }
+        };
+
+        if profile.negate_amounts {
+            amount = -amount;
+        }
+
+        let mut transaction: SaveTransaction = SaveTransaction {
+            account_id:         account_id.to_string(),
+            date:               date.format("%Y-%m-%d").to_string(),
+            amount,
+            payee_id:           None,
+            payee_name:         field(columns.payee).map(|payee| truncate(payee, MAX_PAYEE_NAME_LENGTH)),
+            category_id:        None,
+            memo:               field(columns.memo).map(|memo| truncate(memo, MAX_MEMO_LENGTH)),
+            cleared:            Some(profile.cleared.clone()),
+            approved:           Some(false),
+            flag_color:         None,
+            import_id:          None,
+            subtransactions:    None
+        };
+        generator.assign(&mut transaction);
+        transactions.push(transaction);
+    }
+
+    Ok(transactions)
+}
+
+pub async fn import_csv<R: Read>(client: &Client, budget_id: &str, account_id: &str, reader: R, profile: &CsvProfile) -> Result<Vec<ImportOutcome>, CsvImportError> {
+    let transactions: Vec<SaveTransaction> = read_transactions(reader, profile, account_id)?;
+    Ok(BatchImporter::new(client, budget_id).import(transactions).await)
+}
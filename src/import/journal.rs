@@ -0,0 +1,471 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, Read};
+
+use chrono::NaiveDate;
+use either::{Left, Right};
+use serde::Deserialize;
+
+use crate::builder::{MAX_MEMO_LENGTH, MAX_PAYEE_NAME_LENGTH};
+use crate::client::Client;
+use crate::import::{fnv1a64, parse_milliunits, truncate, BatchImporter, ImportOutcome};
+use crate::models::{Account, AccountsResponse, ClearedStatus, ErrorResponse, SaveSubTransaction, SaveTransaction};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JournalFormat {
+    // ledger-cli and hledger journals.
+    Ledger,
+    Beancount
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JournalPosting {
+    pub account:    String,
+    pub amount:     i64,
+    pub memo:       Option<String>
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JournalEntry {
+    pub line:       usize,
+    pub date:       NaiveDate,
+    pub cleared:    bool,
+    pub payee:      Option<String>,
+    pub memo:       Option<String>,
+    // Set on entries exported from YNAB, which are already in the budget.
+    pub ynab_id:    Option<String>,
+    pub postings:   Vec<JournalPosting>
+}
+
+impl JournalEntry {
+    // `JRNL:` and a FNV-1a hash of the date, payee, memo and postings. The cleared flag is left
+    // out so that clearing an entry in the journal doesn't import it a second time.
+    pub fn import_id(&self) -> String {
+        let mut text: String = format!("{}\n{}\n{}", self.date, self.payee.as_deref().unwrap_or(""), self.memo.as_deref().unwrap_or(""));
+        for posting in &self.postings {
+            text.push_str(&format!("\n{} {}", posting.account, posting.amount));
+        }
+
+        format!("JRNL:{:016x}", fnv1a64(text.as_bytes()))
+    }
+}
+
+// Which YNAB account or category each journal account stands for. Written as
+//
+//     uncategorized = ["Expenses:Uncategorized"]
+//
+//     [accounts]
+//     "Assets:Checking" = "a1b2..."
+//
+//     [categories]
+//     "Expenses:Bills:Rent" = "c3d4..."
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct JournalMapping {
+    // Journal accounts whose postings become uncategorized lines.
+    #[serde(default)]
+    pub uncategorized:  Vec<String>,
+    #[serde(default)]
+    pub accounts:       HashMap<String, String>,
+    #[serde(default)]
+    pub categories:     HashMap<String, String>
+}
+
+impl JournalMapping {
+    pub fn new() -> JournalMapping {
+        JournalMapping::default()
+    }
+
+    pub fn from_toml(mapping: &str) -> Result<JournalMapping, JournalImportError> {
+        toml::from_str(mapping).map_err(|error| JournalImportError::Mapping(error.to_string()))
+    }
+
+    pub fn account(mut self, name: &str, account_id: &str) -> JournalMapping {
+        self.accounts.insert(name.to_string(), account_id.to_string());
+        self
+    }
+
+    pub fn category(mut self, name: &str, category_id: &str) -> JournalMapping {
+        self.categories.insert(name.to_string(), category_id.to_string());
+        self
+    }
+
+    pub fn uncategorized(mut self, name: &str) -> JournalMapping {
+        self.uncategorized.push(name.to_string());
+        self
+    }
+
+    fn target(&self, account: &str, line: usize) -> Result<Target<'_>, JournalImportError> {
+        if let Some(account_id) = self.accounts.get(account) {
+            return Ok(Target::Account(account_id));
+        }
+        if let Some(category_id) = self.categories.get(account) {
+            return Ok(Target::Category(Some(category_id)));
+        }
+        if self.uncategorized.iter().any(|name| name == account) {
+            return Ok(Target::Category(None));
+        }
+
+        Err(JournalImportError::UnmappedAccount { line, account: account.to_string() })
+    }
+}
+
+enum Target<'a> {
+    Account(&'a str),
+    Category(Option<&'a str>)
+}
+
+#[derive(Debug)]
+pub enum JournalImportError {
+    Mapping(String),
+    Read(io::Error),
+    InvalidDate { line: usize, value: String },
+    InvalidAmount { line: usize, value: String },
+    UnbalancedEntry { line: usize },
+    UnmappedAccount { line: usize, account: String },
+    // Every posting of the entry maps to a category, so there is no account to book it in.
+    NoAccount { line: usize },
+    UnknownAccount { line: usize, account_id: String },
+    Accounts(ErrorResponse)
+}
+
+impl fmt::Display for JournalImportError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JournalImportError::Mapping(detail) => write!(formatter, "invalid journal mapping: {}", detail),
+            JournalImportError::Read(error) => write!(formatter, "could not read journal: {}", error),
+            JournalImportError::InvalidDate { line, value } => write!(formatter, "line {}: `{}` is not a date", line, value),
+            JournalImportError::InvalidAmount { line, value } => write!(formatter, "line {}: `{}` is not an amount", line, value),
+            JournalImportError::UnbalancedEntry { line } => write!(formatter, "line {}: entry does not balance", line),
+            JournalImportError::UnmappedAccount { line, account } => write!(formatter, "line {}: `{}` is not in the mapping", line, account),
+            JournalImportError::NoAccount { line } => write!(formatter, "line {}: entry has no posting to a YNAB account", line),
+            JournalImportError::UnknownAccount { line, account_id } => write!(formatter, "line {}: account {} is not in the budget", line, account_id),
+            JournalImportError::Accounts(error_response) => write!(formatter, "could not fetch accounts: {}", error_response.error.detail)
+        }
+    }
+}
+
+impl std::error::Error for JournalImportError {}
+
+struct PendingEntry {
+    line:       usize,
+    date:       NaiveDate,
+    cleared:    bool,
+    payee:      Option<String>,
+    comments:   Vec<String>,
+    ynab_id:    Option<String>,
+    postings:   Vec<ParsedPosting>
+}
+
+struct ParsedPosting {
+    account:    String,
+    amount:     Option<i64>,
+    memo:       Option<String>
+}
+
+// Reads the transactions of a journal. Other directives (`open`, `price`, `account`, automated
+// transactions and so on) are skipped, as are virtual postings and zero postings that only carry
+// a balance assertion. One posting per entry may leave its amount out.
+pub fn parse_journal<R: Read>(mut reader: R, format: JournalFormat) -> Result<Vec<JournalEntry>, JournalImportError> {
+    let mut bytes: Vec<u8> = vec![];
+    reader.read_to_end(&mut bytes).map_err(JournalImportError::Read)?;
+    let contents: String = String::from_utf8_lossy(&bytes).into_owned();
+
+    let mut entries: Vec<JournalEntry> = vec![];
+    let mut pending: Option<PendingEntry> = None;
+
+    for (index, line) in contents.lines().enumerate() {
+        let line_number: usize = index + 1;
+        let line: &str = line.trim_end();
+
+        if line.is_empty() || !line.starts_with([' ', '\t']) {
+            if let Some(entry) = pending.take() {
+                entries.extend(finish(entry)?);
+            }
+            if line.starts_with(|character: char| character.is_ascii_digit()) {
+                pending = header(line, line_number, format)?;
+            }
+            continue;
+        }
+
+        let entry: &mut PendingEntry = match pending.as_mut() {
+            Some(entry) => entry,
+            None => continue
+        };
+        let body: &str = line.trim();
+
+        // Comments and metadata before the first posting belong to the entry, later ones to the
+        // posting above them.
+        if let Some(comment) = body.strip_prefix(';') {
+            let comment: &str = comment.trim_start_matches(';').trim();
+            if format == JournalFormat::Ledger && !comment.is_empty() {
+                match (comment.strip_prefix("ynab-id:"), entry.postings.last_mut()) {
+                    (Some(id), None) => entry.ynab_id = Some(id.trim().to_string()),
+                    (Some(_), Some(_)) => {},
+                    (None, None) => entry.comments.push(comment.to_string()),
+                    (None, Some(posting)) => posting.memo = Some(append(posting.memo.take(), comment))
+                }
+            }
+            continue;
+        }
+
+        if format == JournalFormat::Beancount && body.starts_with(|character: char| character.is_ascii_lowercase()) {
+            let (key, value) = body.split_once(':').unwrap_or((body, ""));
+            match (key, entry.postings.last_mut()) {
+                ("ynab-id", None) => entry.ynab_id = Some(unquote(value.trim())),
+                ("memo", Some(posting)) => posting.memo = Some(unquote(value.trim())),
+                _ => {}
+            }
+            continue;
+        }
+
+        if let Some(posting) = posting(body, line_number, format)? {
+            entry.postings.push(posting);
+        }
+    }
+
+    if let Some(entry) = pending.take() {
+        entries.extend(finish(entry)?);
+    }
+
+    Ok(entries)
+}
+
+// Ledger headers are `DATE[=DATE] [*|!] [(CODE)] PAYEE [; NOTE]`, Beancount ones
+// `DATE (*|!|txn) ["PAYEE"] "NARRATION" [#tags]`. Returns `None` for other dated directives.
+fn header(line: &str, line_number: usize, format: JournalFormat) -> Result<Option<PendingEntry>, JournalImportError> {
+    let (date_text, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    let date_text: &str = date_text.split('=').next().unwrap_or_default();
+    let date: NaiveDate = ["%Y-%m-%d", "%Y/%m/%d", "%Y.%m.%d"].iter()
+        .find_map(|format| NaiveDate::parse_from_str(date_text, format).ok())
+        .ok_or_else(|| JournalImportError::InvalidDate { line: line_number, value: date_text.to_string() })?;
+    let rest: &str = rest.trim();
+
+    let mut entry: PendingEntry = PendingEntry { line: line_number, date, cleared: false, payee: None, comments: vec![], ynab_id: None, postings: vec![] };
+
+    match format {
+        JournalFormat::Ledger => {
+            let (description, note) = split_comment(rest);
+            let description: &str = match description.strip_prefix('*') {
+                Some(description) => {
+                    entry.cleared = true;
+                    description
+                },
+                None => description.strip_prefix('!').unwrap_or(description)
+            };
+            let description: &str = description.trim_start();
+            let description: &str = match description.strip_prefix('(').and_then(|code| code.split_once(')')) {
+                Some((_, description)) => description,
+                None => description
+            };
+
+            entry.payee = Some(description.trim().to_string()).filter(|payee| !payee.is_empty());
+            entry.comments.extend(note.map(str::to_string).filter(|note| !note.is_empty()));
+        },
+        JournalFormat::Beancount => {
+            let (flag, strings) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+            entry.cleared = match flag {
+                "*" | "txn" => true,
+                "!" => false,
+                _ => return Ok(None)
+            };
+
+            let mut strings: Vec<String> = quoted_strings(strings);
+            let narration: Option<String> = strings.pop();
+            entry.payee = strings.pop().filter(|payee| !payee.is_empty());
+            entry.comments.extend(narration.filter(|narration| !narration.is_empty()));
+        }
+    }
+
+    Ok(Some(entry))
+}
+
+fn posting(body: &str, line_number: usize, format: JournalFormat) -> Result<Option<ParsedPosting>, JournalImportError> {
+    let body: &str = body.strip_prefix(['*', '!']).map(str::trim_start).unwrap_or(body);
+    if body.starts_with(['(', '[']) {
+        return Ok(None);
+    }
+
+    let (body, comment) = split_comment(body);
+    let memo: Option<String> = comment.filter(|comment| format == JournalFormat::Ledger && !comment.is_empty()).map(str::to_string);
+    let separator: Option<usize> = match format {
+        JournalFormat::Ledger => body.find("  ").into_iter().chain(body.find('\t')).min(),
+        JournalFormat::Beancount => body.find(char::is_whitespace)
+    };
+    let (account, amount) = match separator {
+        Some(position) => (body[..position].trim(), body[position..].trim()),
+        None => (body.trim(), "")
+    };
+
+    let asserted: bool = amount.contains('=');
+    let amount: &str = amount.split(['=', '@', '{']).next().unwrap_or_default().trim();
+    if amount.is_empty() {
+        return Ok(if asserted { None } else { Some(ParsedPosting { account: account.to_string(), amount: None, memo }) });
+    }
+
+    let milliunits: i64 = parse_milliunits(amount, '.').ok_or_else(|| JournalImportError::InvalidAmount { line: line_number, value: amount.to_string() })?;
+    if asserted && milliunits == 0 {
+        return Ok(None);
+    }
+
+    Ok(Some(ParsedPosting { account: account.to_string(), amount: Some(milliunits), memo }))
+}
+
+// Fills in an elided amount and checks that the entry balances. Entries left without postings,
+// such as bare balance assertions, are dropped.
+fn finish(entry: PendingEntry) -> Result<Option<JournalEntry>, JournalImportError> {
+    if entry.postings.is_empty() {
+        return Ok(None);
+    }
+
+    let total: i64 = entry.postings.iter().filter_map(|posting| posting.amount).sum();
+    let elided: usize = entry.postings.iter().filter(|posting| posting.amount.is_none()).count();
+    if elided > 1 || (elided == 0 && total != 0) {
+        return Err(JournalImportError::UnbalancedEntry { line: entry.line });
+    }
+
+    Ok(Some(JournalEntry {
+        line:       entry.line,
+        date:       entry.date,
+        cleared:    entry.cleared,
+        payee:      entry.payee,
+        memo:       Some(entry.comments.join(" ")).filter(|memo| !memo.is_empty()),
+        ynab_id:    entry.ynab_id,
+        postings:   entry.postings.into_iter().map(|posting| JournalPosting {
+            account:    posting.account,
+            amount:     posting.amount.unwrap_or(-total),
+            memo:       posting.memo
+        }).collect()
+    }))
+}
+
+// Turns journal entries into transactions. The first posting to a mapped YNAB account decides the
+// account and amount; every other posting becomes the category, a transfer to another mapped
+// account, or, with more than one, a split line. Entries carrying a `ynab-id` are skipped.
+pub fn to_save_transactions(entries: &[JournalEntry], mapping: &JournalMapping, accounts: &[Account]) -> Result<Vec<SaveTransaction>, JournalImportError> {
+    let mut occurrences: HashMap<String, u32> = HashMap::new();
+    let mut transactions: Vec<SaveTransaction> = vec![];
+
+    for entry in entries.iter().filter(|entry| entry.ynab_id.is_none()) {
+        let targets: Vec<Target> = entry.postings.iter()
+            .map(|posting| mapping.target(&posting.account, entry.line))
+            .collect::<Result<Vec<Target>, JournalImportError>>()?;
+        let (primary, account_id): (usize, &str) = targets.iter().enumerate()
+            .find_map(|(index, target)| match target {
+                Target::Account(account_id) => Some((index, *account_id)),
+                Target::Category(_) => None
+            })
+            .ok_or(JournalImportError::NoAccount { line: entry.line })?;
+
+        let lines: Vec<SaveSubTransaction> = entry.postings.iter().zip(&targets).enumerate()
+            .filter(|(index, _)| *index != primary)
+            .map(|(_, (posting, target))| {
+                let (payee_id, category_id): (Option<String>, Option<String>) = match target {
+                    Target::Account(transfer_account_id) => {
+                        let account: &Account = accounts.iter()
+                            .find(|account| account.id == *transfer_account_id)
+                            .ok_or_else(|| JournalImportError::UnknownAccount { line: entry.line, account_id: transfer_account_id.to_string() })?;
+                        (Some(account.transfer_payee_id.clone()), None)
+                    },
+                    Target::Category(category_id) => (None, category_id.map(str::to_string))
+                };
+                Ok(SaveSubTransaction {
+                    amount:         -posting.amount,
+                    payee_id,
+                    payee_name:     None,
+                    category_id,
+                    memo:           posting.memo.as_deref().map(|memo| truncate(memo, MAX_MEMO_LENGTH))
+                })
+            })
+            .collect::<Result<Vec<SaveSubTransaction>, JournalImportError>>()?;
+
+        let mut import_id: String = entry.import_id();
+        let occurrence: &mut u32 = occurrences.entry(import_id.clone()).or_insert(0);
+        *occurrence += 1;
+        if *occurrence > 1 {
+            import_id = format!("{}:{}", import_id, occurrence);
+        }
+
+        let single: Option<&SaveSubTransaction> = if lines.len() == 1 { lines.first() } else { None };
+        transactions.push(SaveTransaction {
+            account_id:         account_id.to_string(),
+            date:               entry.date.format("%Y-%m-%d").to_string(),
+            amount:             entry.postings[primary].amount,
+            payee_id:           single.and_then(|line| line.payee_id.clone()),
+            payee_name:         if single.is_some_and(|line| line.payee_id.is_some()) { None } else { entry.payee.as_deref().map(|payee| truncate(payee, MAX_PAYEE_NAME_LENGTH)) },
+            category_id:        single.and_then(|line| line.category_id.clone()),
+            memo:               entry.memo.as_deref().map(|memo| truncate(memo, MAX_MEMO_LENGTH)),
+            cleared:            Some(if entry.cleared { ClearedStatus::Cleared } else { ClearedStatus::Uncleared }),
+            approved:           Some(false),
+            flag_color:         None,
+            import_id:          Some(import_id),
+            subtransactions:    if single.is_some() { None } else { Some(lines) }
+        });
+    }
+
+    Ok(transactions)
+}
+
+pub fn read_transactions<R: Read>(reader: R, format: JournalFormat, mapping: &JournalMapping, accounts: &[Account]) -> Result<Vec<SaveTransaction>, JournalImportError> {
+    let entries: Vec<JournalEntry> = parse_journal(reader, format)?;
+    to_save_transactions(&entries, mapping, accounts)
+}
+
+// Fetches the budget's accounts for their transfer payees, then imports the journal.
+pub async fn import_journal<R: Read>(client: &Client, budget_id: &str, reader: R, format: JournalFormat, mapping: &JournalMapping) -> Result<Vec<ImportOutcome>, JournalImportError> {
+    let response: AccountsResponse = match client.get_account_list(budget_id, None).await {
+        Left(response) => response,
+        Right(error_response) => return Err(JournalImportError::Accounts(error_response))
+    };
+
+    let transactions: Vec<SaveTransaction> = read_transactions(reader, format, mapping, &response.data.accounts)?;
+    Ok(BatchImporter::new(client, budget_id).import(transactions).await)
+}
+
+// Splits `text ; comment` at the first semicolon that follows whitespace.
+fn split_comment(text: &str) -> (&str, Option<&str>) {
+    let position: Option<usize> = text.char_indices()
+        .find(|(position, character)| *character == ';' && (*position == 0 || text[..*position].ends_with(char::is_whitespace)))
+        .map(|(position, _)| position);
+
+    match position {
+        Some(position) => (text[..position].trim_end(), Some(text[position + 1..].trim())),
+        None => (text, None)
+    }
+}
+
+fn quoted_strings(text: &str) -> Vec<String> {
+    let mut strings: Vec<String> = vec![];
+    let mut characters = text.chars();
+
+    while let Some(character) = characters.next() {
+        match character {
+            '"' => {
+                let mut string: String = String::new();
+                while let Some(character) = characters.next() {
+                    match character {
+                        '\\' => string.extend(characters.next()),
+                        '"' => break,
+                        _ => string.push(character)
+                    }
+                }
+                strings.push(string);
+            },
+            ';' => break,
+            _ => {}
+        }
+    }
+
+    strings
+}
+
+fn append(text: Option<String>, addition: &str) -> String {
+    match text {
+        Some(text) => format!("{} {}", text, addition),
+        None => addition.to_string()
+    }
+}
+
+fn unquote(text: &str) -> String {
+    quoted_strings(text).into_iter().next().unwrap_or_else(|| text.to_string())
+}
@@ -0,0 +1,143 @@
+// A forgiving element tree for the SGML and XML statement formats. It reads leaf text and
+// nesting only: attributes, comments and processing instructions are skipped and namespace
+// prefixes are dropped, so callers look elements up by local name, ignoring case.
+
+#[derive(Debug, Default)]
+pub(crate) struct Element {
+    pub(crate) name:        String,
+    pub(crate) text:        Option<String>,
+    pub(crate) children:    Vec<Element>
+}
+
+impl Element {
+    pub(crate) fn child(&self, name: &str) -> Option<&Element> {
+        self.children.iter().find(|child| child.name.eq_ignore_ascii_case(name))
+    }
+
+    pub(crate) fn children<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> + 'a {
+        self.children.iter().filter(move |child| child.name.eq_ignore_ascii_case(name))
+    }
+
+    // Follows a chain of child names, e.g. `["BookgDt", "Dt"]`.
+    pub(crate) fn descendant(&self, path: &[&str]) -> Option<&Element> {
+        path.iter().try_fold(self, |element, name| element.child(name))
+    }
+
+    pub(crate) fn text(&self, name: &str) -> Option<&str> {
+        self.child(name).and_then(|child| child.text.as_deref())
+    }
+
+    pub(crate) fn path_text(&self, path: &[&str]) -> Option<&str> {
+        self.descendant(path).and_then(|element| element.text.as_deref())
+    }
+
+    pub(crate) fn find_all<'a>(&'a self, names: &[&str], found: &mut Vec<&'a Element>) {
+        for child in &self.children {
+            if names.iter().any(|name| child.name.eq_ignore_ascii_case(name)) {
+                found.push(child);
+            } else {
+                child.find_all(names, found);
+            }
+        }
+    }
+}
+
+// Builds an element tree that works for SGML and XML alike: an element followed by text is a
+// leaf and ends right there, anything else is an aggregate that runs until its end tag. An
+// element without text that is never closed was an empty SGML leaf such as `<MEMO>`, so what
+// followed it goes back to its parent.
+pub(crate) fn parse_elements(contents: &str) -> Element {
+    let mut stack: Vec<Element> = vec![Element::default()];
+    let mut rest: &str = contents;
+
+    while let Some(open) = rest.find('<') {
+        let close: usize = match rest[open..].find('>') {
+            Some(close) => open + close,
+            None => break
+        };
+        let tag: &str = rest[open + 1..close].trim();
+        rest = &rest[close + 1..];
+
+        let text_end: usize = rest.find('<').unwrap_or(rest.len());
+        let text: &str = rest[..text_end].trim();
+
+        if tag.starts_with('?') || tag.starts_with('!') || tag.ends_with('/') {
+            continue;
+        }
+
+        if let Some(name) = tag.strip_prefix('/') {
+            let name: &str = local_name(name);
+            if let Some(depth) = stack.iter().rposition(|element| element.name.eq_ignore_ascii_case(name)).filter(|depth| *depth > 0) {
+                while stack.len() > depth + 1 {
+                    let mut element: Element = stack.pop().unwrap();
+                    let siblings: Vec<Element> = std::mem::take(&mut element.children);
+                    let parent: &mut Element = stack.last_mut().unwrap();
+                    parent.children.push(element);
+                    parent.children.extend(siblings);
+                }
+                let element: Element = stack.pop().unwrap();
+                stack.last_mut().unwrap().children.push(element);
+            }
+            continue;
+        }
+
+        let name: String = local_name(tag.split_whitespace().next().unwrap_or_default()).to_string();
+        if text.is_empty() {
+            stack.push(Element { name, text: None, children: vec![] });
+        } else {
+            stack.last_mut().unwrap().children.push(Element { name, text: Some(decode_entities(text)), children: vec![] });
+        }
+    }
+
+    while stack.len() > 1 {
+        let element: Element = stack.pop().unwrap();
+        stack.last_mut().unwrap().children.push(element);
+    }
+
+    stack.pop().unwrap()
+}
+
+fn local_name(tag: &str) -> &str {
+    let tag: &str = tag.trim();
+    tag.rsplit(':').next().unwrap_or(tag)
+}
+
+fn decode_entities(text: &str) -> String {
+    let mut decoded: String = String::new();
+    let mut rest: &str = text;
+
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let entity: Option<(char, usize)> = rest.find(';').filter(|end| *end <= 10).and_then(|end| {
+            let character: Option<char> = match &rest[1..end] {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some(' '),
+                numeric => numeric.strip_prefix("#x").or_else(|| numeric.strip_prefix("#X"))
+                    .map(|hex| u32::from_str_radix(hex, 16).ok())
+                    .unwrap_or_else(|| numeric.strip_prefix('#').and_then(|decimal| decimal.parse().ok()))
+                    .and_then(char::from_u32)
+            };
+            character.map(|character| (character, end))
+        });
+
+        match entity {
+            Some((character, end)) => {
+                decoded.push(character);
+                rest = &rest[end + 1..];
+            },
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    decoded.push_str(rest);
+    decoded
+}
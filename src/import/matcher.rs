@@ -0,0 +1,132 @@
+use std::collections::HashSet;
+
+use chrono::NaiveDate;
+
+use crate::models::{ClearedStatus, SaveTransaction, TransactionDetail};
+
+// YNAB's own importer looks 10 days either side of the imported date.
+pub const DEFAULT_DATE_WINDOW_DAYS: i64 = 10;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MatchOptions {
+    pub date_window_days:   i64,
+    // Minimum bigram similarity (0.0 to 1.0) between payee names, when both sides have one.
+    pub payee_similarity:   Option<f64>
+}
+
+impl Default for MatchOptions {
+    fn default() -> MatchOptions {
+        MatchOptions { date_window_days: DEFAULT_DATE_WINDOW_DAYS, payee_similarity: None }
+    }
+}
+
+impl MatchOptions {
+    pub fn new() -> MatchOptions {
+        MatchOptions::default()
+    }
+
+    pub fn date_window_days(mut self, date_window_days: i64) -> MatchOptions {
+        self.date_window_days = date_window_days.max(0);
+        self
+    }
+
+    pub fn payee_similarity(mut self, payee_similarity: f64) -> MatchOptions {
+        self.payee_similarity = Some(payee_similarity.clamp(0.0, 1.0));
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransactionMatch {
+    // Index of the imported row.
+    pub row:                usize,
+    pub transaction_id:     String,
+    pub days_apart:         i64,
+    pub payee_similarity:   Option<f64>
+}
+
+// A manual entry can absorb an import if it is an uncleared, unimported, unmatched transaction
+// in the same account with exactly the same amount.
+fn is_candidate(imported: &SaveTransaction, existing: &TransactionDetail) -> bool {
+    !existing.deleted
+        && existing.account_id == imported.account_id
+        && existing.amount == imported.amount
+        && matches!(existing.cleared, ClearedStatus::Uncleared)
+        && existing.import_id.is_none()
+        && existing.matched_transaction_id.is_none()
+        && existing.transfer_account_id.is_none()
+}
+
+// Pairs imported rows with existing manual entries. Rows are matched in input order, each
+// taking the closest candidate by date, then the most similar payee, then the lowest id, and
+// every existing transaction is matched at most once, so the same inputs always produce the
+// same pairs.
+pub fn match_transactions(imported: &[SaveTransaction], existing: &[TransactionDetail], options: &MatchOptions) -> Vec<TransactionMatch> {
+    let mut taken: HashSet<&str> = HashSet::new();
+    let mut matches: Vec<TransactionMatch> = vec![];
+
+    for (row, transaction) in imported.iter().enumerate() {
+        let date: NaiveDate = match NaiveDate::parse_from_str(&transaction.date, "%Y-%m-%d") {
+            Ok(date) => date,
+            Err(_) => continue
+        };
+
+        let best: Option<(&TransactionDetail, i64, Option<f64>)> = existing.iter()
+            .filter(|candidate| !taken.contains(candidate.id.as_str()) && is_candidate(transaction, candidate))
+            .filter_map(|candidate| {
+                let candidate_date: NaiveDate = NaiveDate::parse_from_str(&candidate.date, "%Y-%m-%d").ok()?;
+                let days_apart: i64 = (candidate_date - date).num_days().abs();
+                if days_apart > options.date_window_days {
+                    return None;
+                }
+
+                let similarity: Option<f64> = match (transaction.payee_name.as_deref(), candidate.payee_name.as_deref()) {
+                    (Some(imported_payee), Some(existing_payee)) => Some(similarity(imported_payee, existing_payee)),
+                    _ => None
+                };
+                if options.payee_similarity.is_some_and(|threshold| similarity.is_some_and(|similarity| similarity < threshold)) {
+                    return None;
+                }
+
+                Some((candidate, days_apart, similarity))
+            })
+            .min_by(|left, right| {
+                left.1.cmp(&right.1)
+                    .then(right.2.unwrap_or(0.0).total_cmp(&left.2.unwrap_or(0.0)))
+                    .then(left.0.id.cmp(&right.0.id))
+            });
+
+        if let Some((candidate, days_apart, payee_similarity)) = best {
+            taken.insert(candidate.id.as_str());
+            matches.push(TransactionMatch { row, transaction_id: candidate.id.clone(), days_apart, payee_similarity });
+        }
+    }
+
+    matches
+}
+
+// Dice coefficient over the character bigrams of the lower-cased alphanumeric characters, so
+// `AMZN Mktp US` and `Amazon` still share some ground while word order and punctuation don't matter.
+pub fn similarity(left: &str, right: &str) -> f64 {
+    let bigrams = |text: &str| -> Vec<(char, char)> {
+        let characters: Vec<char> = text.chars().filter(|character| character.is_alphanumeric()).flat_map(char::to_lowercase).collect();
+        characters.windows(2).map(|pair| (pair[0], pair[1])).collect()
+    };
+
+    let left: Vec<(char, char)> = bigrams(left);
+    let mut right: Vec<(char, char)> = bigrams(right);
+    if left.is_empty() || right.is_empty() {
+        return 0.0;
+    }
+
+    let total: usize = left.len() + right.len();
+    let mut shared: usize = 0;
+    for bigram in &left {
+        if let Some(position) = right.iter().position(|candidate| candidate == bigram) {
+            right.swap_remove(position);
+            shared += 1;
+        }
+    }
+
+    (2 * shared) as f64 / total as f64
+}
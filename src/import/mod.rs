@@ -0,0 +1,328 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{Duration, Local, NaiveDate};
+use either::{Left, Right};
+
+use crate::builder::{validate_save_transaction, ValidationError, MAX_IMPORT_ID_LENGTH, MAX_PAYEE_NAME_LENGTH};
+use crate::client::Client;
+use crate::import::matcher::{match_transactions, MatchOptions, TransactionMatch};
+use crate::import::payee_rules::{PayeeRules, PayeeTarget, RewriteReport};
+use crate::models::{
+    ClearedStatus, ErrorResponse, PatchTransactionsWrapper, PostTransactionsWrapper, SaveTransaction, SaveTransactionWithId, TransactionDetail
+};
+use crate::query::TransactionQuery;
+
+pub mod camt;
+pub mod csv;
+pub mod journal;
+mod markup;
+pub mod matcher;
+pub mod mt940;
+pub mod ofx;
+pub mod payee_rules;
+pub mod qif;
+pub mod statement;
+
+pub const DEFAULT_BATCH_SIZE: usize = 100;
+
+// Parses a bank formatted amount such as `-1.234,56`, `(12.50)`, `12,50-` or `EUR 7.00` into
+// milliunits. Everything apart from digits, the sign and the decimal separator is ignored, which
+// also drops thousands separators and currency symbols.
+pub fn parse_milliunits(text: &str, decimal_separator: char) -> Option<i64> {
+    let trimmed: &str = text.trim();
+    let mut negative: bool = trimmed.starts_with('(') && trimmed.ends_with(')');
+    let mut digits: String = String::new();
+
+    for (position, character) in trimmed.char_indices() {
+        if character.is_ascii_digit() {
+            digits.push(character);
+        } else if character == decimal_separator {
+            digits.push('.');
+        } else if character == '-' {
+            if !digits.is_empty() && position + 1 != trimmed.len() {
+                return None;
+            }
+            negative = true;
+        }
+    }
+
+    let (integer, fraction) = digits.split_once('.').unwrap_or((digits.as_str(), ""));
+    if (integer.is_empty() && fraction.is_empty()) || fraction.contains('.') || fraction.chars().skip(3).any(|digit| digit != '0') {
+        return None;
+    }
+
+    let integer: i64 = if integer.is_empty() { 0 } else { integer.parse().ok()? };
+    let fraction: i64 = format!("{:0<3}", fraction.chars().take(3).collect::<String>()).parse().ok()?;
+    let milliunits: i64 = integer.checked_mul(1000)?.checked_add(fraction)?;
+
+    if milliunits == 0 {
+        negative = false;
+    }
+
+    Some(if negative { -milliunits } else { milliunits })
+}
+
+// Cuts `text` down to `max_length` characters, the way the API's field limits count them.
+pub fn truncate(text: &str, max_length: usize) -> String {
+    text.trim().chars().take(max_length).collect::<String>().trim_end().to_string()
+}
+
+pub fn import_id(amount: i64, date: NaiveDate, occurrence: u32) -> String {
+    format!("YNAB:{}:{}:{}", amount, date.format("%Y-%m-%d"), occurrence)
+}
+
+// Uses a bank's own transaction reference as import id. References longer than the API allows
+// are replaced by `<prefix>:` and a stable FNV-1a hash of the reference.
+pub fn reference_import_id(prefix: &str, reference: &str) -> String {
+    if reference.chars().count() <= MAX_IMPORT_ID_LENGTH {
+        return reference.to_string();
+    }
+
+    format!("{}:{:016x}", prefix, fnv1a64(reference.as_bytes()))
+}
+
+pub(crate) fn fnv1a64(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| (hash ^ *byte as u64).wrapping_mul(0x100000001b3))
+}
+
+// Hands out import ids in YNAB's own `YNAB:<milliunit_amount>:<iso_date>:<occurrence>` scheme,
+// numbering repeated amount/date pairs the way YNAB's file import does. Use one generator per
+// account and feed it rows in statement order, so that reruns reproduce the same ids.
+#[derive(Debug, Clone, Default)]
+pub struct ImportIdGenerator {
+    occurrences: HashMap<(i64, NaiveDate), u32>
+}
+
+impl ImportIdGenerator {
+    pub fn new() -> ImportIdGenerator {
+        ImportIdGenerator::default()
+    }
+
+    pub fn next(&mut self, amount: i64, date: NaiveDate) -> String {
+        let occurrence: &mut u32 = self.occurrences.entry((amount, date)).or_insert(0);
+        *occurrence += 1;
+        import_id(amount, date, *occurrence)
+    }
+
+    // Fills in `import_id` unless the transaction already carries one. Returns `None` if the
+    // transaction's date is not an ISO date.
+    pub fn assign(&mut self, transaction: &mut SaveTransaction) -> Option<String> {
+        if transaction.import_id.is_none() {
+            let date: NaiveDate = NaiveDate::parse_from_str(&transaction.date, "%Y-%m-%d").ok()?;
+            transaction.import_id = Some(self.next(transaction.amount, date));
+        }
+
+        transaction.import_id.clone()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum ImportOutcome {
+    Created(Box<TransactionDetail>),
+    // An existing manual entry that was cleared and given the row's import id.
+    Matched(Box<TransactionDetail>),
+    Duplicate { import_id: String },
+    Invalid(ValidationError),
+    Failed(ErrorResponse)
+}
+
+// An input row's position and the transaction it will send.
+type Row = (usize, SaveTransaction);
+
+pub struct BatchImporter<'a> {
+    client:         &'a Client,
+    budget_id:      String,
+    batch_size:     usize,
+    payee_rules:    Option<&'a PayeeRules>,
+    matching:       Option<MatchOptions>
+}
+
+impl<'a> BatchImporter<'a> {
+    pub fn new(client: &'a Client, budget_id: &str) -> BatchImporter<'a> {
+        BatchImporter { client, budget_id: budget_id.to_string(), batch_size: DEFAULT_BATCH_SIZE, payee_rules: None, matching: None }
+    }
+
+    pub fn batch_size(mut self, batch_size: usize) -> BatchImporter<'a> {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    // Rewrites raw payee names with `payee_rules` before the rows are validated and sent.
+    pub fn payee_rules(mut self, payee_rules: &'a PayeeRules) -> BatchImporter<'a> {
+        self.payee_rules = Some(payee_rules);
+        self
+    }
+
+    // Matches rows against uncleared manual entries in their account before creating anything.
+    // Matched entries are cleared and take over the row's import id instead of being duplicated.
+    pub fn match_existing(mut self, options: MatchOptions) -> BatchImporter<'a> {
+        self.matching = Some(options);
+        self
+    }
+
+    // Creates `transactions` in batches and returns one outcome per input row, in input order.
+    // Rows without an import id get one from a per-account `ImportIdGenerator`, so rerunning an
+    // import reports the rows as duplicates instead of creating them twice. A row repeating an
+    // earlier row's import id in the same account is reported as a duplicate without being sent.
+    pub async fn import(&self, mut transactions: Vec<SaveTransaction>) -> Vec<ImportOutcome> {
+        if let Some(payee_rules) = self.payee_rules {
+            // A rule's name can expand captures past the API's limit, so cut it down the way the
+            // parsers cut raw names.
+            let report: RewriteReport = payee_rules.apply(&mut transactions);
+            for row in report.matched().filter(|row| matches!(row.target, Some(PayeeTarget::PayeeName(_)))) {
+                let transaction: &mut SaveTransaction = &mut transactions[row.row];
+                transaction.payee_name = transaction.payee_name.as_deref().map(|payee_name| truncate(payee_name, MAX_PAYEE_NAME_LENGTH));
+            }
+        }
+
+        let today: NaiveDate = Local::now().date_naive();
+        let mut generators: HashMap<String, ImportIdGenerator> = HashMap::new();
+        let mut outcomes: Vec<Option<ImportOutcome>> = vec![None; transactions.len()];
+        let mut pending: Vec<Row> = vec![];
+        let mut seen: HashSet<(String, String)> = HashSet::new();
+
+        for (index, mut transaction) in transactions.into_iter().enumerate() {
+            if let Err(error) = validate_save_transaction(&transaction, today) {
+                outcomes[index] = Some(ImportOutcome::Invalid(error));
+                continue;
+            }
+
+            // The API would create the first row and answer both with the same transaction.
+            if let Some(import_id) = generators.entry(transaction.account_id.clone()).or_default().assign(&mut transaction) {
+                if !seen.insert((transaction.account_id.clone(), import_id.clone())) {
+                    outcomes[index] = Some(ImportOutcome::Duplicate { import_id });
+                    continue;
+                }
+            }
+            pending.push((index, transaction));
+        }
+
+        if let Some(options) = &self.matching {
+            pending = self.match_pending(pending, options, &mut outcomes).await;
+        }
+
+        for batch in pending.chunks(self.batch_size) {
+            let wrapper: PostTransactionsWrapper = PostTransactionsWrapper {
+                transaction:    None,
+                transactions:   Some(batch.iter().map(|(_, transaction)| transaction.clone()).collect())
+            };
+
+            match self.client.create_transactions(&self.budget_id, &wrapper).await {
+                Left(response) => {
+                    let duplicates: HashSet<String> = response.data.duplicate_import_ids.unwrap_or_default().into_iter().collect();
+                    let created: Vec<TransactionDetail> = response.data.transactions
+                        .unwrap_or_default()
+                        .into_iter()
+                        .chain(response.data.transaction)
+                        .collect();
+
+                    for (index, transaction) in batch {
+                        let import_id: String = transaction.import_id.clone().unwrap_or_default();
+                        let detail: Option<&TransactionDetail> = created.iter().find(|detail| {
+                            detail.account_id == transaction.account_id && detail.import_id.as_ref() == Some(&import_id)
+                        });
+
+                        outcomes[*index] = Some(match detail {
+                            Some(detail) => ImportOutcome::Created(Box::new(detail.clone())),
+                            None if duplicates.contains(&import_id) => ImportOutcome::Duplicate { import_id },
+                            None => ImportOutcome::Failed(missing_transaction(&format!("import id `{}`", import_id)))
+                        });
+                    }
+                },
+                Right(error_response) => {
+                    for (index, _) in batch {
+                        outcomes[*index] = Some(ImportOutcome::Failed(error_response.clone()));
+                    }
+                }
+            }
+        }
+
+        outcomes.into_iter().map(|outcome| outcome.expect("Every row has an outcome.")).collect()
+    }
+
+    // Settles the rows that match an existing entry, or that were imported before, and returns the rest.
+    async fn match_pending(&self, pending: Vec<Row>, options: &MatchOptions, outcomes: &mut [Option<ImportOutcome>]) -> Vec<Row> {
+        let mut accounts: Vec<String> = pending.iter().map(|(_, transaction)| transaction.account_id.clone()).collect();
+        accounts.sort();
+        accounts.dedup();
+
+        let mut unmatched: Vec<Row> = vec![];
+        let mut matched: Vec<(usize, SaveTransaction, String)> = vec![];
+
+        for account_id in accounts {
+            let rows: Vec<Row> = pending.iter().filter(|(_, transaction)| transaction.account_id == account_id).cloned().collect();
+            let mut query: TransactionQuery = TransactionQuery::new(&self.budget_id).account(&account_id);
+            if let Some(earliest) = rows.iter().filter_map(|(_, transaction)| NaiveDate::parse_from_str(&transaction.date, "%Y-%m-%d").ok()).min() {
+                query = query.since_date(earliest - Duration::days(options.date_window_days));
+            }
+
+            let existing: Vec<TransactionDetail> = match self.client.get_transactions(&query).await {
+                Left(response) => response.data.transactions,
+                Right(error_response) => {
+                    for (index, _) in rows {
+                        outcomes[index] = Some(ImportOutcome::Failed(error_response.clone()));
+                    }
+                    continue;
+                }
+            };
+
+            let imported: HashSet<&str> = existing.iter().filter(|detail| !detail.deleted).filter_map(|detail| detail.import_id.as_deref()).collect();
+            let (duplicates, rows): (Vec<Row>, Vec<Row>) = rows.into_iter()
+                .partition(|(_, transaction)| transaction.import_id.as_deref().is_some_and(|import_id| imported.contains(import_id)));
+            for (index, transaction) in duplicates {
+                outcomes[index] = Some(ImportOutcome::Duplicate { import_id: transaction.import_id.unwrap_or_default() });
+            }
+
+            let saves: Vec<SaveTransaction> = rows.iter().map(|(_, transaction)| transaction.clone()).collect();
+            let pairs: Vec<TransactionMatch> = match_transactions(&saves, &existing, options);
+            for (position, (index, transaction)) in rows.into_iter().enumerate() {
+                match pairs.iter().find(|pair| pair.row == position) {
+                    Some(pair) => matched.push((index, transaction, pair.transaction_id.clone())),
+                    None => unmatched.push((index, transaction))
+                }
+            }
+        }
+
+        for batch in matched.chunks(self.batch_size) {
+            let wrapper: PatchTransactionsWrapper = PatchTransactionsWrapper {
+                transactions: batch.iter().map(|(_, transaction, transaction_id)| SaveTransactionWithId {
+                    id:         Some(transaction_id.clone()),
+                    cleared:    Some(ClearedStatus::Cleared),
+                    import_id:  transaction.import_id.clone(),
+                    ..SaveTransactionWithId::default()
+                }).collect()
+            };
+
+            match self.client.update_transactions(&self.budget_id, &wrapper).await {
+                Left(response) => {
+                    let updated: Vec<TransactionDetail> = response.data.transactions.unwrap_or_default().into_iter().chain(response.data.transaction).collect();
+                    for (index, _, transaction_id) in batch {
+                        outcomes[*index] = Some(match updated.iter().find(|detail| detail.id == *transaction_id) {
+                            Some(detail) => ImportOutcome::Matched(Box::new(detail.clone())),
+                            None => ImportOutcome::Failed(missing_transaction(&format!("id `{}`", transaction_id)))
+                        });
+                    }
+                },
+                Right(error_response) => {
+                    for (index, _, _) in batch {
+                        outcomes[*index] = Some(ImportOutcome::Failed(error_response.clone()));
+                    }
+                }
+            }
+        }
+
+        unmatched.sort_by_key(|(index, _)| *index);
+        unmatched
+    }
+}
+
+// Synthesized on our side, so it carries no HTTP status as its id.
+fn missing_transaction(reference: &str) -> ErrorResponse {
+    ErrorResponse {
+        error: crate::models::ErrorDetail {
+            id:     "missing_transaction".to_string(),
+            name:   "missing_transaction".to_string(),
+            detail: format!("Response did not include a transaction for {}.", reference)
+        }
+    }
+}
@@ -0,0 +1,219 @@
+use std::fmt;
+use std::io::{self, Read};
+
+use chrono::{Datelike, NaiveDate};
+
+use crate::import::parse_milliunits;
+use crate::import::statement::{BankStatement, EntryStatus, StatementEntry};
+
+#[derive(Debug)]
+pub enum Mt940ImportError {
+    Read(io::Error),
+    OrphanedField { line: usize, tag: String },
+    InvalidField { line: usize, tag: String, value: String }
+}
+
+impl fmt::Display for Mt940ImportError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Mt940ImportError::Read(error) => write!(formatter, "could not read MT940: {}", error),
+            Mt940ImportError::OrphanedField { line, tag } => write!(formatter, "line {}: :{}: appears outside of a statement", line, tag),
+            Mt940ImportError::InvalidField { line, tag, value } => write!(formatter, "line {}: cannot read :{}: `{}`", line, tag, value)
+        }
+    }
+}
+
+impl std::error::Error for Mt940ImportError {}
+
+struct Field {
+    line:   usize,
+    tag:    String,
+    value:  String
+}
+
+// Reads SWIFT MT940 statements, and MT942 interim reports whose entries are still pending.
+// Every `:20:` field starts a new statement.
+pub fn parse_mt940<R: Read>(mut reader: R) -> Result<Vec<BankStatement>, Mt940ImportError> {
+    let mut bytes: Vec<u8> = vec![];
+    reader.read_to_end(&mut bytes).map_err(Mt940ImportError::Read)?;
+    let contents: String = String::from_utf8_lossy(&bytes).into_owned();
+
+    let mut statements: Vec<(BankStatement, bool)> = vec![];
+    for field in fields(&contents) {
+        if field.tag == "20" {
+            statements.push((BankStatement { id: Some(field.value.clone()), account: None, currency: None, entries: vec![] }, false));
+            continue;
+        }
+
+        let (statement, interim) = statements.last_mut().ok_or_else(|| Mt940ImportError::OrphanedField { line: field.line, tag: field.tag.clone() })?;
+        match field.tag.as_str() {
+            "25" => statement.account = Some(field.value.clone()),
+            "60F" | "60M" => statement.currency = field.value.get(7..10).map(str::to_string),
+            "34F" => {
+                *interim = true;
+                statement.currency = field.value.get(..3).map(str::to_string);
+            },
+            "61" => statement.entries.push(entry(&field)?),
+            "86" => {
+                if let Some(entry) = statement.entries.last_mut() {
+                    let (counterparty, remittance) = information(&field.value);
+                    entry.counterparty = counterparty;
+                    entry.remittance = remittance;
+                }
+            },
+            _ => {}
+        }
+    }
+
+    Ok(statements.into_iter().map(|(mut statement, interim)| {
+        if interim {
+            statement.entries.iter_mut().for_each(|entry| entry.status = EntryStatus::Pending);
+        }
+        statement
+    }).collect())
+}
+
+// Splits the message into `:tag:value` fields, folding continuation lines into the value and
+// dropping the `{1:...}{2:...}{4:` envelope and the `-}` trailer.
+fn fields(contents: &str) -> Vec<Field> {
+    let mut fields: Vec<Field> = vec![];
+
+    for (index, line) in contents.lines().enumerate() {
+        let line: &str = line.trim_end();
+        let line: &str = match line.rfind("{4:") {
+            Some(start) => &line[start + 3..],
+            None => line
+        };
+
+        if line.is_empty() || line == "-" || line.starts_with("-}") || line.starts_with('{') {
+            continue;
+        }
+
+        let tag: Option<(&str, &str)> = line.strip_prefix(':').and_then(|rest| rest.split_once(':'))
+            .filter(|(tag, _)| (2..=3).contains(&tag.len()) && tag.chars().all(|character| character.is_ascii_alphanumeric()));
+
+        match (tag, fields.last_mut()) {
+            (Some((tag, value)), _) => fields.push(Field { line: index + 1, tag: tag.to_string(), value: value.to_string() }),
+            (None, Some(field)) => {
+                field.value.push('\n');
+                field.value.push_str(line);
+            },
+            (None, None) => {}
+        }
+    }
+
+    fields
+}
+
+// `:61:` reads `YYMMDD[MMDD](C|D|RC|RD)[funds code]amount` followed by a four character
+// transaction type, the customer reference and an optional `//bank reference`.
+fn entry(field: &Field) -> Result<StatementEntry, Mt940ImportError> {
+    let invalid = || Mt940ImportError::InvalidField { line: field.line, tag: field.tag.clone(), value: field.value.clone() };
+    let first_line: &str = field.value.lines().next().unwrap_or_default();
+
+    let value_date: NaiveDate = first_line.get(..6).and_then(|date| NaiveDate::parse_from_str(date, "%y%m%d").ok()).ok_or_else(invalid)?;
+    let mut rest: &str = &first_line[6..];
+
+    let booking_date: NaiveDate = match rest.get(..4).filter(|digits| digits.chars().all(|digit| digit.is_ascii_digit())) {
+        Some(digits) => {
+            rest = &rest[4..];
+            booking_date(value_date, digits).ok_or_else(invalid)?
+        },
+        None => value_date
+    };
+
+    // A reversed credit takes money out again, a reversed debit puts it back.
+    let debit: bool = if let Some(remaining) = rest.strip_prefix("RC") {
+        rest = remaining;
+        true
+    } else if let Some(remaining) = rest.strip_prefix("RD") {
+        rest = remaining;
+        false
+    } else if let Some(remaining) = rest.strip_prefix('D') {
+        rest = remaining;
+        true
+    } else if let Some(remaining) = rest.strip_prefix('C') {
+        rest = remaining;
+        false
+    } else {
+        return Err(invalid());
+    };
+
+    if rest.starts_with(|character: char| character.is_ascii_alphabetic()) {
+        rest = &rest[1..];
+    }
+
+    let amount_length: usize = rest.find(|character: char| !character.is_ascii_digit() && character != ',').unwrap_or(rest.len());
+    let magnitude: i64 = parse_milliunits(&rest[..amount_length], ',').ok_or_else(invalid)?;
+    let references: &str = rest[amount_length..].get(4..).unwrap_or_default();
+
+    // Only the bank reference after `//` identifies the entry. The customer reference before it is
+    // set by the sender and repeats, e.g. on every run of a standing order.
+    let reference: Option<&str> = references
+        .split_once("//")
+        .map(|(_, bank_reference)| bank_reference.trim())
+        .filter(|reference| !reference.is_empty() && *reference != "NONREF");
+
+    Ok(StatementEntry {
+        reference:      reference.map(str::to_string),
+        status:         EntryStatus::Booked,
+        booking_date,
+        value_date:     Some(value_date),
+        amount:         if debit { -magnitude } else { magnitude },
+        counterparty:   None,
+        remittance:     None
+    })
+}
+
+// The entry date only carries month and day, and may fall in the year before or after the value date.
+fn booking_date(value_date: NaiveDate, digits: &str) -> Option<NaiveDate> {
+    let month: u32 = digits[..2].parse().ok()?;
+    let day: u32 = digits[2..].parse().ok()?;
+    let year: i32 = match (value_date.month(), month) {
+        (1, 12) => value_date.year() - 1,
+        (12, 1) => value_date.year() + 1,
+        _ => value_date.year()
+    };
+
+    NaiveDate::from_ymd_opt(year, month, day)
+}
+
+// `:86:` is free text, or the German structured layout `GVC?00text?20purpose...?32name`,
+// where `?20`-`?29` and `?60`-`?63` hold the remittance information and `?32`-`?33` the
+// counterparty. SEPA purpose keys are stripped down to the `SVWZ+` text.
+fn information(value: &str) -> (Option<String>, Option<String>) {
+    let value: String = value.lines().collect::<Vec<&str>>().join("");
+    let separator: Option<char> = value.chars().nth(3).filter(|separator| {
+        value.chars().take(3).all(|digit| digit.is_ascii_digit()) && !separator.is_alphanumeric() && !separator.is_whitespace()
+    });
+
+    let separator: char = match separator {
+        Some(separator) => separator,
+        None => return (None, Some(value.trim().to_string()).filter(|value| !value.is_empty()))
+    };
+
+    let mut purpose: String = String::new();
+    let mut name: String = String::new();
+    for subfield in value.split(separator).skip(1) {
+        if !subfield.is_char_boundary(2) {
+            continue;
+        }
+
+        let (code, text) = subfield.split_at(2);
+        match code.parse::<u32>() {
+            Ok(20..=29) | Ok(60..=63) => purpose.push_str(text),
+            Ok(32..=33) => name.push_str(text),
+            _ => {}
+        }
+    }
+
+    if let Some(start) = purpose.find("SVWZ+") {
+        purpose = purpose[start + 5..].to_string();
+        if let Some(end) = ["ABWA+", "ABWE+", "IBAN+", "BIC+"].iter().filter_map(|key| purpose.find(key)).min() {
+            purpose.truncate(end);
+        }
+    }
+
+    let clean = |text: String| Some(text.trim().to_string()).filter(|text| !text.is_empty());
+    (clean(name), clean(purpose))
+}
@@ -0,0 +1,183 @@
+use std::fmt;
+use std::io::{self, Read};
+
+use chrono::NaiveDate;
+
+use crate::builder::{MAX_MEMO_LENGTH, MAX_PAYEE_NAME_LENGTH};
+use crate::client::Client;
+use crate::import::markup::{parse_elements, Element};
+use crate::import::{parse_milliunits, reference_import_id, truncate, BatchImporter, ImportOutcome};
+use crate::models::{Account, ClearedStatus, SaveTransaction};
+
+#[derive(Debug)]
+pub enum OfxImportError {
+    Read(io::Error),
+    NotOfx,
+    MissingField { aggregate: &'static str, field: &'static str },
+    InvalidDate { field: &'static str, value: String },
+    InvalidAmount { field: &'static str, value: String }
+}
+
+impl fmt::Display for OfxImportError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OfxImportError::Read(error) => write!(formatter, "could not read OFX: {}", error),
+            OfxImportError::NotOfx => write!(formatter, "input has no <OFX> element"),
+            OfxImportError::MissingField { aggregate, field } => write!(formatter, "{} is missing {}", aggregate, field),
+            OfxImportError::InvalidDate { field, value } => write!(formatter, "{} `{}` is not an OFX date", field, value),
+            OfxImportError::InvalidAmount { field, value } => write!(formatter, "{} `{}` is not an amount", field, value)
+        }
+    }
+}
+
+impl std::error::Error for OfxImportError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OfxTransaction {
+    pub transaction_type:   Option<String>,
+    pub posted:             NaiveDate,
+    pub amount:             i64,
+    pub fitid:              String,
+    pub name:               Option<String>,
+    pub memo:               Option<String>,
+    pub check_number:       Option<String>
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LedgerBalance {
+    pub amount: i64,
+    pub as_of:  NaiveDate
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reconciliation {
+    pub statement_balance:  i64,
+    pub cleared_balance:    i64,
+    pub as_of:              NaiveDate
+}
+
+impl Reconciliation {
+    // What the account's cleared balance is missing to match the bank, in milliunits.
+    pub fn difference(&self) -> i64 {
+        self.statement_balance - self.cleared_balance
+    }
+
+    pub fn is_balanced(&self) -> bool {
+        self.difference() == 0
+    }
+}
+
+// One bank (STMTRS) or credit card (CCSTMTRS) statement from an OFX file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OfxStatement {
+    pub account_id:     Option<String>,
+    pub currency:       Option<String>,
+    pub transactions:   Vec<OfxTransaction>,
+    pub ledger_balance: Option<LedgerBalance>
+}
+
+impl OfxStatement {
+    // Maps every STMTTRN to a transaction for `account_id`, using the FITID as import id.
+    pub fn to_save_transactions(&self, account_id: &str) -> Vec<SaveTransaction> {
+        self.transactions.iter().map(|transaction| SaveTransaction {
+            account_id:         account_id.to_string(),
+            date:               transaction.posted.format("%Y-%m-%d").to_string(),
+            amount:             transaction.amount,
+            payee_id:           None,
+            payee_name:         transaction.name.as_deref().map(|name| truncate(name, MAX_PAYEE_NAME_LENGTH)),
+            category_id:        None,
+            memo:               transaction.memo.as_deref().map(|memo| truncate(memo, MAX_MEMO_LENGTH)),
+            cleared:            Some(ClearedStatus::Cleared),
+            approved:           Some(false),
+            flag_color:         None,
+            import_id:          Some(fitid_import_id(&transaction.fitid)),
+            subtransactions:    None
+        }).collect()
+    }
+
+    // Compares LEDGERBAL with the account's cleared balance. `None` if the statement has no LEDGERBAL.
+    pub fn reconcile(&self, account: &Account) -> Option<Reconciliation> {
+        self.ledger_balance.as_ref().map(|ledger_balance| Reconciliation {
+            statement_balance:  ledger_balance.amount,
+            cleared_balance:    account.cleared_balance,
+            as_of:              ledger_balance.as_of
+        })
+    }
+}
+
+// FITIDs are unique per account, so they make good import ids as they are.
+pub fn fitid_import_id(fitid: &str) -> String {
+    reference_import_id("OFX", fitid)
+}
+
+// Parses both OFX 1.x (SGML, leaf elements without end tags) and OFX 2.x (XML) files. QFX
+// files are OFX with a few extra Intuit elements and parse the same way.
+pub fn parse_ofx<R: Read>(mut reader: R) -> Result<Vec<OfxStatement>, OfxImportError> {
+    let mut bytes: Vec<u8> = vec![];
+    reader.read_to_end(&mut bytes).map_err(OfxImportError::Read)?;
+
+    // OFX 1.x files are usually Windows-1252, which only matters for payee names.
+    let contents: String = String::from_utf8_lossy(&bytes).into_owned();
+    let start: usize = contents.to_ascii_uppercase().find("<OFX>").ok_or(OfxImportError::NotOfx)?;
+    let root: Element = parse_elements(&contents[start..]);
+
+    let mut aggregates: Vec<&Element> = vec![];
+    root.find_all(&["STMTRS", "CCSTMTRS"], &mut aggregates);
+    aggregates.into_iter().map(statement).collect()
+}
+
+pub async fn import_ofx(client: &Client, budget_id: &str, account_id: &str, statement: &OfxStatement) -> Vec<ImportOutcome> {
+    BatchImporter::new(client, budget_id).import(statement.to_save_transactions(account_id)).await
+}
+
+fn statement(aggregate: &Element) -> Result<OfxStatement, OfxImportError> {
+    let account: Option<&Element> = aggregate.child("BANKACCTFROM").or_else(|| aggregate.child("CCACCTFROM"));
+    let mut entries: Vec<&Element> = vec![];
+    aggregate.find_all(&["STMTTRN"], &mut entries);
+
+    let ledger_balance: Option<LedgerBalance> = match aggregate.child("LEDGERBAL") {
+        Some(ledger) => Some(LedgerBalance {
+            amount: amount(required(ledger, "LEDGERBAL", "BALAMT")?, "BALAMT")?,
+            as_of:  date(required(ledger, "LEDGERBAL", "DTASOF")?, "DTASOF")?
+        }),
+        None => None
+    };
+
+    Ok(OfxStatement {
+        account_id:     account.and_then(|account| account.text("ACCTID")).map(str::to_string),
+        currency:       aggregate.text("CURDEF").map(str::to_string),
+        transactions:   entries.into_iter().map(transaction).collect::<Result<Vec<OfxTransaction>, OfxImportError>>()?,
+        ledger_balance
+    })
+}
+
+fn transaction(entry: &Element) -> Result<OfxTransaction, OfxImportError> {
+    let name: Option<&str> = entry.text("NAME").or_else(|| entry.child("PAYEE").and_then(|payee| payee.text("NAME")));
+
+    Ok(OfxTransaction {
+        transaction_type:   entry.text("TRNTYPE").map(str::to_string),
+        posted:             date(required(entry, "STMTTRN", "DTPOSTED")?, "DTPOSTED")?,
+        amount:             amount(required(entry, "STMTTRN", "TRNAMT")?, "TRNAMT")?,
+        fitid:              required(entry, "STMTTRN", "FITID")?.to_string(),
+        name:               name.map(str::to_string),
+        memo:               entry.text("MEMO").map(str::to_string),
+        check_number:       entry.text("CHECKNUM").map(str::to_string)
+    })
+}
+
+fn required<'a>(element: &'a Element, aggregate: &'static str, field: &'static str) -> Result<&'a str, OfxImportError> {
+    element.text(field).ok_or(OfxImportError::MissingField { aggregate, field })
+}
+
+// OFX dates look like `20230612`, `20230612120000` or `20230612120000.000[-5:EST]`; only the day matters here.
+fn date(value: &str, field: &'static str) -> Result<NaiveDate, OfxImportError> {
+    value.get(..8)
+        .and_then(|day| NaiveDate::parse_from_str(day, "%Y%m%d").ok())
+        .ok_or_else(|| OfxImportError::InvalidDate { field, value: value.to_string() })
+}
+
+// The spec allows a comma as decimal separator, and some European banks use it.
+fn amount(value: &str, field: &'static str) -> Result<i64, OfxImportError> {
+    let decimal_separator: char = if value.contains(',') && !value.contains('.') { ',' } else { '.' };
+    parse_milliunits(value, decimal_separator).ok_or_else(|| OfxImportError::InvalidAmount { field, value: value.to_string() })
+}
@@ -0,0 +1,298 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::models::{Payee, SaveTransaction};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PayeeTarget {
+    PayeeId(String),
+    PayeeName(String)
+}
+
+#[derive(Debug, Clone)]
+pub enum PayeeMatcher {
+    // Case-insensitive substring.
+    Contains(String),
+    Regex(Regex)
+}
+
+#[derive(Debug, Clone)]
+pub struct PayeeRule {
+    pub name:       Option<String>,
+    pub matcher:    PayeeMatcher,
+    pub target:     PayeeTarget
+}
+
+impl PayeeRule {
+    // Returns the canonical payee for `raw`, with `$1`-style regex captures expanded in payee names.
+    pub fn rewrite(&self, raw: &str) -> Option<PayeeTarget> {
+        match &self.matcher {
+            PayeeMatcher::Contains(pattern) => {
+                raw.to_lowercase().contains(&pattern.to_lowercase()).then(|| self.target.clone())
+            },
+            PayeeMatcher::Regex(regex) => regex.captures(raw).map(|captures| match &self.target {
+                PayeeTarget::PayeeName(template) => {
+                    let mut name: String = String::new();
+                    captures.expand(template, &mut name);
+                    PayeeTarget::PayeeName(name.trim().to_string())
+                },
+                target => target.clone()
+            })
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum PayeeRuleError {
+    Toml(String),
+    Regex { rule: usize, error: regex::Error },
+    InvalidRule { rule: usize, detail: &'static str }
+}
+
+impl fmt::Display for PayeeRuleError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PayeeRuleError::Toml(detail) => write!(formatter, "invalid payee rules: {}", detail),
+            PayeeRuleError::Regex { rule, error } => write!(formatter, "rule {}: {}", rule, error),
+            PayeeRuleError::InvalidRule { rule, detail } => write!(formatter, "rule {}: {}", rule, detail)
+        }
+    }
+}
+
+impl std::error::Error for PayeeRuleError {}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RuleFile {
+    #[serde(default)]
+    rule: Vec<RuleDefinition>
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RuleDefinition {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name:       Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    contains:   Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    regex:      Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    payee_id:   Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    payee_name: Option<String>
+}
+
+// Rewrites raw imported payee names, first matching rule wins. Rules are written as
+//
+//     [[rule]]
+//     name = "Amazon"
+//     regex = "^AMZN (MKTP|DIGITAL)"
+//     payee_name = "Amazon"
+//
+//     [[rule]]
+//     contains = "NETFLIX"
+//     payee_id = "5a3d8b1c-..."
+#[derive(Debug, Clone, Default)]
+pub struct PayeeRules {
+    pub rules: Vec<PayeeRule>
+}
+
+impl PayeeRules {
+    pub fn new() -> PayeeRules {
+        PayeeRules::default()
+    }
+
+    pub fn from_toml(rules: &str) -> Result<PayeeRules, PayeeRuleError> {
+        let file: RuleFile = toml::from_str(rules).map_err(|error| PayeeRuleError::Toml(error.to_string()))?;
+
+        let rules: Vec<PayeeRule> = file.rule.into_iter().enumerate().map(|(index, definition)| {
+            let rule: usize = index + 1;
+            let matcher: PayeeMatcher = match (definition.contains, definition.regex) {
+                (Some(pattern), None) => PayeeMatcher::Contains(pattern),
+                (None, Some(pattern)) => PayeeMatcher::Regex(Regex::new(&pattern).map_err(|error| PayeeRuleError::Regex { rule, error })?),
+                _ => return Err(PayeeRuleError::InvalidRule { rule, detail: "needs exactly one of contains or regex" })
+            };
+            let target: PayeeTarget = match (definition.payee_id, definition.payee_name) {
+                (Some(payee_id), None) => PayeeTarget::PayeeId(payee_id),
+                (None, Some(payee_name)) => PayeeTarget::PayeeName(payee_name),
+                _ => return Err(PayeeRuleError::InvalidRule { rule, detail: "needs exactly one of payee_id or payee_name" })
+            };
+
+            Ok(PayeeRule { name: definition.name, matcher, target })
+        }).collect::<Result<Vec<PayeeRule>, PayeeRuleError>>()?;
+
+        Ok(PayeeRules { rules })
+    }
+
+    pub fn contains(mut self, pattern: &str, target: PayeeTarget) -> PayeeRules {
+        self.rules.push(PayeeRule { name: None, matcher: PayeeMatcher::Contains(pattern.to_string()), target });
+        self
+    }
+
+    pub fn regex(mut self, pattern: &str, target: PayeeTarget) -> Result<PayeeRules, regex::Error> {
+        self.rules.push(PayeeRule { name: None, matcher: PayeeMatcher::Regex(Regex::new(pattern)?), target });
+        Ok(self)
+    }
+
+    // The index of the first rule matching `raw` and what it rewrites `raw` to.
+    pub fn rewrite(&self, raw: &str) -> Option<(usize, PayeeTarget)> {
+        self.rules.iter().enumerate().find_map(|(index, rule)| rule.rewrite(raw).map(|target| (index, target)))
+    }
+
+    // Reports what `apply` would do without touching the transactions.
+    pub fn dry_run(&self, transactions: &[SaveTransaction]) -> RewriteReport {
+        let rows: Vec<RewriteRow> = transactions.iter().enumerate().filter_map(|(row, transaction)| {
+            let raw_payee: &str = transaction.payee_name.as_deref().filter(|_| transaction.payee_id.is_none())?;
+            let rewrite: Option<(usize, PayeeTarget)> = self.rewrite(raw_payee);
+
+            Some(RewriteRow {
+                row,
+                raw_payee:  raw_payee.to_string(),
+                rule:       rewrite.as_ref().map(|(rule, _)| *rule),
+                rule_name:  rewrite.as_ref().and_then(|(rule, _)| self.rules[*rule].name.clone()),
+                target:     rewrite.map(|(_, target)| target)
+            })
+        }).collect();
+
+        RewriteReport { rows }
+    }
+
+    // Rewrites the payee of every transaction that only carries a raw `payee_name`.
+    pub fn apply(&self, transactions: &mut [SaveTransaction]) -> RewriteReport {
+        let report: RewriteReport = self.dry_run(transactions);
+
+        for row in &report.rows {
+            let transaction: &mut SaveTransaction = &mut transactions[row.row];
+            match &row.target {
+                Some(PayeeTarget::PayeeId(payee_id)) => {
+                    transaction.payee_id = Some(payee_id.clone());
+                    transaction.payee_name = None;
+                },
+                Some(PayeeTarget::PayeeName(payee_name)) => transaction.payee_name = Some(payee_name.clone()),
+                None => {}
+            }
+        }
+
+        report
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RewriteRow {
+    // Index of the transaction in the input.
+    pub row:        usize,
+    pub raw_payee:  String,
+    pub rule:       Option<usize>,
+    pub rule_name:  Option<String>,
+    pub target:     Option<PayeeTarget>
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RewriteReport {
+    pub rows: Vec<RewriteRow>
+}
+
+impl RewriteReport {
+    pub fn matched(&self) -> impl Iterator<Item = &RewriteRow> {
+        self.rows.iter().filter(|row| row.rule.is_some())
+    }
+
+    pub fn unmatched(&self) -> impl Iterator<Item = &RewriteRow> {
+        self.rows.iter().filter(|row| row.rule.is_none())
+    }
+}
+
+impl fmt::Display for RewriteReport {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in &self.rows {
+            let rule: String = match (row.rule, &row.rule_name) {
+                (Some(_), Some(name)) => format!("rule `{}`", name),
+                (Some(rule), None) => format!("rule {}", rule + 1),
+                (None, _) => "no rule".to_string()
+            };
+            let target: String = match &row.target {
+                Some(PayeeTarget::PayeeId(payee_id)) => format!(" -> payee {}", payee_id),
+                Some(PayeeTarget::PayeeName(payee_name)) => format!(" -> \"{}\"", payee_name),
+                None => String::new()
+            };
+            writeln!(formatter, "row {}: \"{}\" {}{}", row.row, row.raw_payee, rule, target)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleSuggestion {
+    // Substring of the raw names to use as a `contains` rule.
+    pub pattern:    String,
+    pub payee_id:   String,
+    pub payee_name: String,
+    pub examples:   Vec<String>
+}
+
+impl RuleSuggestion {
+    // A `[[rule]]` table that `PayeeRules::from_toml` reads back.
+    pub fn to_toml(&self) -> String {
+        let file: RuleFile = RuleFile {
+            rule: vec![RuleDefinition {
+                name:       Some(self.payee_name.clone()),
+                contains:   Some(self.pattern.clone()),
+                regex:      None,
+                payee_id:   Some(self.payee_id.clone()),
+                payee_name: None
+            }]
+        };
+        toml::to_string(&file).expect("A rule always serializes.")
+    }
+}
+
+// Proposes rules for raw names that no rule matches, by looking for an existing payee whose
+// words all start a word of the raw name, e.g. `Netflix` for `NETFLIX.COM 866-579-7172`.
+pub fn suggest_rules(report: &RewriteReport, payees: &[Payee]) -> Vec<RuleSuggestion> {
+    let candidates: Vec<(&Payee, Vec<String>)> = payees.iter()
+        .filter(|payee| !payee.deleted && payee.transfer_account_id.is_none())
+        .map(|payee| (payee, words(&payee.name)))
+        .filter(|(_, words)| !words.is_empty())
+        .collect();
+
+    let mut suggestions: BTreeMap<(String, String), RuleSuggestion> = BTreeMap::new();
+    for row in report.unmatched() {
+        let raw_words: Vec<String> = words(&row.raw_payee);
+        let best: Option<(&Payee, String, usize)> = candidates.iter().filter_map(|(payee, payee_words)| {
+            let matched: Vec<&String> = payee_words.iter()
+                .map(|word| raw_words.iter().find(|raw_word| raw_word.starts_with(word.as_str())))
+                .collect::<Option<Vec<&String>>>()?;
+            let joined: String = matched.iter().map(|word| word.as_str()).collect::<Vec<&str>>().join(" ");
+            let pattern: String = if row.raw_payee.to_uppercase().contains(&joined) { joined } else { matched[0].clone() };
+            let score: usize = payee_words.iter().map(String::len).sum();
+            Some((*payee, pattern, score))
+        }).max_by_key(|(_, _, score)| *score);
+
+        if let Some((payee, pattern, _)) = best {
+            let suggestion: &mut RuleSuggestion = suggestions.entry((pattern.clone(), payee.id.clone())).or_insert_with(|| RuleSuggestion {
+                pattern,
+                payee_id:   payee.id.clone(),
+                payee_name: payee.name.clone(),
+                examples:   vec![]
+            });
+            if !suggestion.examples.contains(&row.raw_payee) {
+                suggestion.examples.push(row.raw_payee.clone());
+            }
+        }
+    }
+
+    suggestions.into_values().collect()
+}
+
+// Upper-cased words of at least two letters, without store numbers and other digit runs.
+fn words(name: &str) -> Vec<String> {
+    name.split(|character: char| !character.is_alphanumeric())
+        .filter(|word| word.chars().count() >= 2 && !word.chars().any(|character| character.is_ascii_digit()))
+        .map(str::to_uppercase)
+        .collect()
+}
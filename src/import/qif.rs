@@ -0,0 +1,248 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, Read};
+
+use chrono::NaiveDate;
+
+use crate::builder::{MAX_MEMO_LENGTH, MAX_PAYEE_NAME_LENGTH};
+use crate::client::Client;
+use crate::import::{parse_milliunits, truncate, BatchImporter, ImportIdGenerator, ImportOutcome};
+use crate::models::{ClearedStatus, SaveSubTransaction, SaveTransaction};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DateOrder {
+    #[default]
+    MonthDayYear,
+    DayMonthYear,
+    YearMonthDay
+}
+
+#[derive(Debug, Clone)]
+pub struct QifOptions {
+    pub date_order:         DateOrder,
+    pub decimal_separator:  char,
+    // QIF category names, e.g. `Food:Groceries`, to YNAB category ids.
+    pub categories:         HashMap<String, String>
+}
+
+impl Default for QifOptions {
+    fn default() -> QifOptions {
+        QifOptions { date_order: DateOrder::MonthDayYear, decimal_separator: '.', categories: HashMap::new() }
+    }
+}
+
+impl QifOptions {
+    pub fn new() -> QifOptions {
+        QifOptions::default()
+    }
+
+    pub fn date_order(mut self, date_order: DateOrder) -> QifOptions {
+        self.date_order = date_order;
+        self
+    }
+
+    pub fn decimal_separator(mut self, decimal_separator: char) -> QifOptions {
+        self.decimal_separator = decimal_separator;
+        self
+    }
+
+    pub fn category(mut self, name: &str, category_id: &str) -> QifOptions {
+        self.categories.insert(name.to_string(), category_id.to_string());
+        self
+    }
+
+    // Looks up a QIF category, ignoring any `/Class` suffix. Transfers (`[Account]`) and
+    // unmapped names have no category.
+    fn category_id(&self, name: &str) -> Option<String> {
+        let name: &str = name.split('/').next().unwrap_or_default().trim();
+        if name.is_empty() || name.starts_with('[') {
+            return None;
+        }
+
+        self.categories.get(name).cloned()
+    }
+}
+
+#[derive(Debug)]
+pub enum QifImportError {
+    Read(io::Error),
+    MissingDate { line: usize },
+    MissingAmount { line: usize },
+    InvalidDate { line: usize, value: String },
+    InvalidAmount { line: usize, value: String }
+}
+
+impl fmt::Display for QifImportError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QifImportError::Read(error) => write!(formatter, "could not read QIF: {}", error),
+            QifImportError::MissingDate { line } => write!(formatter, "line {}: record has no date", line),
+            QifImportError::MissingAmount { line } => write!(formatter, "line {}: record has no amount", line),
+            QifImportError::InvalidDate { line, value } => write!(formatter, "line {}: `{}` is not a date", line, value),
+            QifImportError::InvalidAmount { line, value } => write!(formatter, "line {}: `{}` is not an amount", line, value)
+        }
+    }
+}
+
+impl std::error::Error for QifImportError {}
+
+#[derive(Debug, Default)]
+struct Record {
+    date:               Option<(usize, String)>,
+    amount:             Option<(usize, String)>,
+    payee:              Option<String>,
+    memo:               Option<String>,
+    category:           Option<String>,
+    cleared:            Option<String>,
+    splits:             Vec<Split>
+}
+
+#[derive(Debug, Default)]
+struct Split {
+    category:   Option<String>,
+    memo:       Option<String>,
+    amount:     Option<(usize, String)>
+}
+
+// Reads the `!Type:Bank`, `!Type:CCard` and `!Type:Cash` sections of a QIF file into
+// transactions for `account_id`. Records in other sections (investments, memorized
+// transactions, category lists) are skipped.
+pub fn read_transactions<R: Read>(mut reader: R, options: &QifOptions, account_id: &str) -> Result<Vec<SaveTransaction>, QifImportError> {
+    let mut bytes: Vec<u8> = vec![];
+    reader.read_to_end(&mut bytes).map_err(QifImportError::Read)?;
+    let contents: String = String::from_utf8_lossy(&bytes).into_owned();
+
+    let mut generator: ImportIdGenerator = ImportIdGenerator::new();
+    let mut transactions: Vec<SaveTransaction> = vec![];
+    let mut supported: bool = true;
+    let mut record: Record = Record::default();
+
+    for (index, line) in contents.lines().enumerate() {
+        let line_number: usize = index + 1;
+        let line: &str = line.trim_end();
+        if line.is_empty() {
+            continue;
+        }
+
+        if line.starts_with('!') {
+            let header: String = line.to_ascii_lowercase();
+            if header.starts_with("!type:") {
+                supported = matches!(header.trim_start_matches("!type:").trim(), "bank" | "ccard" | "cash");
+            } else if header.starts_with("!account") {
+                supported = false;
+            }
+            record = Record::default();
+            continue;
+        }
+
+        if !supported {
+            continue;
+        }
+
+        let (code, value) = line.split_at(line.chars().next().map_or(0, char::len_utf8));
+        let value: String = value.trim().to_string();
+
+        match code {
+            "D" => record.date = Some((line_number, value)),
+            "T" | "U" => record.amount = Some((line_number, value)),
+            "P" => record.payee = Some(value),
+            "M" => record.memo = Some(value),
+            "L" => record.category = Some(value),
+            "C" => record.cleared = Some(value),
+            "S" => record.splits.push(Split { category: Some(value), ..Split::default() }),
+            "E" => split(&mut record, |split| split.memo.is_some()).memo = Some(value),
+            "$" => split(&mut record, |_| false).amount = Some((line_number, value)),
+            "^" => {
+                let mut transaction: SaveTransaction = transaction(std::mem::take(&mut record), line_number, options, account_id)?;
+                generator.assign(&mut transaction);
+                transactions.push(transaction);
+            },
+            _ => {}
+        }
+    }
+
+    Ok(transactions)
+}
+
+pub async fn import_qif<R: Read>(client: &Client, budget_id: &str, account_id: &str, reader: R, options: &QifOptions) -> Result<Vec<ImportOutcome>, QifImportError> {
+    let transactions: Vec<SaveTransaction> = read_transactions(reader, options, account_id)?;
+    Ok(BatchImporter::new(client, budget_id).import(transactions).await)
+}
+
+// Each split is written as `S`, `E`, `$` with the first two optional, so a split ends at its
+// amount line or when a field repeats.
+fn split(record: &mut Record, taken: fn(&Split) -> bool) -> &mut Split {
+    if record.splits.last().map_or(true, |split| split.amount.is_some() || taken(split)) {
+        record.splits.push(Split::default());
+    }
+
+    record.splits.last_mut().unwrap()
+}
+
+fn transaction(record: Record, line: usize, options: &QifOptions, account_id: &str) -> Result<SaveTransaction, QifImportError> {
+    let (date_line, date_value) = record.date.ok_or(QifImportError::MissingDate { line })?;
+    let date: NaiveDate = parse_date(&date_value, options.date_order).ok_or(QifImportError::InvalidDate { line: date_line, value: date_value })?;
+    let (amount_line, amount_value) = record.amount.ok_or(QifImportError::MissingAmount { line })?;
+    let total: i64 = amount(&amount_value, amount_line, options)?;
+
+    let subtransactions: Vec<SaveSubTransaction> = record.splits.into_iter().map(|split| {
+        let (split_line, split_value) = split.amount.ok_or(QifImportError::MissingAmount { line })?;
+        Ok(SaveSubTransaction {
+            amount:         amount(&split_value, split_line, options)?,
+            payee_id:       None,
+            payee_name:     None,
+            category_id:    split.category.as_deref().and_then(|category| options.category_id(category)),
+            memo:           split.memo.filter(|memo| !memo.is_empty()).map(|memo| truncate(&memo, MAX_MEMO_LENGTH))
+        })
+    }).collect::<Result<Vec<SaveSubTransaction>, QifImportError>>()?;
+
+    let cleared: ClearedStatus = match record.cleared.as_deref().map(str::trim) {
+        Some("*") | Some("c") | Some("C") => ClearedStatus::Cleared,
+        Some("X") | Some("x") | Some("R") | Some("r") => ClearedStatus::Reconciled,
+        _ => ClearedStatus::Uncleared
+    };
+
+    Ok(SaveTransaction {
+        account_id:         account_id.to_string(),
+        date:               date.format("%Y-%m-%d").to_string(),
+        amount:             total,
+        payee_id:           None,
+        payee_name:         record.payee.filter(|payee| !payee.is_empty()).map(|payee| truncate(&payee, MAX_PAYEE_NAME_LENGTH)),
+        category_id:        if subtransactions.is_empty() { record.category.as_deref().and_then(|category| options.category_id(category)) } else { None },
+        memo:               record.memo.filter(|memo| !memo.is_empty()).map(|memo| truncate(&memo, MAX_MEMO_LENGTH)),
+        cleared:            Some(cleared),
+        approved:           Some(false),
+        flag_color:         None,
+        import_id:          None,
+        subtransactions:    if subtransactions.is_empty() { None } else { Some(subtransactions) }
+    })
+}
+
+fn amount(value: &str, line: usize, options: &QifOptions) -> Result<i64, QifImportError> {
+    parse_milliunits(value, options.decimal_separator).ok_or_else(|| QifImportError::InvalidAmount { line, value: value.to_string() })
+}
+
+// Understands the date spellings Quicken and friends write: `6/12/2023`, `6/12/23`,
+// ` 6/12'23` (an apostrophe marks years from 2000), `12.06.2023` and `2023-06-12`.
+pub fn parse_date(value: &str, date_order: DateOrder) -> Option<NaiveDate> {
+    let value: &str = value.trim();
+    let millennium: bool = value.contains('\'');
+    let parts: Vec<&str> = value.split(['/', '.', '-', '\'']).map(str::trim).collect();
+    if parts.len() != 3 || parts.iter().any(|part| part.is_empty() || !part.chars().all(|digit| digit.is_ascii_digit())) {
+        return None;
+    }
+
+    let (year, month, day) = match date_order {
+        _ if parts[0].len() == 4 => (parts[0], parts[1], parts[2]),
+        DateOrder::MonthDayYear => (parts[2], parts[0], parts[1]),
+        DateOrder::DayMonthYear => (parts[2], parts[1], parts[0]),
+        DateOrder::YearMonthDay => (parts[0], parts[1], parts[2])
+    };
+
+    let mut year: i32 = year.parse().ok()?;
+    if year < 100 {
+        year += if millennium || year < 70 { 2000 } else { 1900 };
+    }
+
+    NaiveDate::from_ymd_opt(year, month.parse().ok()?, day.parse().ok()?)
+}
@@ -0,0 +1,73 @@
+use chrono::NaiveDate;
+
+use crate::builder::{MAX_MEMO_LENGTH, MAX_PAYEE_NAME_LENGTH};
+use crate::client::Client;
+use crate::import::{reference_import_id, truncate, BatchImporter, ImportIdGenerator, ImportOutcome};
+use crate::models::{ClearedStatus, SaveTransaction};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryStatus {
+    Booked,
+    Pending
+}
+
+impl EntryStatus {
+    pub fn cleared_status(self) -> ClearedStatus {
+        match self {
+            EntryStatus::Booked => ClearedStatus::Cleared,
+            EntryStatus::Pending => ClearedStatus::Uncleared
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatementEntry {
+    // The reference the bank assigned to the entry, when it has one.
+    pub reference:      Option<String>,
+    pub status:         EntryStatus,
+    pub booking_date:   NaiveDate,
+    pub value_date:     Option<NaiveDate>,
+    pub amount:         i64,
+    pub counterparty:   Option<String>,
+    pub remittance:     Option<String>
+}
+
+// A bank statement as read from camt.053 or MT940.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BankStatement {
+    pub id:         Option<String>,
+    pub account:    Option<String>,
+    pub currency:   Option<String>,
+    pub entries:    Vec<StatementEntry>
+}
+
+impl BankStatement {
+    // Maps every entry to a transaction for `account_id`. The bank's entry reference becomes
+    // the import id; entries without one fall back to YNAB-style ids.
+    pub fn to_save_transactions(&self, account_id: &str) -> Vec<SaveTransaction> {
+        let mut generator: ImportIdGenerator = ImportIdGenerator::new();
+
+        self.entries.iter().map(|entry| {
+            let mut transaction: SaveTransaction = SaveTransaction {
+                account_id:         account_id.to_string(),
+                date:               entry.booking_date.format("%Y-%m-%d").to_string(),
+                amount:             entry.amount,
+                payee_id:           None,
+                payee_name:         entry.counterparty.as_deref().map(|counterparty| truncate(counterparty, MAX_PAYEE_NAME_LENGTH)),
+                category_id:        None,
+                memo:               entry.remittance.as_deref().map(|remittance| truncate(remittance, MAX_MEMO_LENGTH)),
+                cleared:            Some(entry.status.cleared_status()),
+                approved:           Some(false),
+                flag_color:         None,
+                import_id:          entry.reference.as_deref().map(|reference| reference_import_id("REF", reference)),
+                subtransactions:    None
+            };
+            generator.assign(&mut transaction);
+            transaction
+        }).collect()
+    }
+}
+
+pub async fn import_statement(client: &Client, budget_id: &str, account_id: &str, statement: &BankStatement) -> Vec<ImportOutcome> {
+    BatchImporter::new(client, budget_id).import(statement.to_save_transactions(account_id)).await
+}
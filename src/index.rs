@@ -0,0 +1,215 @@
+use std::collections::HashMap;
+
+use crate::models::{
+    Account, BudgetDetail, Category, CategoryGroup, MonthDetail, Payee, PayeeLocation, ScheduledSubTransaction, ScheduledTransactionSummary,
+    SubTransaction, TransactionSummary
+};
+
+// Which entries a `BudgetIndex` leaves out. Children go with their parent, so the subtransactions
+// of a deleted transaction and the categories of a hidden group are left out too.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct IndexFilter {
+    pub skip_deleted:   bool,
+    // Hidden category groups and categories.
+    pub skip_hidden:    bool
+}
+
+impl IndexFilter {
+    // Everything the budget contains.
+    pub fn all() -> IndexFilter {
+        IndexFilter::default()
+    }
+
+    pub fn live() -> IndexFilter {
+        IndexFilter { skip_deleted: true, skip_hidden: false }
+    }
+
+    // What the YNAB app shows: no deleted entries and no hidden categories.
+    pub fn visible() -> IndexFilter {
+        IndexFilter { skip_deleted: true, skip_hidden: true }
+    }
+}
+
+// Hash map lookups by id over a `BudgetDetail`, plus each parent's children in budget order.
+pub struct BudgetIndex<'a> {
+    budget:                     &'a BudgetDetail,
+    filter:                     IndexFilter,
+    accounts:                   HashMap<&'a str, &'a Account>,
+    payees:                     HashMap<&'a str, &'a Payee>,
+    payee_locations:            HashMap<&'a str, &'a PayeeLocation>,
+    category_groups:            HashMap<&'a str, &'a CategoryGroup>,
+    categories:                 HashMap<&'a str, &'a Category>,
+    months:                     HashMap<&'a str, &'a MonthDetail>,
+    transactions:               HashMap<&'a str, &'a TransactionSummary>,
+    subtransactions:            HashMap<&'a str, &'a SubTransaction>,
+    scheduled_transactions:     HashMap<&'a str, &'a ScheduledTransactionSummary>,
+    categories_by_group:        HashMap<&'a str, Vec<&'a Category>>,
+    locations_by_payee:         HashMap<&'a str, Vec<&'a PayeeLocation>>,
+    subtransactions_by_parent:  HashMap<&'a str, Vec<&'a SubTransaction>>,
+    scheduled_by_parent:        HashMap<&'a str, Vec<&'a ScheduledSubTransaction>>
+}
+
+fn by_id<T>(items: &[T], id: fn(&T) -> &str, keep: impl Fn(&T) -> bool) -> HashMap<&str, &T> {
+    items.iter().filter(|item| keep(item)).map(|item| (id(item), item)).collect()
+}
+
+fn by_parent<'a, T>(items: impl Iterator<Item = &'a T>, parent_id: fn(&T) -> &str) -> HashMap<&'a str, Vec<&'a T>> {
+    let mut children: HashMap<&'a str, Vec<&'a T>> = HashMap::new();
+    for item in items {
+        children.entry(parent_id(item)).or_default().push(item);
+    }
+    children
+}
+
+impl<'a> BudgetIndex<'a> {
+    pub fn new(budget: &'a BudgetDetail) -> BudgetIndex<'a> {
+        BudgetIndex::with_filter(budget, IndexFilter::all())
+    }
+
+    pub fn with_filter(budget: &'a BudgetDetail, filter: IndexFilter) -> BudgetIndex<'a> {
+        let live = |deleted: bool| !(filter.skip_deleted && deleted);
+        let shown = |hidden: bool| !(filter.skip_hidden && hidden);
+
+        let accounts = by_id(&budget.accounts, |account| &account.id, |account| live(account.deleted));
+        let payees = by_id(&budget.payees, |payee| &payee.id, |payee| live(payee.deleted));
+        let payee_locations = by_id(&budget.payee_locations, |location| &location.id, |location| {
+            live(location.deleted) && payees.contains_key(location.payee_id.as_str())
+        });
+        let category_groups = by_id(&budget.category_groups, |group| &group.id, |group| live(group.deleted) && shown(group.hidden));
+        let categories = by_id(&budget.categories, |category| &category.id, |category| {
+            live(category.deleted) && shown(category.hidden) && category_groups.contains_key(category.category_group_id.as_str())
+        });
+        let months = by_id(&budget.months, |month| &month.month, |month| live(month.deleted));
+        let transactions = by_id(&budget.transactions, |transaction| &transaction.id, |transaction| live(transaction.deleted));
+        let subtransactions = by_id(&budget.subtransactions, |subtransaction| &subtransaction.id, |subtransaction| {
+            live(subtransaction.deleted) && transactions.contains_key(subtransaction.transaction_id.as_str())
+        });
+        let scheduled_transactions = by_id(&budget.scheduled_transactions, |scheduled| &scheduled.id, |scheduled| live(scheduled.deleted));
+
+        let categories_by_group = by_parent(
+            budget.categories.iter().filter(|category| categories.contains_key(category.id.as_str())),
+            |category| &category.category_group_id
+        );
+        let locations_by_payee = by_parent(
+            budget.payee_locations.iter().filter(|location| payee_locations.contains_key(location.id.as_str())),
+            |location| &location.payee_id
+        );
+        let subtransactions_by_parent = by_parent(
+            budget.subtransactions.iter().filter(|subtransaction| subtransactions.contains_key(subtransaction.id.as_str())),
+            |subtransaction| &subtransaction.transaction_id
+        );
+        let scheduled_by_parent = by_parent(
+            budget.scheduled_subtransactions.iter().filter(|subtransaction| {
+                live(subtransaction.deleted) && scheduled_transactions.contains_key(subtransaction.scheduled_transaction_id.as_str())
+            }),
+            |subtransaction| &subtransaction.scheduled_transaction_id
+        );
+
+        BudgetIndex {
+            budget,
+            filter,
+            accounts,
+            payees,
+            payee_locations,
+            category_groups,
+            categories,
+            months,
+            transactions,
+            subtransactions,
+            scheduled_transactions,
+            categories_by_group,
+            locations_by_payee,
+            subtransactions_by_parent,
+            scheduled_by_parent
+        }
+    }
+
+    pub fn budget(&self) -> &'a BudgetDetail {
+        self.budget
+    }
+
+    pub fn filter(&self) -> IndexFilter {
+        self.filter
+    }
+
+    pub fn account(&self, account_id: &str) -> Option<&'a Account> {
+        self.accounts.get(account_id).copied()
+    }
+
+    pub fn payee(&self, payee_id: &str) -> Option<&'a Payee> {
+        self.payees.get(payee_id).copied()
+    }
+
+    pub fn payee_location(&self, payee_location_id: &str) -> Option<&'a PayeeLocation> {
+        self.payee_locations.get(payee_location_id).copied()
+    }
+
+    pub fn category_group(&self, category_group_id: &str) -> Option<&'a CategoryGroup> {
+        self.category_groups.get(category_group_id).copied()
+    }
+
+    pub fn category(&self, category_id: &str) -> Option<&'a Category> {
+        self.categories.get(category_id).copied()
+    }
+
+    // Looks a month up by its first day, e.g. `2023-06-01`.
+    pub fn month(&self, month: &str) -> Option<&'a MonthDetail> {
+        self.months.get(month).copied()
+    }
+
+    pub fn transaction(&self, transaction_id: &str) -> Option<&'a TransactionSummary> {
+        self.transactions.get(transaction_id).copied()
+    }
+
+    pub fn subtransaction(&self, subtransaction_id: &str) -> Option<&'a SubTransaction> {
+        self.subtransactions.get(subtransaction_id).copied()
+    }
+
+    pub fn scheduled_transaction(&self, scheduled_transaction_id: &str) -> Option<&'a ScheduledTransactionSummary> {
+        self.scheduled_transactions.get(scheduled_transaction_id).copied()
+    }
+
+    // The category and the group it belongs to.
+    pub fn category_with_group(&self, category_id: &str) -> Option<(&'a Category, &'a CategoryGroup)> {
+        let category: &Category = self.category(category_id)?;
+        Some((category, self.category_group(&category.category_group_id)?))
+    }
+
+    // The account a transfer payee stands for.
+    pub fn transfer_account(&self, payee_id: &str) -> Option<&'a Account> {
+        self.payee(payee_id)?.transfer_account_id.as_deref().and_then(|account_id| self.account(account_id))
+    }
+
+    pub fn categories_in(&self, category_group_id: &str) -> &[&'a Category] {
+        self.categories_by_group.get(category_group_id).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    pub fn payee_locations_of(&self, payee_id: &str) -> &[&'a PayeeLocation] {
+        self.locations_by_payee.get(payee_id).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    pub fn subtransactions_of(&self, transaction_id: &str) -> &[&'a SubTransaction] {
+        self.subtransactions_by_parent.get(transaction_id).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    pub fn scheduled_subtransactions_of(&self, scheduled_transaction_id: &str) -> &[&'a ScheduledSubTransaction] {
+        self.scheduled_by_parent.get(scheduled_transaction_id).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    // The entries the filter keeps, in budget order.
+    pub fn accounts(&self) -> impl Iterator<Item = &'a Account> + '_ {
+        self.budget.accounts.iter().filter(|account| self.accounts.contains_key(account.id.as_str()))
+    }
+
+    pub fn payees(&self) -> impl Iterator<Item = &'a Payee> + '_ {
+        self.budget.payees.iter().filter(|payee| self.payees.contains_key(payee.id.as_str()))
+    }
+
+    pub fn category_groups(&self) -> impl Iterator<Item = &'a CategoryGroup> + '_ {
+        self.budget.category_groups.iter().filter(|group| self.category_groups.contains_key(group.id.as_str()))
+    }
+
+    pub fn transactions(&self) -> impl Iterator<Item = &'a TransactionSummary> + '_ {
+        self.budget.transactions.iter().filter(|transaction| self.transactions.contains_key(transaction.id.as_str()))
+    }
+}
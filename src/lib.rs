@@ -1,2 +1,15 @@
 pub mod models;
 pub mod client;
+pub mod cassette;
+pub mod query;
+pub mod builder;
+pub mod import;
+pub mod categorize;
+pub mod export;
+pub mod index;
+pub mod denormalize;
+pub mod hybrid;
+pub mod category_tree;
+pub mod reports;
+#[cfg(feature = "mock-server")]
+pub mod mock;
@@ -0,0 +1,883 @@
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use chrono::Local;
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::client::Client;
+use crate::models::{
+    BudgetDetail, BudgetDetailResponse, BudgetSummary, CategoryGroupWithCategories, ClearedStatus, HybridTransaction, Payee,
+    PatchTransactionsWrapper, PostTransactionsWrapper, SaveTransaction, ScheduledTransactionDetail, ScheduledTransactionSummary, SubTransaction,
+    TransactionDetail, TransactionSummary, TransactionType
+};
+
+pub const MOCK_API_TOKEN: &str = "mock-api-token";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MockError {
+    Unauthorized,
+    NotFound,
+    TooManyRequests,
+    InternalServerError
+}
+
+impl MockError {
+    fn status(&self) -> u16 {
+        match self {
+            MockError::Unauthorized => 401,
+            MockError::NotFound => 404,
+            MockError::TooManyRequests => 429,
+            MockError::InternalServerError => 500
+        }
+    }
+
+    fn body(&self) -> Value {
+        match self {
+            MockError::Unauthorized => error_body("401", "unauthorized", "Unauthorized"),
+            MockError::NotFound => error_body("404.2", "resource_not_found", "Resource not found"),
+            MockError::TooManyRequests => error_body("429", "too_many_requests", "Too many requests"),
+            MockError::InternalServerError => error_body("500", "internal_server_error", "Internal Server Error")
+        }
+    }
+}
+
+struct InjectedError {
+    path_prefix:    String,
+    error:          MockError
+}
+
+struct MockBudget {
+    budget:             BudgetDetail,
+    server_knowledge:   i64,
+    knowledge:          HashMap<(&'static str, String), i64>
+}
+
+impl MockBudget {
+    fn new(budget: BudgetDetail, server_knowledge: i64) -> MockBudget {
+        let knowledge = entities(&budget)
+            .into_iter()
+            .map(|(collection, id, _)| ((collection, id), server_knowledge))
+            .collect();
+
+        MockBudget { budget, server_knowledge, knowledge }
+    }
+
+    fn update<F: FnOnce(&mut BudgetDetail)>(&mut self, update: F) {
+        let before: HashMap<(&'static str, String), Value> = entities(&self.budget)
+            .into_iter()
+            .map(|(collection, id, value)| ((collection, id), value))
+            .collect();
+
+        update(&mut self.budget);
+        self.server_knowledge += 1;
+
+        for (collection, id, value) in entities(&self.budget) {
+            let key = (collection, id);
+            if before.get(&key) != Some(&value) {
+                self.knowledge.insert(key, self.server_knowledge);
+            }
+        }
+    }
+
+    fn changed(&self, collection: &'static str, id: &str, last_knowledge_of_server: Option<i64>) -> bool {
+        match last_knowledge_of_server {
+            Some(last_knowledge) => self.knowledge
+                .get(&(collection, id.to_string()))
+                .map_or(true, |knowledge| *knowledge > last_knowledge),
+            None => true
+        }
+    }
+}
+
+struct MockState {
+    api_token:  String,
+    user_id:    String,
+    budgets:    Vec<MockBudget>,
+    errors:     Vec<InjectedError>,
+    next_id:    u64
+}
+
+pub struct MockServer {
+    address:    SocketAddr,
+    state:      Arc<Mutex<MockState>>,
+    shutdown:   Arc<AtomicBool>,
+    handle:     Option<thread::JoinHandle<()>>
+}
+
+impl MockServer {
+    pub fn start() -> MockServer {
+        let listener: TcpListener = TcpListener::bind("127.0.0.1:0").expect("Could not bind mock server.");
+        let address: SocketAddr = listener.local_addr().expect("Mock server has no local address.");
+
+        let state = Arc::new(Mutex::new(MockState {
+            api_token:  MOCK_API_TOKEN.to_string(),
+            user_id:    "mock-user".to_string(),
+            budgets:    vec![],
+            errors:     vec![],
+            next_id:    1
+        }));
+        let shutdown = Arc::new(AtomicBool::new(false));
+
+        let thread_state = Arc::clone(&state);
+        let thread_shutdown = Arc::clone(&shutdown);
+        let handle = thread::spawn(move || {
+            for stream in listener.incoming() {
+                if thread_shutdown.load(Ordering::SeqCst) {
+                    break;
+                }
+
+                if let Ok(stream) = stream {
+                    handle_connection(stream, &thread_state);
+                }
+            }
+        });
+
+        MockServer { address, state, shutdown, handle: Some(handle) }
+    }
+
+    pub fn url(&self) -> String {
+        format!("http://{}/v1", self.address)
+    }
+
+    pub fn client(&self) -> Client {
+        Client::with_base_url(MOCK_API_TOKEN.to_string(), self.url())
+    }
+
+    pub fn set_user_id(&self, user_id: &str) {
+        self.state.lock().unwrap().user_id = user_id.to_string();
+    }
+
+    pub fn seed_budget(&self, budget: BudgetDetail, server_knowledge: i64) {
+        let mut state = self.state.lock().unwrap();
+        state.budgets.retain(|mock_budget| mock_budget.budget.id != budget.id);
+        state.budgets.push(MockBudget::new(budget, server_knowledge));
+    }
+
+    // Seeds from a captured `get_budget` response body.
+    pub fn seed_budget_json(&self, json: &str) -> Result<(), serde_json::Error> {
+        let response: BudgetDetailResponse = serde_json::from_str(json)?;
+        self.seed_budget(response.data.budget, response.data.server_knowledge);
+        Ok(())
+    }
+
+    // Applies `update` to a seeded budget and bumps the server knowledge of every entity it touched.
+    // Entities should be marked `deleted` rather than removed, as the real API does.
+    pub fn update_budget<F: FnOnce(&mut BudgetDetail)>(&self, budget_id: &str, update: F) -> i64 {
+        let mut state = self.state.lock().unwrap();
+        let mock_budget = state.budgets
+            .iter_mut()
+            .find(|mock_budget| mock_budget.budget.id == budget_id)
+            .unwrap_or_else(|| panic!("Budget `{}` has not been seeded.", budget_id));
+
+        mock_budget.update(update);
+        mock_budget.server_knowledge
+    }
+
+    pub fn budget(&self, budget_id: &str) -> Option<BudgetDetail> {
+        self.state.lock().unwrap().budgets
+            .iter()
+            .find(|mock_budget| mock_budget.budget.id == budget_id)
+            .map(|mock_budget| mock_budget.budget.clone())
+    }
+
+    pub fn server_knowledge(&self, budget_id: &str) -> Option<i64> {
+        self.state.lock().unwrap().budgets
+            .iter()
+            .find(|mock_budget| mock_budget.budget.id == budget_id)
+            .map(|mock_budget| mock_budget.server_knowledge)
+    }
+
+    // Fails the next request whose path (relative to `/v1`) starts with `path_prefix`.
+    pub fn inject_error(&self, path_prefix: &str, error: MockError) {
+        self.state.lock().unwrap().errors.push(InjectedError { path_prefix: path_prefix.to_string(), error });
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.shutdown.store(true, Ordering::SeqCst);
+        let _ = TcpStream::connect(self.address);
+
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+fn handle_connection(mut stream: TcpStream, state: &Arc<Mutex<MockState>>) {
+    let mut reader = BufReader::new(match stream.try_clone() {
+        Ok(read_stream) => read_stream,
+        Err(_) => return
+    });
+
+    let mut request_line = String::new();
+    if reader.read_line(&mut request_line).is_err() {
+        return;
+    }
+
+    let mut authorization: Option<String> = None;
+    let mut content_length: usize = 0;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header).is_err() || header.trim().is_empty() {
+            break;
+        }
+
+        if let Some((name, value)) = header.split_once(':') {
+            match name.trim().to_ascii_lowercase().as_str() {
+                "authorization" => authorization = Some(value.trim().to_string()),
+                "content-length" => content_length = value.trim().parse().unwrap_or(0),
+                _ => {}
+            }
+        }
+    }
+
+    let mut body = vec![0; content_length];
+    if content_length > 0 && reader.read_exact(&mut body).is_err() {
+        return;
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let method: &str = parts.next().unwrap_or("");
+    let target: &str = parts.next().unwrap_or("");
+
+    let (status, response) = {
+        let mut state = state.lock().unwrap();
+        respond(&mut state, method, target, authorization.as_deref(), &body)
+    };
+
+    let response_body: String = response.to_string();
+    let _ = write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason_phrase(status),
+        response_body.len(),
+        response_body
+    );
+    let _ = stream.flush();
+}
+
+fn respond(state: &mut MockState, method: &str, target: &str, authorization: Option<&str>, body: &[u8]) -> (u16, Value) {
+    let (path, query_string) = target.split_once('?').unwrap_or((target, ""));
+    let path: &str = path.strip_prefix("/v1").unwrap_or(path);
+
+    if authorization != Some(format!("Bearer {}", state.api_token).as_str()) {
+        return (401, MockError::Unauthorized.body());
+    }
+
+    if let Some(position) = state.errors.iter().position(|injected| path.starts_with(&injected.path_prefix)) {
+        let injected: InjectedError = state.errors.remove(position);
+        return (injected.error.status(), injected.error.body());
+    }
+
+    let query: HashMap<String, String> = query_string
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .map(|(key, value)| (percent_decode(key), percent_decode(value)))
+        .collect();
+    let segments: Vec<String> = path.split('/').filter(|segment| !segment.is_empty()).map(percent_decode).collect();
+    let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+
+    match segments.as_slice() {
+        ["user"] => (200, json!({ "data": { "user": { "id": state.user_id } } })),
+        ["budgets"] => {
+            let include_accounts: bool = query.get("include_accounts").map(String::as_str) == Some("true");
+            let budgets: Vec<BudgetSummary> = state.budgets
+                .iter()
+                .map(|mock_budget| budget_summary(&mock_budget.budget, include_accounts))
+                .collect();
+            (200, json!({ "data": { "budgets": budgets, "default_budget": null } }))
+        },
+        ["budgets", budget_id, rest @ ..] => {
+            let last_knowledge_of_server: Option<i64> = query.get("last_knowledge_of_server").and_then(|value| value.parse().ok());
+            let budget_id: &str = budget_id;
+            let mock_budget: &mut MockBudget = match state.budgets.iter_mut().find(|mock_budget| mock_budget.budget.id == budget_id || budget_id == "last-used") {
+                Some(mock_budget) => mock_budget,
+                None => return (404, MockError::NotFound.body())
+            };
+
+            match (method, rest) {
+                ("GET", _) => match budget_route(mock_budget, rest, &query, last_knowledge_of_server) {
+                    Some(data) => (200, json!({ "data": data })),
+                    None => (404, MockError::NotFound.body())
+                },
+                ("POST", ["transactions"]) => match serde_json::from_slice::<PostTransactionsWrapper>(body) {
+                    Ok(wrapper) => (201, json!({ "data": create_transactions(mock_budget, wrapper, &mut state.next_id) })),
+                    Err(error) => (400, error_body("400", "bad_request", &error.to_string()))
+                },
+                ("PATCH", ["transactions"]) => match serde_json::from_slice::<PatchTransactionsWrapper>(body) {
+                    Ok(wrapper) => match update_transactions(mock_budget, wrapper, &mut state.next_id) {
+                        Ok(data) => (209, json!({ "data": data })),
+                        Err(detail) => (400, error_body("400", "bad_request", &detail))
+                    },
+                    Err(error) => (400, error_body("400", "bad_request", &error.to_string()))
+                },
+                _ => (405, error_body("405", "method_not_allowed", "Method not allowed"))
+            }
+        },
+        _ => (404, error_body("404.1", "not_found", "Resource not found"))
+    }
+}
+
+fn budget_route(mock_budget: &MockBudget, segments: &[&str], query: &HashMap<String, String>, last_knowledge_of_server: Option<i64>) -> Option<Value> {
+    let budget: &BudgetDetail = &mock_budget.budget;
+    let server_knowledge: i64 = mock_budget.server_knowledge;
+
+    // `current` is the month the request is made in, as the API resolves it.
+    let current_month: String = Local::now().date_naive().format("%Y-%m-01").to_string();
+    let segments: Vec<&str> = match segments {
+        ["months", "current", rest @ ..] => ["months", current_month.as_str()].into_iter().chain(rest.iter().copied()).collect(),
+        _ => segments.to_vec()
+    };
+
+    match segments.as_slice() {
+        [] => {
+            let mut delta: BudgetDetail = budget.clone();
+            delta.accounts.retain(|entity| mock_budget.changed("accounts", &entity.id, last_knowledge_of_server));
+            delta.payees.retain(|entity| mock_budget.changed("payees", &entity.id, last_knowledge_of_server));
+            delta.payee_locations.retain(|entity| mock_budget.changed("payee_locations", &entity.id, last_knowledge_of_server));
+            delta.category_groups.retain(|entity| mock_budget.changed("category_groups", &entity.id, last_knowledge_of_server));
+            delta.categories.retain(|entity| mock_budget.changed("categories", &entity.id, last_knowledge_of_server));
+            delta.months.retain(|entity| mock_budget.changed("months", &entity.month, last_knowledge_of_server));
+            delta.transactions.retain(|entity| mock_budget.changed("transactions", &entity.id, last_knowledge_of_server));
+            delta.subtransactions.retain(|entity| mock_budget.changed("subtransactions", &entity.id, last_knowledge_of_server));
+            delta.scheduled_transactions.retain(|entity| mock_budget.changed("scheduled_transactions", &entity.id, last_knowledge_of_server));
+            delta.scheduled_subtransactions.retain(|entity| mock_budget.changed("scheduled_subtransactions", &entity.id, last_knowledge_of_server));
+            Some(json!({ "budget": delta, "server_knowledge": server_knowledge }))
+        },
+        ["settings"] => Some(json!({ "settings": { "date_format": budget.date_format, "currency_format": budget.currency_format } })),
+        ["accounts"] => {
+            let accounts: Vec<_> = budget.accounts
+                .iter()
+                .filter(|account| mock_budget.changed("accounts", &account.id, last_knowledge_of_server))
+                .collect();
+            Some(json!({ "accounts": accounts, "server_knowledge": server_knowledge }))
+        },
+        ["accounts", account_id] => budget.accounts
+            .iter()
+            .find(|account| account.id == *account_id)
+            .map(|account| json!({ "account": account })),
+        ["accounts", account_id, "transactions"] => {
+            budget.accounts.iter().find(|account| account.id == *account_id)?;
+            let transactions: Vec<TransactionDetail> = transaction_details(mock_budget, query, last_knowledge_of_server, |transaction| transaction.account_id == *account_id);
+            Some(json!({ "transactions": transactions, "server_knowledge": server_knowledge }))
+        },
+        ["categories"] => {
+            let category_groups: Vec<CategoryGroupWithCategories> = budget.category_groups
+                .iter()
+                .filter_map(|group| {
+                    let categories: Vec<_> = budget.categories
+                        .iter()
+                        .filter(|category| category.category_group_id == group.id)
+                        .filter(|category| mock_budget.changed("categories", &category.id, last_knowledge_of_server))
+                        .cloned()
+                        .collect();
+
+                    if categories.is_empty() && !mock_budget.changed("category_groups", &group.id, last_knowledge_of_server) {
+                        return None;
+                    }
+
+                    Some(CategoryGroupWithCategories {
+                        id:         group.id.clone(),
+                        name:       group.name.clone(),
+                        hidden:     group.hidden,
+                        deleted:    group.deleted,
+                        categories
+                    })
+                })
+                .collect();
+            Some(json!({ "category_groups": category_groups, "server_knowledge": server_knowledge }))
+        },
+        ["categories", category_id] => budget.categories
+            .iter()
+            .find(|category| category.id == *category_id)
+            .map(|category| json!({ "category": category })),
+        ["categories", category_id, "transactions"] => {
+            budget.categories.iter().find(|category| category.id == *category_id)?;
+            let transactions: Vec<TransactionDetail> = transaction_details(mock_budget, query, last_knowledge_of_server, |transaction| {
+                transaction.category_id.as_deref() == Some(*category_id)
+                    || budget.subtransactions
+                        .iter()
+                        .any(|subtransaction| subtransaction.transaction_id == transaction.id && subtransaction.category_id.as_deref() == Some(*category_id))
+            });
+            Some(json!({ "transactions": transactions, "server_knowledge": server_knowledge }))
+        },
+        ["payees"] => {
+            let payees: Vec<_> = budget.payees
+                .iter()
+                .filter(|payee| mock_budget.changed("payees", &payee.id, last_knowledge_of_server))
+                .collect();
+            Some(json!({ "payees": payees, "server_knowledge": server_knowledge }))
+        },
+        ["payees", payee_id] => budget.payees
+            .iter()
+            .find(|payee| payee.id == *payee_id)
+            .map(|payee| json!({ "payee": payee })),
+        ["payees", payee_id, "payee_locations"] => {
+            let payee_locations: Vec<_> = budget.payee_locations
+                .iter()
+                .filter(|location| location.payee_id == *payee_id)
+                .collect();
+            Some(json!({ "payee_locations": payee_locations }))
+        },
+        ["payees", payee_id, "transactions"] => {
+            budget.payees.iter().find(|payee| payee.id == *payee_id)?;
+            let transactions: Vec<HybridTransaction> = hybrid_transactions(mock_budget, query, last_knowledge_of_server, payee_id);
+            Some(json!({ "transactions": transactions, "server_knowledge": server_knowledge }))
+        },
+        ["payee_locations"] => Some(json!({ "payee_locations": budget.payee_locations })),
+        ["payee_locations", payee_location_id] => budget.payee_locations
+            .iter()
+            .find(|location| location.id == *payee_location_id)
+            .map(|location| json!({ "payee_location": location })),
+        ["months"] => {
+            let months: Vec<_> = budget.months
+                .iter()
+                .filter(|month| mock_budget.changed("months", &month.month, last_knowledge_of_server))
+                .map(|month| {
+                    let mut summary: Value = serde_json::to_value(month).unwrap();
+                    summary.as_object_mut().unwrap().remove("categories");
+                    summary
+                })
+                .collect();
+            Some(json!({ "months": months, "server_knowledge": server_knowledge }))
+        },
+        ["months", month] => budget.months
+            .iter()
+            .find(|detail| detail.month == *month)
+            .map(|detail| json!({ "month": detail })),
+        ["months", month, "transactions"] => {
+            let month_prefix: &str = month.get(..7)?;
+            let transactions: Vec<TransactionDetail> = transaction_details(mock_budget, query, last_knowledge_of_server, |transaction| transaction.date.starts_with(month_prefix));
+            Some(json!({ "transactions": transactions, "server_knowledge": server_knowledge }))
+        },
+        ["months", month, "categories", category_id] => budget.months
+            .iter()
+            .find(|detail| detail.month == *month)?
+            .categories
+            .iter()
+            .find(|category| category.id == *category_id)
+            .map(|category| json!({ "category": category })),
+        ["transactions"] => {
+            let transactions: Vec<TransactionDetail> = transaction_details(mock_budget, query, last_knowledge_of_server, |_| true);
+            Some(json!({ "transactions": transactions, "server_knowledge": server_knowledge }))
+        },
+        ["transactions", transaction_id] => budget.transactions
+            .iter()
+            .find(|transaction| transaction.id == *transaction_id)
+            .map(|transaction| json!({ "transaction": transaction_detail(budget, transaction) })),
+        ["scheduled_transactions"] => {
+            let scheduled_transactions: Vec<ScheduledTransactionDetail> = budget.scheduled_transactions
+                .iter()
+                .filter(|scheduled| mock_budget.changed("scheduled_transactions", &scheduled.id, last_knowledge_of_server))
+                .map(|scheduled| scheduled_transaction_detail(budget, scheduled))
+                .collect();
+            Some(json!({ "scheduled_transactions": scheduled_transactions, "server_knowledge": server_knowledge }))
+        },
+        ["scheduled_transactions", scheduled_transaction_id] => budget.scheduled_transactions
+            .iter()
+            .find(|scheduled| scheduled.id == *scheduled_transaction_id)
+            .map(|scheduled| json!({ "scheduled_transaction": scheduled_transaction_detail(budget, scheduled) })),
+        _ => None
+    }
+}
+
+// Rows whose import_id already exists on the same account are reported back as duplicates, as the API does.
+fn create_transactions(mock_budget: &mut MockBudget, wrapper: PostTransactionsWrapper, next_id: &mut u64) -> Value {
+    let single: bool = wrapper.transaction.is_some();
+    let saves: Vec<SaveTransaction> = wrapper.transaction.into_iter().chain(wrapper.transactions.into_iter().flatten()).collect();
+    let mut transaction_ids: Vec<String> = vec![];
+    let mut duplicate_import_ids: Vec<String> = vec![];
+
+    mock_budget.update(|budget| {
+        for save in saves {
+            if let Some(import_id) = &save.import_id {
+                let duplicate: bool = budget.transactions.iter().any(|transaction| {
+                    !transaction.deleted && transaction.account_id == save.account_id && transaction.import_id.as_ref() == Some(import_id)
+                });
+
+                if duplicate {
+                    duplicate_import_ids.push(import_id.clone());
+                    continue;
+                }
+            }
+
+            let payee_id: Option<String> = match (&save.payee_id, &save.payee_name) {
+                (Some(payee_id), _) => Some(payee_id.clone()),
+                (None, Some(payee_name)) => Some(find_or_create_payee(budget, payee_name, next_id)),
+                (None, None) => None
+            };
+
+            let id: String = format!("mock-transaction-{}", next_id);
+            *next_id += 1;
+
+            for (index, subtransaction) in save.subtransactions.iter().flatten().enumerate() {
+                let subtransaction_payee_id: Option<String> = match (&subtransaction.payee_id, &subtransaction.payee_name) {
+                    (Some(payee_id), _) => Some(payee_id.clone()),
+                    (None, Some(payee_name)) => Some(find_or_create_payee(budget, payee_name, next_id)),
+                    (None, None) => None
+                };
+
+                budget.subtransactions.push(SubTransaction {
+                    id:                         format!("{}-{}", id, index),
+                    transaction_id:             id.clone(),
+                    amount:                     subtransaction.amount,
+                    memo:                       subtransaction.memo.clone(),
+                    payee_name:                 payee_name(budget, subtransaction_payee_id.as_deref()),
+                    payee_id:                   subtransaction_payee_id,
+                    category_name:              category_name(budget, subtransaction.category_id.as_deref()),
+                    category_id:                subtransaction.category_id.clone(),
+                    transfer_account_id:        None,
+                    transfer_transaction_id:    None,
+                    deleted:                    false
+                });
+            }
+
+            budget.transactions.push(TransactionSummary {
+                id:                         id.clone(),
+                date:                       save.date,
+                amount:                     save.amount,
+                memo:                       save.memo,
+                cleared:                    save.cleared.unwrap_or(ClearedStatus::Uncleared),
+                approved:                   save.approved.unwrap_or(false),
+                flag_color:                 save.flag_color,
+                account_id:                 save.account_id,
+                payee_id,
+                category_id:                save.category_id,
+                transfer_account_id:        None,
+                transfer_transaction_id:    None,
+                matched_transaction_id:     None,
+                import_id:                  save.import_id,
+                import_payee_name:          None,
+                import_payee_name_original: None,
+                debt_transaction_type:      None,
+                deleted:                    false
+            });
+            transaction_ids.push(id);
+        }
+    });
+
+    let budget: &BudgetDetail = &mock_budget.budget;
+    let transactions: Vec<TransactionDetail> = transaction_ids
+        .iter()
+        .filter_map(|id| budget.transactions.iter().find(|transaction| transaction.id == *id))
+        .map(|transaction| transaction_detail(budget, transaction))
+        .collect();
+
+    json!({
+        "transaction_ids": transaction_ids,
+        "transaction": if single { transactions.first().cloned() } else { None },
+        "transactions": if single { None } else { Some(transactions) },
+        "duplicate_import_ids": duplicate_import_ids,
+        "server_knowledge": mock_budget.server_knowledge
+    })
+}
+
+// Applies the fields each save sets to the transaction it names by `id`, or by `import_id`
+// when the id is left out. Unknown transactions reject the whole request, like the API does.
+fn update_transactions(mock_budget: &mut MockBudget, wrapper: PatchTransactionsWrapper, next_id: &mut u64) -> Result<Value, String> {
+    let budget: &BudgetDetail = &mock_budget.budget;
+    let mut transaction_ids: Vec<String> = vec![];
+    for save in &wrapper.transactions {
+        let found: Option<&TransactionSummary> = budget.transactions.iter().find(|transaction| {
+            !transaction.deleted && match (&save.id, &save.import_id) {
+                (Some(id), _) => transaction.id == *id,
+                (None, Some(import_id)) => transaction.import_id.as_ref() == Some(import_id),
+                (None, None) => false
+            }
+        });
+
+        match found {
+            Some(transaction) => transaction_ids.push(transaction.id.clone()),
+            None => return Err(format!("Transaction `{}` does not exist.", save.id.as_deref().or(save.import_id.as_deref()).unwrap_or_default()))
+        }
+    }
+
+    mock_budget.update(|budget| {
+        for (save, id) in wrapper.transactions.into_iter().zip(&transaction_ids) {
+            let payee_id: Option<String> = match (save.payee_id, save.payee_name) {
+                (Some(payee_id), _) => Some(payee_id),
+                (None, Some(payee_name)) => Some(find_or_create_payee(budget, &payee_name, next_id)),
+                (None, None) => None
+            };
+
+            let transaction: &mut TransactionSummary = budget.transactions.iter_mut().find(|transaction| transaction.id == *id).unwrap();
+            if let Some(account_id) = save.account_id { transaction.account_id = account_id; }
+            if let Some(date) = save.date { transaction.date = date; }
+            if let Some(amount) = save.amount { transaction.amount = amount; }
+            if let Some(payee_id) = payee_id { transaction.payee_id = Some(payee_id); }
+            if let Some(category_id) = save.category_id { transaction.category_id = Some(category_id); }
+            if let Some(memo) = save.memo { transaction.memo = Some(memo); }
+            if let Some(cleared) = save.cleared { transaction.cleared = cleared; }
+            if let Some(approved) = save.approved { transaction.approved = approved; }
+            if let Some(flag_color) = save.flag_color { transaction.flag_color = Some(flag_color); }
+            if let (Some(_), Some(import_id)) = (save.id, save.import_id) { transaction.import_id = Some(import_id); }
+        }
+    });
+
+    let budget: &BudgetDetail = &mock_budget.budget;
+    let transactions: Vec<TransactionDetail> = transaction_ids
+        .iter()
+        .filter_map(|id| budget.transactions.iter().find(|transaction| transaction.id == *id))
+        .map(|transaction| transaction_detail(budget, transaction))
+        .collect();
+
+    Ok(json!({
+        "transaction_ids": transaction_ids,
+        "transactions": transactions,
+        "server_knowledge": mock_budget.server_knowledge
+    }))
+}
+
+fn find_or_create_payee(budget: &mut BudgetDetail, payee_name: &str, next_id: &mut u64) -> String {
+    if let Some(payee) = budget.payees.iter().find(|payee| !payee.deleted && payee.name == payee_name) {
+        return payee.id.clone();
+    }
+
+    let id: String = format!("mock-payee-{}", next_id);
+    *next_id += 1;
+    budget.payees.push(Payee { id: id.clone(), name: payee_name.to_string(), transfer_account_id: None, deleted: false });
+    id
+}
+
+fn matches_transaction_query(transaction: &TransactionSummary, query: &HashMap<String, String>) -> bool {
+    if let Some(since_date) = query.get("since_date") {
+        if transaction.date.as_str() < since_date.as_str() {
+            return false;
+        }
+    }
+
+    match query.get("type").map(String::as_str) {
+        Some("uncategorized") => transaction.category_id.is_none() && transaction.transfer_account_id.is_none(),
+        Some("unapproved") => !transaction.approved,
+        _ => true
+    }
+}
+
+fn transaction_details<F: Fn(&TransactionSummary) -> bool>(mock_budget: &MockBudget, query: &HashMap<String, String>, last_knowledge_of_server: Option<i64>, scope: F) -> Vec<TransactionDetail> {
+    mock_budget.budget.transactions
+        .iter()
+        .filter(|transaction| mock_budget.changed("transactions", &transaction.id, last_knowledge_of_server))
+        .filter(|transaction| matches_transaction_query(transaction, query))
+        .filter(|transaction| scope(transaction))
+        .map(|transaction| transaction_detail(&mock_budget.budget, transaction))
+        .collect()
+}
+
+fn hybrid_transactions(mock_budget: &MockBudget, query: &HashMap<String, String>, last_knowledge_of_server: Option<i64>, payee_id: &str) -> Vec<HybridTransaction> {
+    let budget: &BudgetDetail = &mock_budget.budget;
+    let mut transactions: Vec<HybridTransaction> = vec![];
+
+    for transaction in budget.transactions.iter().filter(|transaction| {
+        mock_budget.changed("transactions", &transaction.id, last_knowledge_of_server) && matches_transaction_query(transaction, query)
+    }) {
+        let detail: TransactionDetail = transaction_detail(budget, transaction);
+
+        if transaction.payee_id.as_deref() == Some(payee_id) {
+            transactions.push(HybridTransaction {
+                id:                         detail.id.clone(),
+                date:                       detail.date.clone(),
+                amount:                     detail.amount,
+                memo:                       detail.memo.clone(),
+                cleared:                    detail.cleared.clone(),
+                approved:                   detail.approved,
+                flag_color:                 detail.flag_color.clone(),
+                account_id:                 detail.account_id.clone(),
+                payee_id:                   detail.payee_id.clone(),
+                category_id:                detail.category_id.clone(),
+                transfer_account_id:        detail.transfer_account_id.clone(),
+                matched_transaction_id:     detail.matched_transaction_id.clone(),
+                import_id:                  detail.import_id.clone(),
+                import_payee_name:          detail.import_payee_name.clone(),
+                import_payee_name_original: detail.import_payee_name_original.clone(),
+                debt_transaction_type:      detail.debt_transaction_type.clone(),
+                deleted:                    detail.deleted,
+                r#type:                     TransactionType::Transaction,
+                parent_transaction_id:      None,
+                account_name:               detail.account_name.clone(),
+                payee_name:                 detail.payee_name.clone(),
+                category_name:              detail.category_name.clone()
+            });
+        }
+
+        for subtransaction in detail.subtransactions.iter().filter(|subtransaction| subtransaction.payee_id.as_deref() == Some(payee_id)) {
+            transactions.push(HybridTransaction {
+                id:                         subtransaction.id.clone(),
+                date:                       detail.date.clone(),
+                amount:                     subtransaction.amount,
+                memo:                       subtransaction.memo.clone(),
+                cleared:                    detail.cleared.clone(),
+                approved:                   detail.approved,
+                flag_color:                 detail.flag_color.clone(),
+                account_id:                 detail.account_id.clone(),
+                payee_id:                   subtransaction.payee_id.clone(),
+                category_id:                subtransaction.category_id.clone(),
+                transfer_account_id:        subtransaction.transfer_account_id.clone(),
+                matched_transaction_id:     None,
+                import_id:                  None,
+                import_payee_name:          None,
+                import_payee_name_original: None,
+                debt_transaction_type:      None,
+                deleted:                    subtransaction.deleted,
+                r#type:                     TransactionType::SubTransaction,
+                parent_transaction_id:      Some(detail.id.clone()),
+                account_name:               detail.account_name.clone(),
+                payee_name:                 subtransaction.payee_name.clone(),
+                category_name:              subtransaction.category_name.clone()
+            });
+        }
+    }
+
+    transactions
+}
+
+fn account_name(budget: &BudgetDetail, account_id: &str) -> String {
+    budget.accounts
+        .iter()
+        .find(|account| account.id == account_id)
+        .map(|account| account.name.clone())
+        .unwrap_or_default()
+}
+
+fn payee_name(budget: &BudgetDetail, payee_id: Option<&str>) -> Option<String> {
+    budget.payees.iter().find(|payee| Some(payee.id.as_str()) == payee_id).map(|payee| payee.name.clone())
+}
+
+fn category_name(budget: &BudgetDetail, category_id: Option<&str>) -> Option<String> {
+    budget.categories.iter().find(|category| Some(category.id.as_str()) == category_id).map(|category| category.name.clone())
+}
+
+fn transaction_detail(budget: &BudgetDetail, transaction: &TransactionSummary) -> TransactionDetail {
+    TransactionDetail {
+        id:                         transaction.id.clone(),
+        date:                       transaction.date.clone(),
+        amount:                     transaction.amount,
+        memo:                       transaction.memo.clone(),
+        cleared:                    transaction.cleared.clone(),
+        approved:                   transaction.approved,
+        flag_color:                 transaction.flag_color.clone(),
+        account_id:                 transaction.account_id.clone(),
+        payee_id:                   transaction.payee_id.clone(),
+        category_id:                transaction.category_id.clone(),
+        transfer_account_id:        transaction.transfer_account_id.clone(),
+        matched_transaction_id:     transaction.matched_transaction_id.clone(),
+        import_id:                  transaction.import_id.clone(),
+        import_payee_name:          transaction.import_payee_name.clone(),
+        import_payee_name_original: transaction.import_payee_name_original.clone(),
+        debt_transaction_type:      transaction.debt_transaction_type.clone(),
+        deleted:                    transaction.deleted,
+        account_name:               account_name(budget, &transaction.account_id),
+        payee_name:                 payee_name(budget, transaction.payee_id.as_deref()),
+        category_name:              category_name(budget, transaction.category_id.as_deref()),
+        subtransactions:            budget.subtransactions
+            .iter()
+            .filter(|subtransaction| subtransaction.transaction_id == transaction.id)
+            .cloned()
+            .collect()
+    }
+}
+
+fn scheduled_transaction_detail(budget: &BudgetDetail, scheduled: &ScheduledTransactionSummary) -> ScheduledTransactionDetail {
+    ScheduledTransactionDetail {
+        id:                     scheduled.id.clone(),
+        date_first:             scheduled.date_first.clone(),
+        date_next:              scheduled.date_next.clone(),
+        frequency:              scheduled.frequency.clone(),
+        amount:                 scheduled.amount,
+        memo:                   scheduled.memo.clone(),
+        flag_color:             scheduled.flag_color.clone(),
+        account_id:             scheduled.account_id.clone(),
+        payee_id:               scheduled.payee_id.clone(),
+        category_id:            scheduled.category_id.clone(),
+        transfer_account_id:    scheduled.transfer_account_id.clone(),
+        deleted:                scheduled.deleted,
+        account_name:           account_name(budget, &scheduled.account_id),
+        payee_name:             payee_name(budget, scheduled.payee_id.as_deref()),
+        category_name:          category_name(budget, scheduled.category_id.as_deref()),
+        subtransactions:        budget.scheduled_subtransactions
+            .iter()
+            .filter(|subtransaction| subtransaction.scheduled_transaction_id == scheduled.id)
+            .cloned()
+            .collect()
+    }
+}
+
+fn budget_summary(budget: &BudgetDetail, include_accounts: bool) -> BudgetSummary {
+    BudgetSummary {
+        id:                 budget.id.clone(),
+        name:               budget.name.clone(),
+        last_modified_on:   Some(budget.last_modified_on.clone()),
+        first_month:        Some(budget.first_month.clone()),
+        last_month:         Some(budget.last_month.clone()),
+        date_format:        Some(budget.date_format.clone()),
+        currency_format:    Some(budget.currency_format.clone()),
+        accounts:           if include_accounts { Some(budget.accounts.clone()) } else { None }
+    }
+}
+
+fn entities(budget: &BudgetDetail) -> Vec<(&'static str, String, Value)> {
+    fn collect<T: Serialize>(entities: &mut Vec<(&'static str, String, Value)>, collection: &'static str, items: &[T], id: fn(&T) -> &str) {
+        for item in items {
+            entities.push((collection, id(item).to_string(), serde_json::to_value(item).unwrap()));
+        }
+    }
+
+    let mut entities: Vec<(&'static str, String, Value)> = vec![];
+    collect(&mut entities, "accounts", &budget.accounts, |entity| &entity.id);
+    collect(&mut entities, "payees", &budget.payees, |entity| &entity.id);
+    collect(&mut entities, "payee_locations", &budget.payee_locations, |entity| &entity.id);
+    collect(&mut entities, "category_groups", &budget.category_groups, |entity| &entity.id);
+    collect(&mut entities, "categories", &budget.categories, |entity| &entity.id);
+    collect(&mut entities, "months", &budget.months, |entity| &entity.month);
+    collect(&mut entities, "transactions", &budget.transactions, |entity| &entity.id);
+    collect(&mut entities, "subtransactions", &budget.subtransactions, |entity| &entity.id);
+    collect(&mut entities, "scheduled_transactions", &budget.scheduled_transactions, |entity| &entity.id);
+    collect(&mut entities, "scheduled_subtransactions", &budget.scheduled_subtransactions, |entity| &entity.id);
+    entities
+}
+
+fn error_body(id: &str, name: &str, detail: &str) -> Value {
+    json!({ "error": { "id": id, "name": name, "detail": detail } })
+}
+
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        209 => "Updated",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        429 => "Too Many Requests",
+        _ => "Internal Server Error"
+    }
+}
+
+fn percent_decode(value: &str) -> String {
+    let bytes: &[u8] = value.as_bytes();
+    let mut decoded: Vec<u8> = Vec::with_capacity(bytes.len());
+    let mut index: usize = 0;
+
+    while index < bytes.len() {
+        if bytes[index] == b'%' && index + 2 < bytes.len() {
+            let hex: &str = std::str::from_utf8(&bytes[index + 1..index + 3]).unwrap_or("");
+            if let Ok(byte) = u8::from_str_radix(hex, 16) {
+                decoded.push(byte);
+                index += 3;
+                continue;
+            }
+        }
+
+        decoded.push(bytes[index]);
+        index += 1;
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
+}
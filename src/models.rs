@@ -1,39 +1,39 @@
 use std::collections::HashMap;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ErrorResponse {
     pub error: ErrorDetail
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ErrorDetail {
     pub id:     String,
     pub name:   String,
     pub detail: String
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UserData {
     pub user: User
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UserResponse {
     pub data: UserData
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct User {
     pub id: String
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DateFormat {
     pub format: String
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CurrencyFormat {
     pub iso_code:           String,
     pub example_format:	    String,
@@ -45,18 +45,18 @@ pub struct CurrencyFormat {
     pub display_symbol:     bool
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BudgetSummaryResponseData {
     pub budgets:        Vec<BudgetSummary>,
     pub default_budget: Option<BudgetSummary>
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BudgetSummaryResponse {
     pub data: BudgetSummaryResponseData
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BudgetSummary {
     pub id:                 String,
     pub name:               String,
@@ -68,18 +68,18 @@ pub struct BudgetSummary {
     pub accounts:           Option<Vec<Account>>
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BudgetDetailResponseData {
     pub budget:             BudgetDetail,
     pub server_knowledge:   i64
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BudgetDetailResponse {
     pub data: BudgetDetailResponseData
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BudgetDetail {
     pub id:                         String,
     pub name:                       String,
@@ -100,44 +100,44 @@ pub struct BudgetDetail {
     pub scheduled_subtransactions:  Vec<ScheduledSubTransaction>
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BudgetSettingsResponseData {
     pub settings: BudgetSettings
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BudgetSettingsResponse {
     pub data: BudgetSettingsResponseData
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BudgetSettings {
     pub date_format: DateFormat,
     pub currency_format: CurrencyFormat
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AccountsResponseData {
     pub accounts: Vec<Account>,
     pub server_knowledge: i64
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AccountsResponse {
     pub data: AccountsResponseData
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AccountResponseData {
     pub account: Account
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AccountResponse {
     pub data: AccountResponseData
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum AccountType {
     Checking,
@@ -155,7 +155,18 @@ pub enum AccountType {
     OtherDebt
 }
 
-#[derive(Debug, Deserialize)]
+impl AccountType {
+    // Debt and loan accounts. YNAB keeps their balances negative while money is owed.
+    pub fn is_liability(&self) -> bool {
+        match self {
+            AccountType::CreditCard | AccountType::LineOfCredit | AccountType::OtherLiability | AccountType::Mortgage | AccountType::AutoLoan
+                | AccountType::StudentLoan | AccountType::PersonalLoan | AccountType::MedicalDebt | AccountType::OtherDebt => true,
+            AccountType::Checking | AccountType::Savings | AccountType::Cash | AccountType::OtherAsset => false
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Account {
     pub id:                     String,
     pub name:                   String,
@@ -177,46 +188,46 @@ pub struct Account {
     pub deleted:                bool
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PostAccountWrapper {
     pub account: SaveAccount
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SaveAccount {
     pub name: String,
     pub account_type: AccountType,
     pub balance: i64
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LoanAccountPeriodicValue {
     #[serde(flatten)]
     pub map: HashMap<String, i64>
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CategoriesResponseData {
     pub category_groups: Vec<CategoryGroupWithCategories>,
     pub server_knowledge: i64,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CategoriesResponse {
     pub data: CategoriesResponseData
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CategoryResponseData {
     pub category: Category
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CategoryResponse {
     pub data: CategoryResponseData
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CategoryGroupWithCategories {
     pub id:         String,
     pub name:       String,
@@ -225,7 +236,7 @@ pub struct CategoryGroupWithCategories {
     pub categories: Vec<Category>
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CategoryGroup {
     pub id:         String,
     pub name:       String,
@@ -233,7 +244,7 @@ pub struct CategoryGroup {
     pub deleted:    bool
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum GoalType {
     TB,
     TBD,
@@ -242,7 +253,7 @@ pub enum GoalType {
     DEBT
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Category {
     pub id:                         String,
     pub category_group_id:          String,
@@ -269,39 +280,39 @@ pub struct Category {
     pub deleted:                    bool
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SaveCategoryResponseData {
     pub category:           Category,
     pub server_knowledge:   i64
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SaveCategoryResponse {
     pub data: SaveCategoryResponseData
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PayeesResponseData {
     pub payees:             Vec<Payee>,
     pub server_knowledge:   i64
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PayeesResponse {
     pub data: PayeesResponseData
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PayeeResponseData {
     pub payee: Payee
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PayeeResponse {
     pub data: PayeeResponseData
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Payee {
     pub id:                     String,
     pub name:                   String,
@@ -309,27 +320,27 @@ pub struct Payee {
     pub deleted:                bool
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PayeeLocationsResponseData {    
     pub payee_locations: Vec<PayeeLocation>
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PayeeLocationsResponse {
     pub data: PayeeLocationsResponseData
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PayeeLocationResponseData {
     pub payee_location: PayeeLocation
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PayeeLocationResponse {
     pub data: PayeeLocationResponseData
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PayeeLocation {
     pub id:         String,
     pub payee_id:   String,
@@ -338,40 +349,40 @@ pub struct PayeeLocation {
     pub deleted:    bool
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TransactionsResponseData {
     pub transactions:       Vec<TransactionDetail>,
     pub server_knowledge:   i64
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TransactionsResponse {
     pub data: TransactionsResponseData
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HybridTransactionsResponseData {
     pub transactions: Vec<HybridTransaction>,
     pub server_knowledge: Option<i64>
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HybridTransactionsResponse {
     pub data: HybridTransactionsResponseData
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PutTransactionWrapper {
     pub transaction: SaveTransaction
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PostTransactionsWrapper {
     pub transaction:    Option<SaveTransaction>,
     pub transactions:   Option<Vec<SaveTransaction>>
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum ClearedStatus {
     Cleared,
@@ -379,7 +390,7 @@ pub enum ClearedStatus {
     Reconciled
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum FlagColor {
     Red,
@@ -390,45 +401,67 @@ pub enum FlagColor {
     Purple
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SaveTransaction {
     pub account_id:         String,
     pub date:               String,
     pub amount:             i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub payee_id:           Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub payee_name:         Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub category_id:        Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub memo:               Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub cleared:            Option<ClearedStatus>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub approved:           Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub flag_color:         Option<FlagColor>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub import_id:          Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub subtransactions:    Option<Vec<SaveSubTransaction>>
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PatchTransactionsWrapper {
     pub transactions: Vec<SaveTransactionWithId>
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SaveTransactionWithId {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub id:                 Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub account_id:         Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub date:               Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub amount:             Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub payee_id:           Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub payee_name:         Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub category_id:        Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub memo:               Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub cleared:            Option<ClearedStatus>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub approved:           Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub flag_color:         Option<FlagColor>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub import_id:          Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub subtransactions:    Option<Vec<SaveSubTransaction>>
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SaveTransactionWithOptionalFields {
     pub account_id:         Option<String>,
     pub date:               Option<String>,
@@ -444,16 +477,20 @@ pub struct SaveTransactionWithOptionalFields {
     pub subtransactions:    Option<Vec<SaveSubTransaction>>
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SaveSubTransaction {
     pub amount:         i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub payee_id:       Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub payee_name:     Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub category_id:    Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub memo:           Option<String>
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SaveTransactionsResponseData {
     pub transaction_ids:        Vec<String>,
     pub transaction:            Option<TransactionDetail>,
@@ -462,22 +499,22 @@ pub struct SaveTransactionsResponseData {
     pub server_knowledge:       i64
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SaveTransactionsResponse {
     pub data: SaveTransactionsResponseData
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TransactionResponseData {
     pub transaction: TransactionDetail
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TransactionResponse {
     pub data: TransactionResponseData
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum DebtTransactionType {
     Payment,
@@ -485,12 +522,13 @@ pub enum DebtTransactionType {
     Fee,
     Interest,
     Escrow,
+    #[serde(rename = "balanceAdjustment")]
     BalancedAdjustment,
     Credit,
     Charge
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TransactionSummary {
     pub id:                         String,
     pub date:                       String,
@@ -512,7 +550,7 @@ pub struct TransactionSummary {
     pub deleted:                    bool
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TransactionDetail {
     pub id:                         String,
     pub date:                       String,
@@ -537,14 +575,15 @@ pub struct TransactionDetail {
     pub subtransactions:            Vec<SubTransaction>
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum TransactionType {
     Transaction,
+    #[serde(rename = "subtransaction")]
     SubTransaction
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HybridTransaction {
     pub id:                         String,
     pub date:                       String,
@@ -570,48 +609,48 @@ pub struct HybridTransaction {
     pub category_name:              Option<String>
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PatchMonthCategoryWrapper {
     pub category: SaveMonthCategory
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SaveMonthCategory {
     pub budgeted: i64
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TransactionsImportResponseData {
     pub transaction_ids: Vec<String>
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TransactionsImportResponse {
     pub data: TransactionsImportResponseData
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BulkResponseDataBulk {
     pub transaction_ids:        Vec<String>,
     pub duplicate_import_ids:   Vec<String>
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BulkResponseData {
     pub bulk: BulkResponseDataBulk
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BulkResponse {
     pub data: BulkResponseData
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BulkTransactions {
     pub transactions: Vec<SaveTransaction>
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SubTransaction {
     pub id:                         String,
     pub transaction_id:             String,
@@ -626,28 +665,28 @@ pub struct SubTransaction {
     pub deleted:                    bool
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScheduledTransactionsResponseData {
     pub scheduled_transactions: Vec<ScheduledTransactionDetail>,
     pub server_knowledge:       i64
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScheduledTransactionsResponse {
     pub data: ScheduledTransactionsResponseData
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScheduledTransactionResponseData {
     pub scheduled_transaction: ScheduledTransactionDetail
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScheduledTransactionResponse {
     pub data: ScheduledTransactionResponseData
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum ScheduleFrequency {
     Never,
@@ -665,7 +704,7 @@ pub enum ScheduleFrequency {
     EveryOtherYear
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScheduledTransactionSummary {
     pub id:                     String,
     pub date_first:             String,
@@ -681,7 +720,7 @@ pub struct ScheduledTransactionSummary {
     pub deleted:                bool
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScheduledTransactionDetail {
     pub id:                     String,
     pub date_first:             String,
@@ -701,7 +740,7 @@ pub struct ScheduledTransactionDetail {
     pub subtransactions:        Vec<ScheduledSubTransaction>
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScheduledSubTransaction {
     pub id:                         String,
     pub scheduled_transaction_id:   String,
@@ -713,28 +752,28 @@ pub struct ScheduledSubTransaction {
     pub deleted:                    bool
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MonthSummariesResponseData {
     pub months:             Vec<MonthSummary>,
     pub server_knowledge:   i64
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MonthSummariesResponse {
     pub data: MonthSummariesResponseData
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MonthDetailResponseData {
     pub month: MonthDetail
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MonthDetailResponse {
     pub data: MonthDetailResponseData
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MonthSummary {
     pub month:          String,
     pub note:           Option<String>,
@@ -746,7 +785,7 @@ pub struct MonthSummary {
     pub deleted:        bool
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MonthDetail {
     pub month:          String,
     pub note:           Option<String>,
@@ -0,0 +1,101 @@
+use chrono::NaiveDate;
+
+use crate::client::Endpoint;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransactionFilter {
+    Uncategorized,
+    Unapproved
+}
+
+impl TransactionFilter {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TransactionFilter::Uncategorized => "uncategorized",
+            TransactionFilter::Unapproved => "unapproved"
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TransactionScope {
+    Budget,
+    Account(String),
+    Category(String),
+    Payee(String),
+    Month(NaiveDate)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransactionQuery {
+    pub budget_id:                  String,
+    pub scope:                      TransactionScope,
+    pub since_date:                 Option<NaiveDate>,
+    pub filter:                     Option<TransactionFilter>,
+    pub last_knowledge_of_server:   Option<i64>
+}
+
+impl TransactionQuery {
+    pub fn new(budget_id: &str) -> TransactionQuery {
+        TransactionQuery {
+            budget_id:                  budget_id.to_string(),
+            scope:                      TransactionScope::Budget,
+            since_date:                 None,
+            filter:                     None,
+            last_knowledge_of_server:   None
+        }
+    }
+
+    pub fn account(mut self, account_id: &str) -> TransactionQuery {
+        self.scope = TransactionScope::Account(account_id.to_string());
+        self
+    }
+
+    pub fn category(mut self, category_id: &str) -> TransactionQuery {
+        self.scope = TransactionScope::Category(category_id.to_string());
+        self
+    }
+
+    pub fn payee(mut self, payee_id: &str) -> TransactionQuery {
+        self.scope = TransactionScope::Payee(payee_id.to_string());
+        self
+    }
+
+    pub fn month(mut self, month: NaiveDate) -> TransactionQuery {
+        self.scope = TransactionScope::Month(month);
+        self
+    }
+
+    pub fn since_date(mut self, since_date: NaiveDate) -> TransactionQuery {
+        self.since_date = Some(since_date);
+        self
+    }
+
+    pub fn filter(mut self, filter: TransactionFilter) -> TransactionQuery {
+        self.filter = Some(filter);
+        self
+    }
+
+    pub fn last_knowledge(mut self, last_knowledge_of_server: i64) -> TransactionQuery {
+        self.last_knowledge_of_server = Some(last_knowledge_of_server);
+        self
+    }
+
+    pub(crate) fn endpoint(&self) -> Endpoint {
+        let endpoint: Endpoint = Endpoint::new("budgets").id("budget id", &self.budget_id);
+
+        let endpoint: Endpoint = match &self.scope {
+            TransactionScope::Budget => endpoint,
+            TransactionScope::Account(account_id) => endpoint.segment("accounts").id("account id", account_id),
+            TransactionScope::Category(category_id) => endpoint.segment("categories").id("category id", category_id),
+            TransactionScope::Payee(payee_id) => endpoint.segment("payees").id("payee id", payee_id),
+            TransactionScope::Month(month) => endpoint.segment("months").month(&month.format("%Y-%m-01").to_string())
+        };
+
+        endpoint
+            .segment("transactions")
+            .parameter("since_date", self.since_date.map(|date| date.format("%Y-%m-%d")))
+            .parameter("type", self.filter.map(|filter| filter.as_str()))
+            .parameter("last_knowledge_of_server", self.last_knowledge_of_server)
+    }
+}
@@ -0,0 +1,66 @@
+use std::fmt;
+
+use chrono::{Datelike, Months, NaiveDate};
+
+use crate::models::ErrorResponse;
+
+pub mod net_worth;
+pub mod spending;
+
+#[derive(Debug)]
+pub enum ReportError {
+    InvalidMonth(String),
+    InvalidDate(String),
+    // The last month comes before the first.
+    EmptyRange { first: String, last: String },
+    // The snapshot has no data for a month in the range.
+    MissingMonth(String),
+    Request { endpoint: &'static str, error_response: ErrorResponse }
+}
+
+impl fmt::Display for ReportError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReportError::InvalidMonth(value) => write!(formatter, "`{}` is not a month", value),
+            ReportError::InvalidDate(value) => write!(formatter, "`{}` is not an ISO date", value),
+            ReportError::EmptyRange { first, last } => write!(formatter, "{} comes after {}", first, last),
+            ReportError::MissingMonth(month) => write!(formatter, "budget has no month {}", month),
+            ReportError::Request { endpoint, error_response } => write!(formatter, "{} failed: {}", endpoint, error_response.error.detail)
+        }
+    }
+}
+
+impl std::error::Error for ReportError {}
+
+fn first_of_month(value: &str) -> Result<NaiveDate, ReportError> {
+    let date: String = if value.len() == 7 { format!("{}-01", value) } else { value.to_string() };
+    NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.with_day0(0))
+        .ok_or_else(|| ReportError::InvalidMonth(value.to_string()))
+}
+
+// Every month from `first` to `last`, both included, as YNAB names them (`2023-06-01`). Either end
+// may be given as `2023-06` or as any date in the month.
+pub fn month_range(first: &str, last: &str) -> Result<Vec<String>, ReportError> {
+    let (start, end) = (first_of_month(first)?, first_of_month(last)?);
+    if end < start {
+        return Err(ReportError::EmptyRange { first: first.to_string(), last: last.to_string() });
+    }
+
+    let mut months: Vec<String> = vec![];
+    let mut month: NaiveDate = start;
+    while month <= end {
+        months.push(month.format("%Y-%m-%d").to_string());
+        month = month.checked_add_months(Months::new(1)).ok_or_else(|| ReportError::InvalidMonth(last.to_string()))?;
+    }
+    Ok(months)
+}
+
+// Rounded to the nearest milliunit.
+pub(crate) fn average(total: i64, count: usize) -> i64 {
+    if count == 0 {
+        return 0;
+    }
+    (total as f64 / count as f64).round() as i64
+}
@@ -0,0 +1,171 @@
+use chrono::{Datelike, Days, Months, NaiveDate};
+use either::{Left, Right};
+use serde::Serialize;
+
+use crate::client::Client;
+use crate::models::{Account, AccountsResponse, BudgetDetail, TransactionDetail};
+use crate::query::TransactionQuery;
+use crate::reports::ReportError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Interval {
+    Daily,
+    // The last day of each month.
+    Monthly
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AccountSeries {
+    pub account_id: String,
+    pub name:       String,
+    pub liability:  bool,
+    pub closed:     bool,
+    // One balance per point of the series, in milliunits.
+    pub balances:   Vec<i64>
+}
+
+// Liabilities are what is owed, so a card with a balance of -500.00 adds 500.00 to them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct NetWorthPoint {
+    pub date:           String,
+    pub assets:         i64,
+    pub liabilities:    i64,
+    pub net_worth:      i64
+}
+
+// Balance history of every account, rebuilt by walking transactions backward from today's
+// balance. A point's balance is the balance at the end of its day. Closed accounts stay in the
+// series: before they were closed they count like any other account, and after their last
+// transaction their balance is zero, as YNAB only closes accounts that are settled. Deleted
+// accounts and transactions are left out.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct NetWorthSeries {
+    pub interval:   Interval,
+    pub points:     Vec<NetWorthPoint>,
+    pub accounts:   Vec<AccountSeries>
+}
+
+fn parse_date(value: &str) -> Result<NaiveDate, ReportError> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| ReportError::InvalidDate(value.to_string()))
+}
+
+// Every day from `first` to `last`, or the end of every month they touch with the final point on
+// `last` itself.
+fn point_dates(interval: Interval, first: &str, last: &str) -> Result<Vec<String>, ReportError> {
+    let (start, end) = (parse_date(first)?, parse_date(last)?);
+    if end < start {
+        return Err(ReportError::EmptyRange { first: first.to_string(), last: last.to_string() });
+    }
+
+    let next = |date: NaiveDate| -> Option<NaiveDate> {
+        match interval {
+            Interval::Daily => date.checked_add_days(Days::new(1)),
+            Interval::Monthly => date.checked_add_days(Days::new(1))?.checked_add_months(Months::new(1))?.pred_opt()
+        }
+    };
+    let mut date: NaiveDate = match interval {
+        Interval::Daily => start,
+        Interval::Monthly => start.with_day0(0).and_then(|month| month.checked_add_months(Months::new(1))).and_then(|month| month.pred_opt())
+            .ok_or_else(|| ReportError::InvalidDate(first.to_string()))?
+    };
+
+    let mut dates: Vec<String> = vec![];
+    while date < end {
+        dates.push(date.format("%Y-%m-%d").to_string());
+        date = next(date).ok_or_else(|| ReportError::InvalidDate(last.to_string()))?;
+    }
+    dates.push(end.format("%Y-%m-%d").to_string());
+    Ok(dates)
+}
+
+impl NetWorthSeries {
+    // `transactions` are `(account_id, date, amount)` of every live transaction after `first`.
+    fn build<'t>(accounts: &[Account], transactions: impl Iterator<Item = (&'t str, &'t str, i64)>, interval: Interval, first: &str, last: &str) -> Result<NetWorthSeries, ReportError> {
+        let dates: Vec<String> = point_dates(interval, first, last)?;
+        let accounts: Vec<&Account> = accounts.iter().filter(|account| !account.deleted).collect();
+
+        let mut history: Vec<Vec<(&str, i64)>> = vec![vec![]; accounts.len()];
+        for (account_id, date, amount) in transactions {
+            if let Some(position) = accounts.iter().position(|account| account.id == account_id) {
+                history[position].push((date, amount));
+            }
+        }
+
+        let series: Vec<AccountSeries> = accounts.iter().zip(history).map(|(account, mut transactions)| {
+            transactions.sort();
+
+            // Undo the transactions dated after each point, newest point first.
+            let mut balance: i64 = account.balance;
+            let mut remaining: usize = transactions.len();
+            let mut balances: Vec<i64> = vec![0; dates.len()];
+            for (position, date) in dates.iter().enumerate().rev() {
+                while remaining > 0 && transactions[remaining - 1].0 > date.as_str() {
+                    remaining -= 1;
+                    balance -= transactions[remaining].1;
+                }
+                balances[position] = balance;
+            }
+
+            AccountSeries {
+                account_id: account.id.clone(),
+                name:       account.name.clone(),
+                liability:  account.r#type.is_liability(),
+                closed:     account.closed,
+                balances
+            }
+        }).collect();
+
+        let points: Vec<NetWorthPoint> = dates.into_iter().enumerate().map(|(position, date)| {
+            let mut assets: i64 = 0;
+            let mut liabilities: i64 = 0;
+            for account in &series {
+                if account.liability {
+                    liabilities -= account.balances[position];
+                } else {
+                    assets += account.balances[position];
+                }
+            }
+            NetWorthPoint { date, assets, liabilities, net_worth: assets - liabilities }
+        }).collect();
+
+        Ok(NetWorthSeries { interval, points, accounts: series })
+    }
+
+    // Builds the series from a saved `get_budget` download, whose balances are those at the time it
+    // was downloaded.
+    pub fn from_budget(budget: &BudgetDetail, interval: Interval, first: &str, last: &str) -> Result<NetWorthSeries, ReportError> {
+        let transactions = budget.transactions.iter()
+            .filter(|transaction| !transaction.deleted)
+            .map(|transaction| (transaction.account_id.as_str(), transaction.date.as_str(), transaction.amount));
+        NetWorthSeries::build(&budget.accounts, transactions, interval, first, last)
+    }
+
+    // Builds the series from accounts and transactions fetched together. Only transactions after
+    // `first` are needed.
+    pub fn from_transactions(accounts: &[Account], transactions: &[TransactionDetail], interval: Interval, first: &str, last: &str) -> Result<NetWorthSeries, ReportError> {
+        let transactions = transactions.iter()
+            .filter(|transaction| !transaction.deleted)
+            .map(|transaction| (transaction.account_id.as_str(), transaction.date.as_str(), transaction.amount));
+        NetWorthSeries::build(accounts, transactions, interval, first, last)
+    }
+
+    pub fn account(&self, account_id: &str) -> Option<&AccountSeries> {
+        self.accounts.iter().find(|account| account.account_id == account_id)
+    }
+}
+
+// Fetches the accounts and the transactions since `first`.
+pub async fn fetch_net_worth(client: &Client, budget_id: &str, interval: Interval, first: &str, last: &str) -> Result<NetWorthSeries, ReportError> {
+    let accounts: AccountsResponse = match client.get_account_list(budget_id, None).await {
+        Left(response) => response,
+        Right(error_response) => return Err(ReportError::Request { endpoint: "get_account_list", error_response })
+    };
+    let query: TransactionQuery = TransactionQuery::new(budget_id).since_date(parse_date(first)?);
+    let transactions: Vec<TransactionDetail> = match client.get_transactions(&query).await {
+        Left(response) => response.data.transactions,
+        Right(error_response) => return Err(ReportError::Request { endpoint: "get_transactions", error_response })
+    };
+
+    NetWorthSeries::from_transactions(&accounts.data.accounts, &transactions, interval, first, last)
+}
@@ -0,0 +1,241 @@
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+use either::{Left, Right};
+use serde::Serialize;
+
+use crate::category_tree::{CategoryTree, GroupKind};
+use crate::client::Client;
+use crate::export::format_amount;
+use crate::models::{BudgetDetail, CategoriesResponse, Category, CurrencyFormat, MonthDetail};
+use crate::reports::{average, month_range, ReportError};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct Amounts {
+    pub budgeted:   i64,
+    pub activity:   i64,
+    pub balance:    i64
+}
+
+impl Amounts {
+    fn add(&mut self, other: &Amounts) {
+        self.budgeted += other.budgeted;
+        self.activity += other.activity;
+        self.balance += other.balance;
+    }
+
+    fn is_zero(&self) -> bool {
+        *self == Amounts::default()
+    }
+}
+
+// A row's amounts for each month of the report, in milliunits. Budgeted and activity totals are
+// sums over the range, while the balance total is the balance at the end of it, since balances
+// carry over from month to month. Averages are per month.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Figures {
+    pub months:     Vec<Amounts>,
+    pub total:      Amounts,
+    pub average:    Amounts
+}
+
+impl Figures {
+    fn new(months: Vec<Amounts>) -> Figures {
+        let mut sums: Amounts = Amounts::default();
+        for amounts in &months {
+            sums.add(amounts);
+        }
+
+        Figures {
+            total:      Amounts { balance: months.last().map(|amounts| amounts.balance).unwrap_or(0), ..sums },
+            average:    Amounts {
+                budgeted:   average(sums.budgeted, months.len()),
+                activity:   average(sums.activity, months.len()),
+                balance:    average(sums.balance, months.len())
+            },
+            months
+        }
+    }
+
+    fn sum<'f>(rows: impl Iterator<Item = &'f Figures>, month_count: usize) -> Figures {
+        let mut months: Vec<Amounts> = vec![Amounts::default(); month_count];
+        for figures in rows {
+            for (sum, amounts) in months.iter_mut().zip(&figures.months) {
+                sum.add(amounts);
+            }
+        }
+        Figures::new(months)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CategoryRow {
+    pub category_id:    String,
+    pub name:           String,
+    pub hidden:         bool,
+    pub figures:        Figures
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GroupRow {
+    pub category_group_id:  String,
+    pub name:               String,
+    pub categories:         Vec<CategoryRow>,
+    pub figures:            Figures
+}
+
+// Budgeted, activity and balance per category and group over a range of months, as the budget
+// screen shows them month by month. Groups and categories follow the app's order, with hidden
+// categories back in their original group. The internal master group is left out, and so are
+// hidden and deleted categories that have nothing in the range.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SpendingReport {
+    pub months:     Vec<String>,
+    pub groups:     Vec<GroupRow>,
+    pub figures:    Figures
+}
+
+impl SpendingReport {
+    // Builds the report from month details in any order. Deleted months are skipped.
+    pub fn from_months(tree: &CategoryTree, months: &[MonthDetail]) -> SpendingReport {
+        let mut months: Vec<&MonthDetail> = months.iter().filter(|month| !month.deleted).collect();
+        months.sort_by(|left, right| left.month.cmp(&right.month));
+
+        let amounts: Vec<HashMap<&str, Amounts>> = months.iter().map(|month| {
+            month.categories.iter().map(|category| (category.id.as_str(), amounts_of(category))).collect()
+        }).collect();
+        let figures_of = |category_id: &str| Figures::new(amounts.iter().map(|month| month.get(category_id).copied().unwrap_or_default()).collect());
+
+        let mut groups: Vec<GroupRow> = tree.groups().iter()
+            .filter(|group| group.kind != GroupKind::Internal)
+            .map(|group| GroupRow {
+                category_group_id:  group.id.to_string(),
+                name:               group.name.to_string(),
+                categories:         group.categories.iter().map(|category| CategoryRow {
+                    category_id:    category.id.clone(),
+                    name:           category.name.clone(),
+                    hidden:         category.hidden,
+                    figures:        figures_of(&category.id)
+                }).collect(),
+                figures:            Figures::new(vec![])
+            })
+            .collect();
+
+        // Categories the tree doesn't know, such as ones deleted since, still count towards the
+        // group they were in.
+        for category in months.iter().flat_map(|month| month.categories.iter()) {
+            if tree.category(&category.id).is_some() {
+                continue;
+            }
+            if groups.iter().any(|group| group.categories.iter().any(|row| row.category_id == category.id)) {
+                continue;
+            }
+            if tree.group(&category.category_group_id).is_some_and(|group| group.kind == GroupKind::Internal) {
+                continue;
+            }
+
+            let position: usize = match groups.iter().position(|group| group.category_group_id == category.category_group_id) {
+                Some(position) => position,
+                None => {
+                    groups.push(GroupRow {
+                        category_group_id:  category.category_group_id.clone(),
+                        name:               category.category_group_name.clone().unwrap_or_default(),
+                        categories:         vec![],
+                        figures:            Figures::new(vec![])
+                    });
+                    groups.len() - 1
+                }
+            };
+            groups[position].categories.push(CategoryRow {
+                category_id:    category.id.clone(),
+                name:           category.name.clone(),
+                hidden:         category.hidden || category.deleted,
+                figures:        figures_of(&category.id)
+            });
+        }
+
+        for group in groups.iter_mut() {
+            group.categories.retain(|row| !row.hidden || row.figures.months.iter().any(|amounts| !amounts.is_zero()));
+            group.figures = Figures::sum(group.categories.iter().map(|row| &row.figures), months.len());
+        }
+        groups.retain(|group| !group.categories.is_empty());
+
+        SpendingReport {
+            months:     months.iter().map(|month| month.month.clone()).collect(),
+            figures:    Figures::sum(groups.iter().map(|group| &group.figures), months.len()),
+            groups
+        }
+    }
+
+    // Builds the report from a saved `get_budget` download.
+    pub fn from_budget(budget: &BudgetDetail, first: &str, last: &str) -> Result<SpendingReport, ReportError> {
+        let months: Vec<MonthDetail> = month_range(first, last)?.into_iter()
+            .map(|month| budget.months.iter().find(|detail| detail.month == month && !detail.deleted).cloned().ok_or(ReportError::MissingMonth(month)))
+            .collect::<Result<Vec<MonthDetail>, ReportError>>()?;
+        Ok(SpendingReport::from_months(&CategoryTree::from_budget(budget), &months))
+    }
+
+    pub fn category(&self, category_id: &str) -> Option<&CategoryRow> {
+        self.groups.iter().flat_map(|group| group.categories.iter()).find(|row| row.category_id == category_id)
+    }
+
+    pub fn group(&self, category_group_id: &str) -> Option<&GroupRow> {
+        self.groups.iter().find(|group| group.category_group_id == category_group_id)
+    }
+
+    // One line per group followed by its categories, and a final `Total` line. Each month gets a
+    // budgeted, activity and balance column, followed by the totals and averages. Amounts are plain
+    // decimals with the currency's digits so spreadsheets read them as numbers.
+    pub fn write_csv<W: Write>(&self, writer: W, currency_format: &CurrencyFormat) -> io::Result<W> {
+        let mut csv_writer: ::csv::Writer<W> = ::csv::Writer::from_writer(writer);
+
+        let mut header: Vec<String> = vec!["group".to_string(), "category".to_string()];
+        let labels = self.months.iter().map(|month| month.get(..7).unwrap_or(month)).chain(["total", "average"]);
+        for label in labels {
+            header.extend(["budgeted", "activity", "balance"].iter().map(|column| format!("{} {}", label, column)));
+        }
+        csv_writer.write_record(&header)?;
+
+        let record = |group: &str, category: &str, figures: &Figures| -> Vec<String> {
+            let mut record: Vec<String> = vec![group.to_string(), category.to_string()];
+            for amounts in figures.months.iter().chain([&figures.total, &figures.average]) {
+                record.extend([amounts.budgeted, amounts.activity, amounts.balance].iter().map(|amount| format_amount(*amount, currency_format.decimal_digits)));
+            }
+            record
+        };
+
+        for group in &self.groups {
+            csv_writer.write_record(record(&group.name, "", &group.figures))?;
+            for category in &group.categories {
+                csv_writer.write_record(record(&group.name, &category.name, &category.figures))?;
+            }
+        }
+        csv_writer.write_record(record("Total", "", &self.figures))?;
+
+        csv_writer.into_inner().map_err(|error| error.into_error())
+    }
+}
+
+fn amounts_of(category: &Category) -> Amounts {
+    Amounts { budgeted: category.budgeted, activity: category.activity, balance: category.balance }
+}
+
+// Fetches the category list and then each month in the range with `get_month`.
+pub async fn fetch_spending_report(client: &Client, budget_id: &str, first: &str, last: &str) -> Result<SpendingReport, ReportError> {
+    let months: Vec<String> = month_range(first, last)?;
+
+    let categories: CategoriesResponse = match client.get_category_list(budget_id, None).await {
+        Left(response) => response,
+        Right(error_response) => return Err(ReportError::Request { endpoint: "get_category_list", error_response })
+    };
+
+    let mut details: Vec<MonthDetail> = vec![];
+    for month in &months {
+        match client.get_month(budget_id, month).await {
+            Left(response) => details.push(response.data.month),
+            Right(error_response) => return Err(ReportError::Request { endpoint: "get_month", error_response })
+        }
+    }
+
+    Ok(SpendingReport::from_months(&CategoryTree::from_groups(&categories.data.category_groups), &details))
+}
@@ -0,0 +1,79 @@
+mod common;
+
+use std::fs::File;
+
+use arrow::array::{Array, AsArray};
+use arrow::datatypes::{DataType, Date32Type, Decimal128Type};
+use arrow::record_batch::RecordBatch;
+use common::budget;
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use ynab_rs::export::arrow::{accounts_batch, month_categories_batch, transactions_batch, write_parquet};
+use ynab_rs::models::{BudgetDetail, TransactionDetail, TransactionsResponse};
+
+fn transactions() -> Vec<TransactionDetail> {
+    let response: TransactionsResponse = serde_json::from_str(include_str!("fixtures/transactions.json")).unwrap();
+    response.data.transactions
+}
+
+fn strings(batch: &RecordBatch, column: &str) -> Vec<Option<String>> {
+    batch.column_by_name(column).unwrap().as_string::<i32>().iter().map(|value| value.map(str::to_string)).collect()
+}
+
+fn amounts(batch: &RecordBatch, column: &str) -> Vec<Option<i128>> {
+    batch.column_by_name(column).unwrap().as_primitive::<Decimal128Type>().iter().collect()
+}
+
+#[test]
+fn converts_transactions() {
+    let batch: RecordBatch = transactions_batch(&transactions()).unwrap();
+
+    assert_eq!(batch.num_rows(), 7);
+    assert_eq!(batch.schema().field_with_name("amount").unwrap().data_type(), &DataType::Decimal128(18, 3));
+    assert_eq!(strings(&batch, "id")[2..4], [Some("subtransaction-groceries".to_string()), Some("subtransaction-dining".to_string())]);
+    assert_eq!(strings(&batch, "parent_id")[3], Some("transaction-grocer".to_string()));
+    assert_eq!(strings(&batch, "memo")[2..4], [Some("Weekly shop".to_string()), Some("Bakery counter".to_string())]);
+    assert_eq!(strings(&batch, "cleared")[1], Some("reconciled".to_string()));
+    assert_eq!(strings(&batch, "flag_color")[1], Some("blue".to_string()));
+    assert_eq!(amounts(&batch, "amount").iter().flatten().sum::<i128>(), 2500000 - 900000 - 85000 - 125000);
+
+    let dates = batch.column_by_name("date").unwrap().as_primitive::<Date32Type>();
+    assert_eq!(dates.value_as_date(0).unwrap().to_string(), "2023-06-01");
+    assert_eq!(dates.value(0), 19509);
+
+    let mut invalid: Vec<TransactionDetail> = transactions();
+    invalid[0].date = "01.06.2023".to_string();
+    assert!(transactions_batch(&invalid).unwrap_err().to_string().contains("`01.06.2023` is not an ISO date"));
+}
+
+#[test]
+fn converts_month_categories_and_accounts() {
+    let budget: BudgetDetail = budget();
+
+    let months: RecordBatch = month_categories_batch(&budget.months).unwrap();
+    assert_eq!(months.num_rows(), 2);
+    assert_eq!(strings(&months, "name"), vec![Some("Rent".to_string()), Some("Groceries".to_string())]);
+    assert_eq!(amounts(&months, "balance"), vec![Some(0), Some(340000)]);
+    assert_eq!(months.column_by_name("month").unwrap().as_primitive::<Date32Type>().value_as_date(1).unwrap().to_string(), "2023-06-01");
+
+    let accounts: RecordBatch = accounts_batch(&budget.accounts).unwrap();
+    assert_eq!(strings(&accounts, "type"), vec![Some("checking".to_string()), Some("savings".to_string())]);
+    assert_eq!(amounts(&accounts, "cleared_balance"), vec![Some(1400000), Some(200000)]);
+    assert_eq!(accounts.column_by_name("note").unwrap().null_count(), 1);
+}
+
+#[test]
+fn round_trips_through_parquet() {
+    let batch: RecordBatch = transactions_batch(&transactions()).unwrap();
+    let bytes: Vec<u8> = write_parquet(&batch, vec![]).unwrap();
+    assert_eq!(&bytes[..4], b"PAR1");
+
+    let path = std::env::temp_dir().join(format!("ynab-rs-transactions-{}.parquet", std::process::id()));
+    std::fs::write(&path, &bytes).unwrap();
+    let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(&path).unwrap()).unwrap().build().unwrap();
+    let batches: Vec<RecordBatch> = reader.collect::<Result<Vec<RecordBatch>, _>>().unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(batches.len(), 1);
+    assert_eq!(batches[0].schema().fields(), batch.schema().fields());
+    assert_eq!(batches[0], batch);
+}
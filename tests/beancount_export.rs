@@ -0,0 +1,115 @@
+mod common;
+
+use common::budget;
+use ynab_rs::export::beancount::BeancountExporter;
+use ynab_rs::models::{BudgetDetail, FlagColor, TransactionDetail, TransactionsResponse};
+
+fn ledger(budget: &BudgetDetail) -> String {
+    let mut output: Vec<u8> = vec![];
+    BeancountExporter::new(budget).write_budget(&mut output).unwrap();
+    String::from_utf8(output).unwrap()
+}
+
+fn entry<'a>(ledger: &'a str, header: &str) -> Vec<&'a str> {
+    ledger.split("\n\n")
+        .find(|entry| entry.starts_with(header))
+        .unwrap_or_else(|| panic!("no entry `{}` in\n{}", header, ledger))
+        .lines()
+        .collect()
+}
+
+#[test]
+fn opens_accounts_at_first_use() {
+    let mut budget: BudgetDetail = budget();
+    budget.accounts[1].closed = true;
+    let ledger: String = ledger(&budget);
+
+    assert!(ledger.starts_with("option \"operating_currency\" \"EUR\"\n"));
+    assert_eq!(
+        entry(&ledger, "2023-06-01 open"),
+        vec![
+            "2023-06-01 open Assets:Checking EUR",
+            "  ynab-id: \"account-checking\"",
+            "2023-06-01 open Income:Ready-to-Assign EUR",
+            "2023-06-03 open Expenses:Bills:Rent EUR",
+            "2023-06-05 open Expenses:Everyday-Expenses:Dining-Out EUR",
+            "2023-06-05 open Expenses:Everyday-Expenses:Groceries EUR",
+            "2023-06-10 open Assets:Savings EUR",
+            "  ynab-id: \"account-savings\""
+        ]
+    );
+    assert!(ledger.ends_with("\n2023-06-10 close Assets:Savings\n"));
+    assert!(!ledger.contains("close Assets:Checking"));
+}
+
+#[test]
+fn opens_unused_accounts() {
+    let mut budget: BudgetDetail = budget();
+    let mut cash = budget.accounts[0].clone();
+    cash.id = "account-cash".to_string();
+    cash.name = "Wallet (cash)".to_string();
+    cash.closed = true;
+    budget.accounts.push(cash);
+    let ledger: String = ledger(&budget);
+
+    assert!(ledger.contains("2023-06-01 open Assets:Wallet-cash EUR\n  ynab-id: \"account-cash\"\n"));
+    assert!(ledger.contains("2023-06-01 close Assets:Wallet-cash\n"));
+}
+
+#[test]
+fn writes_flags_tags_and_metadata() {
+    let mut budget: BudgetDetail = budget();
+    budget.transactions[0].flag_color = Some(FlagColor::Purple);
+    budget.transactions[0].memo = Some("June \"bonus\" salary".to_string());
+    let ledger: String = ledger(&budget);
+
+    assert_eq!(
+        entry(&ledger, "2023-06-01 *"),
+        vec![
+            "2023-06-01 * \"Employer\" \"June \\\"bonus\\\" salary\" #flag-purple",
+            "  ynab-id: \"transaction-paycheck\"",
+            "  Assets:Checking                           2500.00 EUR",
+            "  Income:Ready-to-Assign                    -2500.00 EUR"
+        ]
+    );
+    assert_eq!(entry(&ledger, "2023-06-03")[0], "2023-06-03 * \"Landlord\" \"\" #flag-blue");
+    assert_eq!(
+        entry(&ledger, "2023-06-05 !"),
+        vec![
+            "2023-06-05 ! \"Grocer\" \"Weekly shop\"",
+            "  ynab-id: \"transaction-grocer\"",
+            "  Assets:Checking                           -85.00 EUR",
+            "  Expenses:Everyday-Expenses:Groceries      60.00 EUR",
+            "    ynab-id: \"subtransaction-groceries\"",
+            "  Expenses:Everyday-Expenses:Dining-Out     25.00 EUR",
+            "    ynab-id: \"subtransaction-dining\"",
+            "    memo: \"Bakery counter\""
+        ]
+    );
+}
+
+#[test]
+fn writes_transfers_once() {
+    let ledger: String = ledger(&budget());
+
+    assert_eq!(ledger.matches("\"Transfer :").count(), 1);
+    assert_eq!(
+        entry(&ledger, "2023-06-10 *")[2..],
+        ["  Assets:Savings                            200.00 EUR", "  Assets:Checking                           -200.00 EUR"]
+    );
+}
+
+#[test]
+fn exports_query_results() {
+    let budget: BudgetDetail = budget();
+    let response: TransactionsResponse = serde_json::from_str(include_str!("fixtures/transactions.json")).unwrap();
+    let transactions: Vec<TransactionDetail> = response.data.transactions;
+
+    let mut output: Vec<u8> = vec![];
+    BeancountExporter::new(&budget).write_transactions(&transactions, &mut output).unwrap();
+    let ledger: String = String::from_utf8(output).unwrap();
+
+    assert!(!ledger.contains("transaction-deleted"));
+    assert!(ledger.contains("2023-06-30 open Assets:Unknown:Account-mortgage EUR\n"));
+    assert_eq!(ledger.matches("\"Transfer :").count(), 1);
+}
@@ -0,0 +1,76 @@
+mod common;
+
+use common::date;
+use ynab_rs::builder::{SaveTransactionBuilder, ValidationProblem};
+use ynab_rs::models::{ClearedStatus, SaveSubTransaction};
+
+fn subtransaction(amount: i64, category_id: &str) -> SaveSubTransaction {
+    SaveSubTransaction {
+        amount,
+        payee_id:       None,
+        payee_name:     None,
+        category_id:    Some(category_id.to_string()),
+        memo:           None
+    }
+}
+
+#[test]
+fn builds_valid_split_transaction() {
+    let transaction = SaveTransactionBuilder::new("account-checking", date(2023, 6, 5), -85000)
+        .payee_name("Grocer")
+        .memo("Weekly shop")
+        .cleared(ClearedStatus::Cleared)
+        .import_id("YNAB:-85000:2023-06-05:1")
+        .subtransaction(subtransaction(-60000, "category-groceries"))
+        .subtransaction(subtransaction(-25000, "category-dining"))
+        .build_as_of(date(2023, 6, 20))
+        .unwrap();
+
+    assert_eq!(transaction.date, "2023-06-05");
+    assert_eq!(transaction.subtransactions.unwrap().len(), 2);
+}
+
+#[test]
+fn reports_every_problem() {
+    let error = SaveTransactionBuilder::new("account-checking", date(2028, 6, 21), -85000)
+        .payee_id("payee-grocer")
+        .payee_name(&"G".repeat(51))
+        .category_id("category-groceries")
+        .memo(&"m".repeat(201))
+        .import_id(&"i".repeat(37))
+        .subtransaction(subtransaction(-60000, "category-groceries"))
+        .build_as_of(date(2023, 6, 20))
+        .unwrap_err();
+
+    assert_eq!(error.problems, vec![
+        ValidationProblem::DateTooFarInFuture { date: date(2028, 6, 21), latest: date(2028, 6, 20) },
+        ValidationProblem::ConflictingPayee { subtransaction: None },
+        ValidationProblem::MemoTooLong { subtransaction: None, length: 201 },
+        ValidationProblem::PayeeNameTooLong { subtransaction: None, length: 51 },
+        ValidationProblem::ImportIdTooLong { length: 37 },
+        ValidationProblem::CategoryOnSplit,
+        ValidationProblem::SubtransactionSumMismatch { amount: -85000, subtransaction_total: -60000 }
+    ]);
+    assert!(error.to_string().starts_with("invalid transaction: date 2028-06-21 is after 2028-06-20"));
+}
+
+#[test]
+fn checks_subtransaction_fields() {
+    let mut long_memo = subtransaction(-60000, "category-groceries");
+    long_memo.memo = Some("ü".repeat(201));
+    let mut both_payees = subtransaction(-25000, "category-dining");
+    both_payees.payee_id = Some("payee-grocer".to_string());
+    both_payees.payee_name = Some("Grocer".to_string());
+
+    let error = SaveTransactionBuilder::new("account-checking", date(2023, 6, 5), -85000)
+        .subtransaction(long_memo)
+        .subtransaction(both_payees)
+        .build_as_of(date(2023, 6, 20))
+        .unwrap_err();
+
+    assert_eq!(error.problems, vec![
+        ValidationProblem::MemoTooLong { subtransaction: Some(0), length: 201 },
+        ValidationProblem::ConflictingPayee { subtransaction: Some(1) }
+    ]);
+    assert!(error.to_string().ends_with("; subtransaction 1 payee_id and payee_name are mutually exclusive"));
+}
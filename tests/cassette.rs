@@ -0,0 +1,64 @@
+mod common;
+
+use common::seeded_server;
+use either::{Left, Right};
+use ynab_rs::cassette::{Cassette, REDACTED};
+use ynab_rs::client::Client;
+use ynab_rs::mock::MOCK_API_TOKEN;
+
+#[tokio::test]
+async fn replays_recorded_cassette() {
+    let server = seeded_server();
+    // The token only travels in a header, so put it in a response body to see it redacted.
+    server.update_budget("budget-household", |budget| budget.accounts[0].note = Some(format!("token {}", MOCK_API_TOKEN)));
+
+    let client = server.client().record();
+    let recorded = client.get_account_list("budget-household", None).await.left().unwrap();
+    assert!(client.get_account("budget-household", "account-unknown").await.is_right());
+
+    let path = std::env::temp_dir().join(format!("ynab-rs-cassette-{}.json", std::process::id()));
+    client.cassette().unwrap().save(&path).unwrap();
+    drop(server);
+
+    let contents = std::fs::read_to_string(&path).unwrap();
+    assert!(!contents.contains(MOCK_API_TOKEN));
+    assert!(contents.contains(&format!("token {}", REDACTED)));
+
+    let replaying = Client::replay(Cassette::load(&path).unwrap());
+    std::fs::remove_file(&path).unwrap();
+
+    let replayed = replaying.get_account_list("budget-household", None).await.left().unwrap();
+    assert_eq!(replayed.data.server_knowledge, recorded.data.server_knowledge);
+    assert_eq!(replayed.data.accounts.len(), recorded.data.accounts.len());
+    assert_eq!(replayed.data.accounts[0].note.as_deref(), Some("token [REDACTED]"));
+
+    match replaying.get_account("budget-household", "account-unknown").await {
+        Left(_) => panic!("Expected the recorded error."),
+        Right(error) => assert_eq!(error.error.id, "404.2")
+    }
+}
+
+#[tokio::test]
+async fn replays_committed_cassette() {
+    let client = Client::replay(Cassette::load("tests/cassettes/payees.json").unwrap());
+
+    match client.get_payee_list("budget-household", None).await {
+        Left(response) => {
+            assert_eq!(response.data.payees.len(), 2);
+            assert_eq!(response.data.payees[1].transfer_account_id.as_deref(), Some("account-savings"));
+        },
+        Right(error) => panic!("Unexpected error: {:?}", error)
+    }
+
+    match client.get_payee("budget-household", "payee-unknown").await {
+        Left(_) => panic!("Expected the recorded error."),
+        Right(error) => assert_eq!(error.error.name, "resource_not_found")
+    }
+}
+
+#[tokio::test]
+#[should_panic(expected = "Cassette has no unplayed interaction")]
+async fn rejects_unrecorded_requests() {
+    let client = Client::replay(Cassette::default());
+    let _ = client.get_user().await;
+}
@@ -0,0 +1,141 @@
+mod common;
+
+use chrono::NaiveDate;
+use either::{Left, Right};
+use ynab_rs::builder::SaveTransactionBuilder;
+use ynab_rs::categorize::{learn_rules, CategoryRule, CategoryRuleError, Categorizer};
+use ynab_rs::models::{PostTransactionsWrapper, SaveTransaction, TransactionDetail, TransactionsResponse};
+use ynab_rs::mock::MockServer;
+
+const RULES: &str = r#"
+[[rule]]
+name = "Rent"
+payee = "landlord"
+max_amount = -500000
+min_day = 1
+max_day = 5
+category_id = "category-rent"
+
+[[rule]]
+name = "Dining"
+memo = "(?i)lunch|dinner"
+category_id = "category-dining"
+
+[[rule]]
+payee_id = "payee-grocer"
+account_id = "account-checking"
+min_amount = -200000
+category_id = "category-groceries"
+"#;
+
+fn history() -> Vec<TransactionDetail> {
+    let response: TransactionsResponse = serde_json::from_str(include_str!("fixtures/transactions.json")).unwrap();
+    response.data.transactions
+}
+
+fn transaction(day: u32, amount: i64, payee: &str, memo: &str) -> SaveTransaction {
+    let builder = SaveTransactionBuilder::new("account-checking", NaiveDate::from_ymd_opt(2023, 7, day).unwrap(), amount).memo(memo);
+    let builder = if payee.starts_with("payee-") { builder.payee_id(payee) } else { builder.payee_name(payee) };
+    builder.build().unwrap()
+}
+
+async fn with_july_transactions() -> MockServer {
+    let server = common::seeded_server();
+    let wrapper = PostTransactionsWrapper {
+        transaction:    None,
+        transactions:   Some(vec![
+            transaction(2, -900000, "payee-landlord", "July"),
+            transaction(12, -900000, "payee-landlord", "Deposit"),
+            transaction(14, -32000, "Corner Bistro", "Team lunch"),
+            transaction(20, -64000, "payee-grocer", "Weekly shop"),
+            transaction(21, -7000, "Kiosk", "Newspaper")
+        ])
+    };
+    assert!(server.client().create_transactions("budget-household", &wrapper).await.is_left());
+    server
+}
+
+#[test]
+fn matches_every_condition() {
+    let detail = history().into_iter().find(|transaction| transaction.payee_id.as_deref() == Some("payee-grocer")).unwrap();
+
+    let rule = CategoryRule { payee_name: Some("GROC".to_string()), ..CategoryRule::new("category-groceries") };
+    assert!(rule.matches(&detail));
+
+    let rule = CategoryRule { min_amount: Some(detail.amount + 1), ..CategoryRule::new("category-groceries") };
+    assert!(!rule.matches(&detail));
+
+    let rule = CategoryRule { account_id: Some("account-savings".to_string()), ..CategoryRule::new("category-groceries") };
+    assert!(!rule.matches(&detail));
+}
+
+#[test]
+fn rejects_rules_without_conditions() {
+    let error = Categorizer::from_toml("[[rule]]\ncategory_id = \"category-rent\"\n").unwrap_err();
+    assert!(matches!(error, CategoryRuleError::InvalidRule { rule: 1, .. }));
+
+    let error = Categorizer::from_toml("[[rule]]\nmin_day = 0\ncategory_id = \"category-rent\"\n").unwrap_err();
+    assert!(matches!(error, CategoryRuleError::InvalidRule { rule: 1, .. }));
+
+    let error = Categorizer::from_toml("[[rule]]\nmemo = \"(\"\ncategory_id = \"category-rent\"\n").unwrap_err();
+    assert!(matches!(error, CategoryRuleError::Regex { rule: 1, .. }));
+}
+
+#[test]
+fn learns_rules_from_history() {
+    let mut history = history();
+    let template = history.iter().find(|transaction| transaction.payee_id.as_deref() == Some("payee-landlord")).unwrap().clone();
+    history.push(TransactionDetail { id: "rent-2".to_string(), ..template.clone() });
+    history.push(TransactionDetail { id: "rent-3".to_string(), category_id: Some("category-groceries".to_string()), ..template });
+
+    let rules = learn_rules(&history, 3, 0.6);
+    assert_eq!(rules.len(), 1);
+    assert_eq!(rules[0].payee_id.as_deref(), Some("payee-landlord"));
+    assert_eq!(rules[0].category_id, "category-rent");
+    assert_eq!(rules[0].name.as_deref(), Some("learned: Landlord"));
+
+    assert!(learn_rules(&history, 3, 0.7).is_empty());
+    assert!(learn_rules(&history, 4, 0.5).is_empty());
+}
+
+#[tokio::test]
+async fn categorizes_uncategorized_transactions() {
+    let server = with_july_transactions().await;
+    let client = server.client();
+    let categorizer = Categorizer::from_toml(RULES).unwrap();
+
+    let report = match categorizer.run(&client, "budget-household", NaiveDate::from_ymd_opt(2023, 7, 1)).await {
+        Left(report) => report,
+        Right(error) => panic!("Unexpected error: {:?}", error)
+    };
+    assert!(report.applied);
+    assert_eq!(
+        report.changes.iter().map(|change| (change.date.as_str(), change.category_id.as_str())).collect::<Vec<(&str, &str)>>(),
+        vec![("2023-07-02", "category-rent"), ("2023-07-14", "category-dining"), ("2023-07-20", "category-groceries")]
+    );
+    assert!(report.to_string().starts_with("changed "));
+    assert!(report.to_string().lines().next().unwrap().ends_with("category -> category-rent (rule `Rent`)"));
+
+    let budget = server.budget("budget-household").unwrap();
+    let categorized = budget.transactions.iter().filter(|transaction| transaction.date.starts_with("2023-07") && transaction.category_id.is_some()).count();
+    assert_eq!(categorized, 3);
+    let untouched = budget.transactions.iter().find(|transaction| transaction.memo.as_deref() == Some("Newspaper")).unwrap();
+    assert_eq!(untouched.category_id, None);
+
+    let rerun = categorizer.run(&client, "budget-household", NaiveDate::from_ymd_opt(2023, 7, 1)).await.left().unwrap();
+    assert!(rerun.changes.is_empty());
+    assert!(!rerun.applied);
+}
+
+#[tokio::test]
+async fn plans_without_sending() {
+    let server = with_july_transactions().await;
+    let client = server.client();
+    let transactions = client.get_transaction_list("budget-household", Some("2023-07-01"), Some("uncategorized"), None).await.left().unwrap();
+    let report = Categorizer::from_toml(RULES).unwrap().plan(&transactions.data.transactions);
+
+    assert_eq!(report.changes.len(), 3);
+    assert!(!report.applied);
+    assert!(report.to_string().starts_with("would change "));
+    assert!(server.budget("budget-household").unwrap().transactions.iter().all(|transaction| !transaction.date.starts_with("2023-07") || transaction.category_id.is_none()));
+}
@@ -0,0 +1,77 @@
+mod common;
+
+use common::budget;
+use ynab_rs::category_tree::{CategoryTree, GroupKind, GroupNode};
+use ynab_rs::models::{BudgetDetail, CategoriesResponse, Category, CategoryGroupWithCategories};
+
+fn category_groups() -> Vec<CategoryGroupWithCategories> {
+    let response: CategoriesResponse = serde_json::from_str(include_str!("fixtures/categories.json")).unwrap();
+    response.data.category_groups
+}
+
+fn names(group: &GroupNode) -> Vec<String> {
+    group.categories.iter().map(|category| category.name.clone()).collect()
+}
+
+#[test]
+fn puts_hidden_categories_back_in_their_original_group() {
+    let groups: Vec<CategoryGroupWithCategories> = category_groups();
+    let tree: CategoryTree = CategoryTree::from_groups(&groups);
+
+    let everyday: &GroupNode = tree.group("group-everyday").unwrap();
+    assert_eq!(names(everyday), vec!["Groceries", "Dining Out", "Vacation", "Gym"]);
+    assert_eq!(everyday.visible_categories().count(), 3);
+    assert!(tree.group_named("Hidden Categories").unwrap().categories.is_empty());
+    assert_eq!(tree.group_of("category-old-gym").unwrap().name, "Everyday Expenses");
+    assert!(tree.category("category-deleted").is_none());
+}
+
+#[test]
+fn orders_groups_as_the_app_lists_them() {
+    let mut groups: Vec<CategoryGroupWithCategories> = category_groups();
+    let mut cards: CategoryGroupWithCategories = groups[1].clone();
+    cards.id = "group-cards".to_string();
+    cards.name = "Credit Card Payments".to_string();
+    cards.categories.clear();
+    groups.push(cards);
+    groups[1].deleted = true;
+
+    let tree: CategoryTree = CategoryTree::from_groups(&groups);
+    let kinds: Vec<(&str, GroupKind)> = tree.groups().iter().map(|group| (group.name, group.kind)).collect();
+    assert_eq!(kinds, vec![
+        ("Credit Card Payments", GroupKind::CreditCardPayments),
+        ("Everyday Expenses", GroupKind::User),
+        ("Hidden Categories", GroupKind::Hidden),
+        ("Internal Master Category", GroupKind::Internal)
+    ]);
+    assert_eq!(tree.user_groups().count(), 1);
+    assert!(tree.category("category-rent").is_none());
+}
+
+#[test]
+fn finds_categories_by_path() {
+    let groups: Vec<CategoryGroupWithCategories> = category_groups();
+    let tree: CategoryTree = CategoryTree::from_groups(&groups);
+
+    assert_eq!(tree.find("Bills/Rent").unwrap().id, "category-rent");
+    assert_eq!(tree.find("Everyday Expenses/Gym").unwrap().id, "category-old-gym");
+    assert_eq!(tree.find("Dining Out").unwrap().id, "category-dining");
+    assert!(tree.find("Bills/Groceries").is_none());
+    assert_eq!(tree.path("category-dining").as_deref(), Some("Everyday Expenses/Dining Out"));
+    assert_eq!(tree.ready_to_assign().unwrap().id, "category-ready-to-assign");
+}
+
+#[test]
+fn builds_the_same_tree_from_a_budget() {
+    let mut budget: BudgetDetail = budget();
+    let mut groceries: Category = budget.categories[2].clone();
+    groceries.id = "category-groceries-and-more".to_string();
+    groceries.name = "Groceries/More".to_string();
+    budget.categories.push(groceries);
+
+    let tree: CategoryTree = CategoryTree::from_budget(&budget);
+    assert_eq!(names(tree.group("group-everyday").unwrap()), vec!["Groceries", "Dining Out", "Groceries/More"]);
+    assert_eq!(tree.find("Everyday Expenses/Groceries/More").unwrap().id, "category-groceries-and-more");
+    assert_eq!(tree.find("Bills/Rent").unwrap().id, "category-rent");
+    assert_eq!(tree.group_of("category-ready-to-assign").unwrap().kind, GroupKind::Internal);
+}
@@ -0,0 +1,25 @@
+// Helpers shared by the integration tests. Each test binary only uses some of them.
+#![allow(dead_code)]
+
+use chrono::NaiveDate;
+#[cfg(feature = "mock-server")]
+use ynab_rs::mock::MockServer;
+use ynab_rs::models::{BudgetDetail, BudgetDetailResponse};
+
+pub fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+// The `budget-household` fixture as `get_budget` returns it.
+pub fn budget() -> BudgetDetail {
+    let response: BudgetDetailResponse = serde_json::from_str(include_str!("../fixtures/budget_detail.json")).unwrap();
+    response.data.budget
+}
+
+// A mock server holding the `budget-household` fixture.
+#[cfg(feature = "mock-server")]
+pub fn seeded_server() -> MockServer {
+    let server = MockServer::start();
+    server.seed_budget_json(include_str!("../fixtures/budget_detail.json")).unwrap();
+    server
+}
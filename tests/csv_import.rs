@@ -0,0 +1,130 @@
+mod common;
+
+use common::seeded_server;
+use ynab_rs::import::csv::{import_csv, read_transactions, Column, CsvImportError, CsvProfile};
+use ynab_rs::import::{parse_milliunits, ImportOutcome};
+
+const GERMAN_PROFILE: &str = r#"
+skip_rows = 2
+delimiter = ";"
+date_column = "Buchungstag"
+date_format = "%d.%m.%Y"
+amount_column = "Betrag"
+decimal_separator = ","
+payee_column = "Empfänger"
+memo_column = "Verwendungszweck"
+"#;
+
+const GERMAN_STATEMENT: &str = "Kontoauszug Girokonto\n\
+Zeitraum: 01.06.2023 - 30.06.2023\n\
+Buchungstag;Empfänger;Verwendungszweck;Betrag\n\
+12.06.2023;Bäckerei;Brötchen;-4,50\n\
+12.06.2023;Bäckerei;Brötchen;-4,50\n\
+;;;\n\
+15.06.2023;Arbeitgeber GmbH;Gehalt Juni;1.234,56\n";
+
+#[test]
+fn parses_bank_formatted_amounts() {
+    assert_eq!(parse_milliunits("-1.234,56", ','), Some(-1234560));
+    assert_eq!(parse_milliunits("1,234.56", '.'), Some(1234560));
+    assert_eq!(parse_milliunits("(12.50)", '.'), Some(-12500));
+    assert_eq!(parse_milliunits("12,50-", ','), Some(-12500));
+    assert_eq!(parse_milliunits("EUR 7.00", '.'), Some(7000));
+    assert_eq!(parse_milliunits("0.125", '.'), Some(125));
+    assert_eq!(parse_milliunits("-0.00", '.'), Some(0));
+    assert_eq!(parse_milliunits("0.1234", '.'), None);
+    assert_eq!(parse_milliunits("1-2", '.'), None);
+    assert_eq!(parse_milliunits("n/a", '.'), None);
+}
+
+#[test]
+fn reads_statement_with_named_columns() {
+    let profile = CsvProfile::from_toml(GERMAN_PROFILE).unwrap();
+    let transactions = read_transactions(GERMAN_STATEMENT.as_bytes(), &profile, "account-checking").unwrap();
+
+    assert_eq!(transactions.len(), 3);
+    assert_eq!(transactions[0].date, "2023-06-12");
+    assert_eq!(transactions[0].amount, -4500);
+    assert_eq!(transactions[0].payee_name.as_deref(), Some("Bäckerei"));
+    assert_eq!(transactions[0].memo.as_deref(), Some("Brötchen"));
+    assert_eq!(transactions[0].import_id.as_deref(), Some("YNAB:-4500:2023-06-12:1"));
+    assert_eq!(transactions[1].import_id.as_deref(), Some("YNAB:-4500:2023-06-12:2"));
+    assert_eq!(transactions[2].amount, 1234560);
+    assert!(transactions.iter().all(|transaction| transaction.account_id == "account-checking"));
+}
+
+#[test]
+fn reads_debit_and_credit_columns_by_index() {
+    let profile = CsvProfile::from_toml(r#"
+        has_header = false
+        date_column = 0
+        date_format = "%m/%d/%Y"
+        debit_column = 2
+        credit_column = 3
+        payee_column = 1
+    "#).unwrap();
+    let statement = "06/12/2023,Bakery,4.50,\n06/15/2023,Employer,,\"1,234.56\"\n";
+    let transactions = read_transactions(statement.as_bytes(), &profile, "account-checking").unwrap();
+
+    assert_eq!(transactions.iter().map(|transaction| transaction.amount).collect::<Vec<i64>>(), vec![-4500, 1234560]);
+}
+
+#[test]
+fn skips_the_byte_order_mark_of_excel_exports() {
+    let profile = CsvProfile::from_toml(r#"
+        date_column = "Date"
+        date_format = "%Y-%m-%d"
+        debit_column = "Debit"
+        credit_column = "Credit"
+    "#).unwrap();
+    let statement = "\u{feff}Date,Debit,Credit\n2023-06-12,4.50,\n2023-06-13,0.00,2.00\n2023-06-14,0.00,\n";
+    let transactions = read_transactions(statement.as_bytes(), &profile, "account-checking").unwrap();
+
+    assert_eq!(transactions.iter().map(|transaction| transaction.amount).collect::<Vec<i64>>(), vec![-4500, 2000, 0]);
+}
+
+#[test]
+fn truncates_long_payees_and_memos() {
+    let profile = CsvProfile::from_toml(r#"
+        date_column = "Date"
+        date_format = "%Y-%m-%d"
+        amount_column = "Amount"
+        payee_column = "Payee"
+        memo_column = "Memo"
+    "#).unwrap();
+    let statement = format!("Date,Payee,Memo,Amount\n2023-06-12,{},{},-1.00\n", "p".repeat(80), "m".repeat(300));
+    let transactions = read_transactions(statement.as_bytes(), &profile, "account-checking").unwrap();
+
+    assert_eq!(transactions[0].payee_name.as_ref().unwrap().chars().count(), 50);
+    assert_eq!(transactions[0].memo.as_ref().unwrap().chars().count(), 200);
+}
+
+#[test]
+fn rejects_invalid_profiles_and_rows() {
+    assert!(matches!(CsvProfile::from_toml("date_column = 0\ndate_format = \"%Y-%m-%d\""), Err(CsvImportError::Profile(_))));
+
+    let profile = CsvProfile::from_toml(GERMAN_PROFILE).unwrap();
+    let error = read_transactions("\n\nDatum;Betrag\n".as_bytes(), &profile, "account-checking").unwrap_err();
+    assert!(matches!(error, CsvImportError::UnknownColumn(Column::Name(name)) if name == "Buchungstag"));
+
+    let statement = "\n\nBuchungstag;Empfänger;Verwendungszweck;Betrag\n2023-06-12;Bäckerei;;-4,50\n";
+    let error = read_transactions(statement.as_bytes(), &profile, "account-checking").unwrap_err();
+    assert!(matches!(error, CsvImportError::InvalidDate { line: 4, ref value } if value == "2023-06-12"));
+
+    let statement = "\n\nBuchungstag;Empfänger;Verwendungszweck;Betrag\n12.06.2023;Bäckerei;;abc\n";
+    let error = read_transactions(statement.as_bytes(), &profile, "account-checking").unwrap_err();
+    assert!(matches!(error, CsvImportError::InvalidAmount { line: 4, .. }));
+}
+
+#[tokio::test]
+async fn imports_statement_once() {
+    let server = seeded_server();
+    let client = server.client();
+    let profile = CsvProfile::from_toml(GERMAN_PROFILE).unwrap();
+
+    let first = import_csv(&client, "budget-household", "account-checking", GERMAN_STATEMENT.as_bytes(), &profile).await.unwrap();
+    assert!(first.iter().all(|outcome| matches!(outcome, ImportOutcome::Created(_))));
+
+    let second = import_csv(&client, "budget-household", "account-checking", GERMAN_STATEMENT.as_bytes(), &profile).await.unwrap();
+    assert!(second.iter().all(|outcome| matches!(outcome, ImportOutcome::Duplicate { .. })));
+}
@@ -0,0 +1,65 @@
+mod common;
+
+use common::budget;
+use ynab_rs::denormalize::{scheduled_transaction_details, transaction_details};
+use ynab_rs::models::{
+    BudgetDetail, ScheduledSubTransaction, ScheduledTransactionDetail, ScheduledTransactionsResponse, TransactionDetail,
+    TransactionsResponse
+};
+
+fn listed() -> Vec<TransactionDetail> {
+    let response: TransactionsResponse = serde_json::from_str(include_str!("fixtures/transactions.json")).unwrap();
+    response.data.transactions
+}
+
+fn scheduled_listed() -> Vec<ScheduledTransactionDetail> {
+    let response: ScheduledTransactionsResponse = serde_json::from_str(include_str!("fixtures/scheduled_transactions.json")).unwrap();
+    response.data.scheduled_transactions
+}
+
+#[test]
+fn rebuilds_what_the_transaction_list_returns() {
+    let details: Vec<TransactionDetail> = transaction_details(&budget());
+    let listed: Vec<TransactionDetail> = listed().into_iter().filter(|transaction| details.iter().any(|detail| detail.id == transaction.id)).collect();
+
+    assert_eq!(details.len(), 5);
+    assert_eq!(serde_json::to_value(&details).unwrap(), serde_json::to_value(&listed).unwrap());
+}
+
+#[test]
+fn nests_subtransactions_and_fills_in_names() {
+    let mut budget: BudgetDetail = budget();
+    for subtransaction in budget.subtransactions.iter_mut() {
+        subtransaction.category_name = None;
+    }
+    budget.subtransactions[1].deleted = true;
+    budget.transactions.iter_mut().find(|transaction| transaction.id == "transaction-rent").unwrap().deleted = true;
+
+    let details: Vec<TransactionDetail> = transaction_details(&budget);
+    assert!(details.iter().all(|detail| detail.id != "transaction-rent"));
+
+    let split: &TransactionDetail = details.iter().find(|detail| detail.id == "transaction-grocer").unwrap();
+    assert_eq!((split.account_name.as_str(), split.payee_name.as_deref()), ("Checking", Some("Grocer")));
+    assert_eq!(split.subtransactions.len(), 1);
+    assert_eq!(split.subtransactions[0].category_name.as_deref(), Some("Groceries"));
+
+    let transfer: &TransactionDetail = details.iter().find(|detail| detail.id == "transaction-transfer-out").unwrap();
+    assert_eq!(transfer.payee_name.as_deref(), Some("Transfer : Savings"));
+}
+
+#[test]
+fn rebuilds_scheduled_transactions() {
+    let mut budget: BudgetDetail = budget();
+    let details: Vec<ScheduledTransactionDetail> = scheduled_transaction_details(&budget);
+    assert_eq!(serde_json::to_value(&details).unwrap(), serde_json::to_value(&scheduled_listed()[..1]).unwrap());
+
+    let utilities: ScheduledTransactionDetail = scheduled_listed().into_iter().find(|scheduled| scheduled.id == "scheduled-utilities").unwrap();
+    budget.scheduled_transactions.push(serde_json::from_value(serde_json::to_value(&utilities).unwrap()).unwrap());
+    let mut subtransactions: Vec<ScheduledSubTransaction> = utilities.subtransactions.clone();
+    subtransactions[0].deleted = true;
+    budget.scheduled_subtransactions.extend(subtransactions);
+
+    let details: Vec<ScheduledTransactionDetail> = scheduled_transaction_details(&budget);
+    assert_eq!(details[1].account_name, "Checking");
+    assert_eq!(details[1].subtransactions.iter().map(|subtransaction| subtransaction.id.as_str()).collect::<Vec<&str>>(), vec!["scheduled-sub-water"]);
+}
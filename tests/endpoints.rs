@@ -0,0 +1,57 @@
+mod common;
+
+use chrono::Local;
+use common::seeded_server;
+use either::{Left, Right};
+use ynab_rs::models::MonthDetail;
+
+#[tokio::test]
+async fn percent_encodes_path_segments_and_parameters() {
+    let server = seeded_server();
+    let client = server.client().record();
+
+    match client.get_payee("budget-household", "payee-grocer/../../user?x=1#y").await {
+        Left(_) => panic!("The id must not escape its path segment."),
+        Right(error) => assert_eq!(error.error.id, "404.2")
+    }
+    assert!(client.get_transaction_list("budget-household", Some("2023-06-01"), Some("unapproved"), Some(100)).await.is_left());
+
+    let endpoints: Vec<String> = client.cassette().unwrap().interactions.into_iter().map(|interaction| interaction.endpoint).collect();
+    assert_eq!(endpoints, vec![
+        "/budgets/budget-household/payees/payee-grocer%2F..%2F..%2Fuser%3Fx%3D1%23y".to_string(),
+        "/budgets/budget-household/transactions?since_date=2023-06-01&type=unapproved&last_knowledge_of_server=100".to_string()
+    ]);
+}
+
+#[tokio::test]
+async fn rejects_invalid_inputs_before_sending() {
+    let server = seeded_server();
+    let client = server.client().record();
+
+    let invalid = [
+        client.get_account("budget-household", "").await.right().unwrap(),
+        client.get_account("budget-household", "..").await.right().unwrap(),
+        client.get_month("budget-household", "2023-06-01/categories").await.right().unwrap(),
+        client.get_transaction_list("budget-household", Some("06/01/2023"), None, None).await.right().unwrap(),
+        client.get_transaction_list("budget-household", None, Some("uncategorized&type=unapproved"), None).await.right().unwrap()
+    ];
+
+    assert!(invalid.iter().all(|error| error.error.id == "400" && error.error.name == "bad_request"));
+    assert!(client.cassette().unwrap().interactions.is_empty());
+
+    assert!(client.get_month("budget-household", "2023-06-01").await.is_left());
+}
+
+#[tokio::test]
+async fn resolves_the_current_month() {
+    let server = seeded_server();
+    let current: String = Local::now().date_naive().format("%Y-%m-01").to_string();
+    server.update_budget("budget-household", |budget| {
+        let mut month: MonthDetail = budget.months[0].clone();
+        month.month = current.clone();
+        budget.months.push(month);
+    });
+
+    let response = server.client().get_month("budget-household", "current").await.left().unwrap();
+    assert_eq!(response.data.month.month, current);
+}
@@ -0,0 +1,118 @@
+mod common;
+
+use common::budget;
+use serde_json::Value;
+use ynab_rs::export::flat::{FlatColumn, FlatExporter, FlatFormat, SplitMode};
+use ynab_rs::export::{format_currency, format_date};
+use ynab_rs::models::{
+    BudgetDetail, CategoriesResponse, CurrencyFormat, DateFormat, HybridTransactionsResponse, TransactionDetail, TransactionsResponse
+};
+
+fn transactions() -> Vec<TransactionDetail> {
+    let response: TransactionsResponse = serde_json::from_str(include_str!("fixtures/transactions.json")).unwrap();
+    response.data.transactions
+}
+
+fn dollars() -> CurrencyFormat {
+    CurrencyFormat {
+        iso_code:           "USD".to_string(),
+        example_format:     "123,456.78".to_string(),
+        decimal_digits:     2,
+        decimal_separator:  ".".to_string(),
+        symbol_first:       true,
+        group_separator:    ",".to_string(),
+        currency_symbol:    "$".to_string(),
+        display_symbol:     true
+    }
+}
+
+#[test]
+fn formats_currencies_and_dates() {
+    let budget: BudgetDetail = budget();
+    assert_eq!(format_currency(-1234567, &budget.currency_format), "-1.234,57 €");
+    assert_eq!(format_currency(1234567890, &budget.currency_format), "1.234.567,89 €");
+    assert_eq!(format_currency(-4, &budget.currency_format), "0,00 €");
+    assert_eq!(format_currency(-1234560, &dollars()), "-$1,234.56");
+
+    let yen: CurrencyFormat = CurrencyFormat { decimal_digits: 0, currency_symbol: "¥".to_string(), ..dollars() };
+    assert_eq!(format_currency(1500500, &yen), "¥1,501");
+    let hidden: CurrencyFormat = CurrencyFormat { display_symbol: false, ..dollars() };
+    assert_eq!(format_currency(999000, &hidden), "999.00");
+
+    assert_eq!(format_date("2023-06-05", &budget.date_format), "05.06.2023");
+    assert_eq!(format_date("2023-06-05", &DateFormat { format: "MM/DD/YY".to_string() }), "06/05/23");
+    assert_eq!(format_date("June 5th", &budget.date_format), "June 5th");
+}
+
+#[test]
+fn writes_csv_with_exploded_splits() {
+    let budget: BudgetDetail = budget();
+    let output: Vec<u8> = FlatExporter::for_budget(&budget).write_transactions(&transactions(), vec![]).unwrap();
+
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "account,flag,date,payee,category_group,category,memo,outflow,inflow,cleared\n\
+         Checking,,01.06.2023,Employer,Internal Master Category,Inflow: Ready to Assign,June salary,,\"2.500,00 €\",cleared\n\
+         Checking,blue,03.06.2023,Landlord,Bills,Rent,,\"900,00 €\",,reconciled\n\
+         Checking,,05.06.2023,Grocer,Everyday Expenses,Groceries,Weekly shop,\"60,00 €\",,uncleared\n\
+         Checking,,05.06.2023,Grocer,Everyday Expenses,Dining Out,Bakery counter,\"25,00 €\",,uncleared\n\
+         Checking,,10.06.2023,Transfer : Savings,,,,\"200,00 €\",,cleared\n\
+         Savings,,10.06.2023,Transfer : Checking,,,,,\"200,00 €\",cleared\n\
+         Mortgage,,30.06.2023,,,,Interest June,\"125,00 €\",,cleared\n"
+    );
+}
+
+#[test]
+fn aggregates_splits() {
+    let budget: BudgetDetail = budget();
+    let exporter = FlatExporter::for_budget(&budget)
+        .columns(&[FlatColumn::Id, FlatColumn::CategoryGroup, FlatColumn::Category, FlatColumn::Amount])
+        .split_mode(SplitMode::Aggregate);
+    let output: Vec<u8> = exporter.write_transactions(&transactions()[2..3], vec![]).unwrap();
+
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "id,category_group,category,amount\ntransaction-grocer,Everyday Expenses,Groceries; Dining Out,\"-85,00 €\"\n"
+    );
+}
+
+#[test]
+fn writes_json_lines() {
+    let budget: BudgetDetail = budget();
+    let exporter = FlatExporter::for_budget(&budget)
+        .columns(&[FlatColumn::Id, FlatColumn::ParentId, FlatColumn::Date, FlatColumn::Milliunits, FlatColumn::Approved, FlatColumn::Flag])
+        .format(FlatFormat::JsonLines);
+
+    let mut writer = exporter.writer(vec![]).unwrap();
+    for transaction in transactions() {
+        writer.write_transaction(&transaction).unwrap();
+    }
+    let output: String = String::from_utf8(writer.finish().unwrap()).unwrap();
+
+    let rows: Vec<Value> = output.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+    assert_eq!(rows.len(), 7);
+    assert_eq!(
+        rows[1],
+        serde_json::json!({ "id": "transaction-rent", "parent_id": null, "date": "03.06.2023", "milliunits": -900000, "approved": true, "flag": "blue" })
+    );
+    assert_eq!(rows[3]["id"], "subtransaction-dining");
+    assert_eq!(rows[3]["parent_id"], "transaction-grocer");
+    assert_eq!(rows[3]["milliunits"], -25000);
+}
+
+#[test]
+fn writes_hybrid_transactions() {
+    let budget: BudgetDetail = budget();
+    let response: HybridTransactionsResponse = serde_json::from_str(include_str!("fixtures/hybrid_transactions.json")).unwrap();
+    let categories: CategoriesResponse = serde_json::from_str(include_str!("fixtures/categories.json")).unwrap();
+
+    let exporter = FlatExporter::new(&budget.currency_format, &budget.date_format)
+        .category_groups(&categories.data.category_groups)
+        .columns(&[FlatColumn::Id, FlatColumn::ParentId, FlatColumn::CategoryGroup, FlatColumn::Category]);
+    let output: String = String::from_utf8(exporter.write_hybrid_transactions(&response.data.transactions, vec![]).unwrap()).unwrap();
+
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines.len(), 6);
+    assert_eq!(lines[2], "transaction-rent,,Bills,Rent");
+    assert_eq!(lines[4], "subtransaction-groceries,transaction-grocer,Everyday Expenses,Groceries");
+}
@@ -0,0 +1,48 @@
+use ynab_rs::hybrid::{flatten_transaction_details, group_hybrid_transactions};
+use ynab_rs::models::{HybridTransaction, HybridTransactionsResponse, TransactionDetail, TransactionsResponse};
+
+fn hybrids() -> Vec<HybridTransaction> {
+    let response: HybridTransactionsResponse = serde_json::from_str(include_str!("fixtures/hybrid_transactions.json")).unwrap();
+    response.data.transactions
+}
+
+// The details behind the hybrid fixture's rows.
+fn details() -> Vec<TransactionDetail> {
+    let response: TransactionsResponse = serde_json::from_str(include_str!("fixtures/transactions.json")).unwrap();
+    response.data.transactions.into_iter()
+        .filter(|transaction| ["transaction-paycheck", "transaction-rent", "transaction-grocer"].contains(&transaction.id.as_str()))
+        .collect()
+}
+
+#[test]
+fn groups_split_rows_under_their_parent() {
+    let grouped: Vec<TransactionDetail> = group_hybrid_transactions(&hybrids());
+
+    assert_eq!(grouped.len(), 3);
+    assert_eq!(serde_json::to_value(&grouped).unwrap(), serde_json::to_value(details()).unwrap());
+}
+
+#[test]
+fn flattens_details_into_hybrid_rows() {
+    let flattened: Vec<HybridTransaction> = flatten_transaction_details(&details());
+
+    assert_eq!(serde_json::to_value(&flattened).unwrap(), serde_json::to_value(hybrids()).unwrap());
+}
+
+#[test]
+fn stands_in_for_missing_parents() {
+    let mut rows: Vec<HybridTransaction> = hybrids().into_iter().filter(|row| row.id != "transaction-grocer").collect();
+    rows.swap(0, 2);
+
+    let grouped: Vec<TransactionDetail> = group_hybrid_transactions(&rows);
+    let ids: Vec<&str> = grouped.iter().map(|transaction| transaction.id.as_str()).collect();
+    assert_eq!(ids, vec!["transaction-grocer", "transaction-rent", "transaction-paycheck"]);
+
+    let stand_in: &TransactionDetail = &grouped[0];
+    assert_eq!(stand_in.amount, -85000);
+    assert_eq!((stand_in.date.as_str(), stand_in.account_name.as_str()), ("2023-06-05", "Checking"));
+    assert!(stand_in.payee_id.is_none() && stand_in.category_id.is_none() && stand_in.memo.is_none());
+    assert_eq!(stand_in.subtransactions.len(), 2);
+    assert_eq!(stand_in.subtransactions[0].payee_name.as_deref(), Some("Grocer"));
+    assert_eq!(stand_in.subtransactions[1].category_name.as_deref(), Some("Dining Out"));
+}
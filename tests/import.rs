@@ -0,0 +1,81 @@
+mod common;
+
+use common::{date, seeded_server};
+use ynab_rs::builder::SaveTransactionBuilder;
+use ynab_rs::import::{BatchImporter, ImportIdGenerator, ImportOutcome};
+use ynab_rs::models::SaveTransaction;
+
+fn statement() -> Vec<SaveTransaction> {
+    vec![
+        SaveTransactionBuilder::new("account-checking", date(2023, 6, 12), -4500).payee_name("Bakery").build().unwrap(),
+        SaveTransactionBuilder::new("account-checking", date(2023, 6, 12), -4500).payee_name("Bakery").build().unwrap(),
+        SaveTransactionBuilder::new("account-checking", date(2023, 6, 13), -12000).payee_id("payee-grocer").build().unwrap(),
+        SaveTransactionBuilder::new("account-savings", date(2023, 6, 12), -4500).build().unwrap()
+    ]
+}
+
+#[test]
+fn counts_occurrences_per_amount_and_date() {
+    let mut generator = ImportIdGenerator::new();
+
+    assert_eq!(generator.next(-294230, date(2015, 12, 30)), "YNAB:-294230:2015-12-30:1");
+    assert_eq!(generator.next(-294230, date(2015, 12, 30)), "YNAB:-294230:2015-12-30:2");
+    assert_eq!(generator.next(-294230, date(2015, 12, 31)), "YNAB:-294230:2015-12-31:1");
+    assert_eq!(generator.next(10000, date(2015, 12, 30)), "YNAB:10000:2015-12-30:1");
+}
+
+#[tokio::test]
+async fn reruns_report_duplicates() {
+    let server = seeded_server();
+    let client = server.client();
+    let importer = BatchImporter::new(&client, "budget-household").batch_size(2);
+
+    let first = importer.import(statement()).await;
+    let import_ids: Vec<String> = first.iter().map(|outcome| match outcome {
+        ImportOutcome::Created(detail) => detail.import_id.clone().unwrap(),
+        other => panic!("Unexpected outcome: {:?}", other)
+    }).collect();
+    assert_eq!(import_ids, vec![
+        "YNAB:-4500:2023-06-12:1",
+        "YNAB:-4500:2023-06-12:2",
+        "YNAB:-12000:2023-06-13:1",
+        "YNAB:-4500:2023-06-12:1"
+    ]);
+
+    let second = importer.import(statement()).await;
+    assert!(second.iter().zip(&import_ids).all(|(outcome, import_id)| {
+        matches!(outcome, ImportOutcome::Duplicate { import_id: duplicate } if duplicate == import_id)
+    }));
+    assert_eq!(server.budget("budget-household").unwrap().transactions.len(), 9);
+}
+
+#[tokio::test]
+async fn reports_invalid_rows_without_sending_them() {
+    let server = seeded_server();
+    let client = server.client();
+
+    let mut invalid = statement().remove(0);
+    invalid.payee_id = Some("payee-grocer".to_string());
+
+    let outcomes = BatchImporter::new(&client, "budget-household").import(vec![invalid, statement().remove(2)]).await;
+    assert!(matches!(outcomes[0], ImportOutcome::Invalid(_)));
+    assert!(matches!(outcomes[1], ImportOutcome::Created(_)));
+}
+
+#[tokio::test]
+async fn reports_repeated_import_ids_within_a_batch() {
+    let server = seeded_server();
+    let client = server.client();
+
+    let mut rows = statement();
+    for row in &mut rows {
+        row.import_id = Some("BANK:0042".to_string());
+    }
+
+    let outcomes = BatchImporter::new(&client, "budget-household").import(rows).await;
+    assert!(matches!(&outcomes[0], ImportOutcome::Created(detail) if detail.account_id == "account-checking"));
+    assert!(matches!(&outcomes[1], ImportOutcome::Duplicate { import_id } if import_id == "BANK:0042"));
+    assert!(matches!(&outcomes[2], ImportOutcome::Duplicate { import_id } if import_id == "BANK:0042"));
+    assert!(matches!(&outcomes[3], ImportOutcome::Created(detail) if detail.account_id == "account-savings"));
+    assert_eq!(server.budget("budget-household").unwrap().transactions.len(), 7);
+}
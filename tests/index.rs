@@ -0,0 +1,99 @@
+mod common;
+
+use common::budget;
+use ynab_rs::index::{BudgetIndex, IndexFilter};
+use ynab_rs::models::{BudgetDetail, Category, SubTransaction};
+
+fn ids<T>(items: &[&T], id: fn(&T) -> &str) -> Vec<String> {
+    items.iter().map(|item| id(item).to_string()).collect()
+}
+
+#[test]
+fn looks_entries_up_by_id() {
+    let budget: BudgetDetail = budget();
+    let index: BudgetIndex = BudgetIndex::new(&budget);
+
+    assert_eq!(index.account("account-savings").unwrap().name, "Savings");
+    assert_eq!(index.payee("payee-grocer").unwrap().name, "Grocer");
+    assert_eq!(index.payee_location("location-grocer").unwrap().payee_id, "payee-grocer");
+    assert_eq!(index.category("category-rent").unwrap().name, "Rent");
+    assert_eq!(index.category_group("group-bills").unwrap().name, "Bills");
+    assert_eq!(index.month("2023-06-01").unwrap().month, "2023-06-01");
+    assert_eq!(index.transaction("transaction-rent").unwrap().payee_id.as_deref(), Some("payee-landlord"));
+    assert_eq!(index.subtransaction("subtransaction-dining").unwrap().transaction_id, "transaction-grocer");
+    assert_eq!(index.scheduled_transaction("scheduled-rent").unwrap().payee_id.as_deref(), Some("payee-landlord"));
+    assert!(index.account("account-missing").is_none());
+}
+
+#[test]
+fn groups_children_by_parent_in_budget_order() {
+    let budget: BudgetDetail = budget();
+    let index: BudgetIndex = BudgetIndex::new(&budget);
+
+    assert_eq!(
+        ids(index.subtransactions_of("transaction-grocer"), |subtransaction: &SubTransaction| &subtransaction.id),
+        vec!["subtransaction-groceries", "subtransaction-dining"]
+    );
+    assert_eq!(
+        ids(index.categories_in("group-everyday"), |category: &Category| &category.id),
+        vec!["category-groceries", "category-dining"]
+    );
+    assert_eq!(index.payee_locations_of("payee-grocer").len(), 1);
+    assert!(index.subtransactions_of("transaction-rent").is_empty());
+    assert!(index.payee_locations_of("payee-landlord").is_empty());
+    assert!(index.scheduled_subtransactions_of("scheduled-rent").is_empty());
+}
+
+#[test]
+fn resolves_categories_and_transfer_payees() {
+    let budget: BudgetDetail = budget();
+    let index: BudgetIndex = BudgetIndex::new(&budget);
+
+    let (category, group) = index.category_with_group("category-dining").unwrap();
+    assert_eq!((category.name.as_str(), group.name.as_str()), ("Dining Out", "Everyday Expenses"));
+    assert_eq!(index.transfer_account("payee-transfer-savings").unwrap().id, "account-savings");
+    assert!(index.transfer_account("payee-grocer").is_none());
+}
+
+#[test]
+fn live_filter_drops_deleted_entries_and_their_children() {
+    let mut budget: BudgetDetail = budget();
+    budget.transactions.iter_mut().find(|transaction| transaction.id == "transaction-grocer").unwrap().deleted = true;
+    budget.payees.iter_mut().find(|payee| payee.id == "payee-grocer").unwrap().deleted = true;
+    budget.accounts.iter_mut().find(|account| account.id == "account-savings").unwrap().deleted = true;
+
+    let all: BudgetIndex = BudgetIndex::new(&budget);
+    assert!(all.transaction("transaction-grocer").is_some());
+    assert_eq!(all.subtransactions_of("transaction-grocer").len(), 2);
+
+    let live: BudgetIndex = BudgetIndex::with_filter(&budget, IndexFilter::live());
+    assert!(live.transaction("transaction-grocer").is_none());
+    assert!(live.subtransaction("subtransaction-dining").is_none());
+    assert!(live.subtransactions_of("transaction-grocer").is_empty());
+    assert!(live.payee_location("location-grocer").is_none());
+    assert!(live.payee_locations_of("payee-grocer").is_empty());
+    assert!(live.transfer_account("payee-transfer-savings").is_none());
+    assert_eq!(live.transactions().count(), 4);
+    assert_eq!(live.accounts().map(|account| account.id.as_str()).collect::<Vec<&str>>(), vec!["account-checking"]);
+}
+
+#[test]
+fn visible_filter_drops_hidden_groups_and_categories() {
+    let mut budget: BudgetDetail = budget();
+    budget.category_groups.iter_mut().find(|group| group.id == "group-bills").unwrap().hidden = true;
+    budget.categories.iter_mut().find(|category| category.id == "category-dining").unwrap().hidden = true;
+
+    let live: BudgetIndex = BudgetIndex::with_filter(&budget, IndexFilter::live());
+    assert!(live.category("category-rent").is_some());
+    assert_eq!(live.categories_in("group-everyday").len(), 2);
+
+    let visible: BudgetIndex = BudgetIndex::with_filter(&budget, IndexFilter::visible());
+    assert!(visible.category_group("group-bills").is_none());
+    assert!(visible.category("category-rent").is_none());
+    assert!(visible.categories_in("group-bills").is_empty());
+    assert_eq!(
+        ids(visible.categories_in("group-everyday"), |category: &Category| &category.id),
+        vec!["category-groceries"]
+    );
+    assert_eq!(visible.category_groups().count(), 2);
+}
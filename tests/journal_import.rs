@@ -0,0 +1,207 @@
+mod common;
+
+use common::{budget, seeded_server};
+use ynab_rs::export::beancount::BeancountExporter;
+use ynab_rs::export::ledger::LedgerExporter;
+use ynab_rs::import::journal::{import_journal, parse_journal, read_transactions, JournalFormat, JournalImportError, JournalMapping};
+use ynab_rs::import::ImportOutcome;
+use ynab_rs::models::{Account, BudgetDetail, ClearedStatus};
+
+const LEDGER: &str = "\
+; adjustments from the accountant
+P 2023-06-01 USD 0.92 EUR
+
+= expr account =~ /Rent/
+    (Budget:Rent)  -1
+
+2023-06-20 * (A-17) Corner Bistro  ; team lunch
+    Expenses:Everyday Expenses:Dining Out  42.50 EUR
+    Assets:Checking
+
+2023/06/21=2023/06/22 ! Grocer
+    Assets:Checking  -1,060.00 EUR
+    Expenses:Everyday Expenses:Groceries  60.00 EUR  ; fruit
+    Expenses:Bills:Rent  1000.00 EUR
+    (Budget:Rent)  -1000.00 EUR
+
+2023-06-22 * Move to savings
+    Assets:Checking  -50.00 EUR
+    Assets:Savings  50.00 EUR = 250.00 EUR
+
+2023-06-30 * Balance assertion
+    Assets:Savings  0.00 EUR = 250.00 EUR
+";
+
+const BEANCOUNT: &str = "\
+option \"operating_currency\" \"EUR\"
+
+2023-06-01 open Assets:Checking EUR
+  ynab-id: \"account-checking\"
+
+2023-06-20 txn \"Corner Bistro\" \"team lunch\" #work
+  source: \"receipt\"
+  Expenses:Everyday-Expenses:Dining-Out  42.50 EUR
+  Assets:Checking
+
+2023-06-21 ! \"\" \"Grocer \\\"bulk\\\" run\"
+  Assets:Checking  -85.00 EUR
+  Expenses:Everyday-Expenses:Groceries  60.00 EUR
+    memo: \"fruit\"
+  Expenses:Uncategorized  25.00 EUR ; no receipt
+
+2023-06-30 balance Assets:Checking  1000.00 EUR
+";
+
+fn mapping() -> JournalMapping {
+    JournalMapping::from_toml(r#"
+uncategorized = ["Expenses:Uncategorized"]
+
+[accounts]
+"Assets:Checking" = "account-checking"
+"Assets:Savings" = "account-savings"
+
+[categories]
+"Expenses:Bills:Rent" = "category-rent"
+"Expenses:Everyday Expenses:Groceries" = "category-groceries"
+"Expenses:Everyday Expenses:Dining Out" = "category-dining"
+"Expenses:Everyday-Expenses:Groceries" = "category-groceries"
+"Expenses:Everyday-Expenses:Dining-Out" = "category-dining"
+"Income:Ready to Assign" = "category-ready-to-assign"
+"Income:Ready-to-Assign" = "category-ready-to-assign"
+"#).unwrap()
+}
+
+fn accounts() -> Vec<Account> {
+    budget().accounts
+}
+
+#[test]
+fn parses_ledger_entries() {
+    let entries = parse_journal(LEDGER.as_bytes(), JournalFormat::Ledger).unwrap();
+    assert_eq!(entries.len(), 3);
+
+    let lunch = &entries[0];
+    assert_eq!((lunch.line, lunch.date.to_string(), lunch.cleared), (7, "2023-06-20".to_string(), true));
+    assert_eq!((lunch.payee.as_deref(), lunch.memo.as_deref()), (Some("Corner Bistro"), Some("team lunch")));
+    assert_eq!(lunch.postings[1].account, "Assets:Checking");
+    assert_eq!(lunch.postings[1].amount, -42500);
+
+    let groceries = &entries[1];
+    assert_eq!((groceries.date.to_string(), groceries.cleared), ("2023-06-21".to_string(), false));
+    assert_eq!(groceries.postings.iter().map(|posting| posting.amount).collect::<Vec<i64>>(), vec![-1060000, 60000, 1000000]);
+    assert_eq!(groceries.postings[1].memo.as_deref(), Some("fruit"));
+
+    assert_eq!(entries[2].postings[1].amount, 50000);
+}
+
+#[test]
+fn parses_beancount_entries() {
+    let entries = parse_journal(BEANCOUNT.as_bytes(), JournalFormat::Beancount).unwrap();
+    assert_eq!(entries.len(), 2);
+
+    assert_eq!((entries[0].payee.as_deref(), entries[0].memo.as_deref(), entries[0].cleared), (Some("Corner Bistro"), Some("team lunch"), true));
+    assert_eq!(entries[0].postings[1].amount, -42500);
+
+    assert_eq!((entries[1].payee.as_deref(), entries[1].memo.as_deref(), entries[1].cleared), (None, Some("Grocer \"bulk\" run"), false));
+    assert_eq!(entries[1].postings[1].memo.as_deref(), Some("fruit"));
+    assert_eq!(entries[1].postings[2].memo, None);
+}
+
+#[test]
+fn maps_postings_to_transactions() {
+    let transactions = read_transactions(LEDGER.as_bytes(), JournalFormat::Ledger, &mapping(), &accounts()).unwrap();
+    assert_eq!(transactions.len(), 3);
+
+    let lunch = &transactions[0];
+    assert_eq!((lunch.account_id.as_str(), lunch.amount, lunch.category_id.as_deref()), ("account-checking", -42500, Some("category-dining")));
+    assert_eq!((lunch.payee_name.as_deref(), lunch.memo.as_deref()), (Some("Corner Bistro"), Some("team lunch")));
+    assert!(matches!(lunch.cleared, Some(ClearedStatus::Cleared)));
+    assert!(lunch.subtransactions.is_none());
+
+    let split = &transactions[1];
+    assert_eq!((split.amount, split.category_id.as_deref()), (-1060000, None));
+    assert!(matches!(split.cleared, Some(ClearedStatus::Uncleared)));
+    let lines = split.subtransactions.as_ref().unwrap();
+    assert_eq!(
+        lines.iter().map(|line| (line.amount, line.category_id.as_deref(), line.memo.as_deref())).collect::<Vec<_>>(),
+        vec![(-60000, Some("category-groceries"), Some("fruit")), (-1000000, Some("category-rent"), None)]
+    );
+
+    let transfer = &transactions[2];
+    assert_eq!((transfer.amount, transfer.payee_id.as_deref(), transfer.payee_name.as_deref()), (-50000, Some("payee-transfer-savings"), None));
+
+    let beancount = read_transactions(BEANCOUNT.as_bytes(), JournalFormat::Beancount, &mapping(), &accounts()).unwrap();
+    let lines = beancount[1].subtransactions.as_ref().unwrap();
+    assert_eq!((beancount[1].amount, beancount[1].payee_name.as_deref()), (-85000, None));
+    assert_eq!((lines[1].amount, lines[1].category_id.as_deref()), (-25000, None));
+}
+
+#[test]
+fn derives_import_ids_from_entries() {
+    let first = read_transactions(LEDGER.as_bytes(), JournalFormat::Ledger, &mapping(), &accounts()).unwrap();
+    let again = read_transactions(LEDGER.as_bytes(), JournalFormat::Ledger, &mapping(), &accounts()).unwrap();
+    let import_ids: Vec<String> = first.iter().map(|transaction| transaction.import_id.clone().unwrap()).collect();
+    assert_eq!(import_ids, again.iter().map(|transaction| transaction.import_id.clone().unwrap()).collect::<Vec<String>>());
+    assert!(import_ids.iter().all(|import_id| import_id.starts_with("JRNL:") && import_id.len() == 21));
+
+    // Clearing an entry keeps its id, editing it doesn't, and repeats are numbered.
+    let cleared = read_transactions(LEDGER.replace("! Grocer", "* Grocer").as_bytes(), JournalFormat::Ledger, &mapping(), &accounts()).unwrap();
+    assert_eq!(cleared[1].import_id.as_ref(), Some(&import_ids[1]));
+    let edited = read_transactions(LEDGER.replace("42.50", "42.60").as_bytes(), JournalFormat::Ledger, &mapping(), &accounts()).unwrap();
+    assert_ne!(edited[0].import_id.as_ref(), Some(&import_ids[0]));
+
+    let lunch: &str = "2023-06-20 * Corner Bistro\n    Expenses:Everyday Expenses:Dining Out  42.50 EUR\n    Assets:Checking\n\n";
+    let twice = read_transactions(lunch.repeat(2).as_bytes(), JournalFormat::Ledger, &mapping(), &accounts()).unwrap();
+    assert_eq!(twice[1].import_id, Some(format!("{}:2", twice[0].import_id.as_ref().unwrap())));
+}
+
+#[test]
+fn skips_entries_exported_from_ynab() {
+    let budget: BudgetDetail = budget();
+
+    let mut ledger: Vec<u8> = vec![];
+    LedgerExporter::new(&budget).write_budget(&mut ledger).unwrap();
+    assert_eq!(parse_journal(ledger.as_slice(), JournalFormat::Ledger).unwrap().len(), 4);
+    assert!(read_transactions(ledger.as_slice(), JournalFormat::Ledger, &mapping(), &budget.accounts).unwrap().is_empty());
+
+    let mut beancount: Vec<u8> = vec![];
+    BeancountExporter::new(&budget).write_budget(&mut beancount).unwrap();
+    assert_eq!(parse_journal(beancount.as_slice(), JournalFormat::Beancount).unwrap().len(), 4);
+    assert!(read_transactions(beancount.as_slice(), JournalFormat::Beancount, &mapping(), &budget.accounts).unwrap().is_empty());
+}
+
+#[test]
+fn reports_problems() {
+    let read = |journal: &str| read_transactions(journal.as_bytes(), JournalFormat::Ledger, &mapping(), &accounts()).unwrap_err();
+
+    let error = read("2023-06-20 * Bistro\n    Expenses:Everyday Expenses:Dining Out  42.50 EUR\n    Assets:Checking  -40.00 EUR\n");
+    assert!(matches!(error, JournalImportError::UnbalancedEntry { line: 1 }));
+
+    let error = read("2023-06-20 * Bistro\n    Expenses:Travel  42.50 EUR\n    Assets:Checking\n");
+    assert!(matches!(error, JournalImportError::UnmappedAccount { line: 1, ref account } if account == "Expenses:Travel"));
+
+    let error = read("2023-06-20 * Reclass\n    Expenses:Bills:Rent  42.50 EUR\n    Expenses:Everyday Expenses:Groceries\n");
+    assert!(matches!(error, JournalImportError::NoAccount { line: 1 }));
+
+    let error = read("\n2023-06-31 * Bistro\n");
+    assert!(matches!(error, JournalImportError::InvalidDate { line: 2, ref value } if value == "2023-06-31"));
+
+    let error = read("2023-06-20 * Bistro\n    Assets:Checking  lots\n    Expenses:Bills:Rent\n");
+    assert!(matches!(error, JournalImportError::InvalidAmount { line: 2, ref value } if value == "lots"));
+}
+
+#[tokio::test]
+async fn imports_journal() {
+    let server = seeded_server();
+    let client = server.client();
+
+    let outcomes = import_journal(&client, "budget-household", LEDGER.as_bytes(), JournalFormat::Ledger, &mapping()).await.unwrap();
+    assert_eq!(outcomes.len(), 3);
+    assert!(outcomes.iter().all(|outcome| matches!(outcome, ImportOutcome::Created(_))));
+
+    let outcomes = import_journal(&client, "budget-household", LEDGER.as_bytes(), JournalFormat::Ledger, &mapping()).await.unwrap();
+    assert!(outcomes.iter().all(|outcome| matches!(outcome, ImportOutcome::Duplicate { .. })));
+
+    let error = import_journal(&client, "budget-missing", LEDGER.as_bytes(), JournalFormat::Ledger, &mapping()).await.unwrap_err();
+    assert!(matches!(error, JournalImportError::Accounts(_)));
+}
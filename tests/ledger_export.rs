@@ -0,0 +1,120 @@
+mod common;
+
+use common::budget;
+use ynab_rs::export::ledger::LedgerExporter;
+use ynab_rs::models::{AccountType, BudgetDetail, TransactionDetail, TransactionsResponse};
+
+fn queried() -> Vec<TransactionDetail> {
+    let response: TransactionsResponse = serde_json::from_str(include_str!("fixtures/transactions.json")).unwrap();
+    response.data.transactions
+}
+
+fn journal(exporter: &LedgerExporter) -> String {
+    let mut output: Vec<u8> = vec![];
+    exporter.write_budget(&mut output).unwrap();
+    String::from_utf8(output).unwrap()
+}
+
+fn postings<'a>(journal: &'a str, header: &str) -> Vec<&'a str> {
+    journal.split("\n\n")
+        .find(|entry| entry.starts_with(header))
+        .unwrap_or_else(|| panic!("no entry `{}` in\n{}", header, journal))
+        .lines()
+        .skip(1)
+        .map(str::trim)
+        .collect()
+}
+
+#[test]
+fn maps_accounts_and_categories() {
+    let budget: BudgetDetail = budget();
+    let journal: String = journal(&LedgerExporter::new(&budget));
+
+    assert_eq!(
+        postings(&journal, "2023-06-01 * Employer"),
+        vec![
+            "; June salary",
+            "; ynab-id: transaction-paycheck",
+            "Assets:Checking                           2500.00 EUR",
+            "Income:Ready to Assign                    -2500.00 EUR"
+        ]
+    );
+    assert_eq!(
+        postings(&journal, "2023-06-03 * Landlord")[1..],
+        ["Assets:Checking                           -900.00 EUR", "Expenses:Bills:Rent                       900.00 EUR"]
+    );
+
+    let mut liability: BudgetDetail = budget.clone();
+    liability.accounts[1].r#type = AccountType::CreditCard;
+    let journal: String = self::journal(&LedgerExporter::new(&liability));
+    assert!(journal.contains("Liabilities:Savings"));
+    assert!(!journal.contains("Assets:Savings"));
+}
+
+#[test]
+fn writes_splits_as_postings() {
+    let budget: BudgetDetail = budget();
+    let journal: String = journal(&LedgerExporter::new(&budget));
+
+    assert_eq!(
+        postings(&journal, "2023-06-05 ! Grocer"),
+        vec![
+            "; Weekly shop",
+            "; ynab-id: transaction-grocer",
+            "Assets:Checking                           -85.00 EUR",
+            "Expenses:Everyday Expenses:Groceries      60.00 EUR",
+            "Expenses:Everyday Expenses:Dining Out     25.00 EUR  ; Bakery counter"
+        ]
+    );
+}
+
+#[test]
+fn writes_transfers_once() {
+    let budget: BudgetDetail = budget();
+    let journal: String = journal(&LedgerExporter::new(&budget));
+
+    assert_eq!(journal.matches("2023-06-10 * Transfer").count(), 1);
+    assert!(journal.contains("ynab-id: transaction-transfer-in"));
+    assert!(!journal.contains("ynab-id: transaction-transfer-out"));
+    assert_eq!(
+        postings(&journal, "2023-06-10 * Transfer : Checking")[1..],
+        ["Assets:Savings                            200.00 EUR", "Assets:Checking                           -200.00 EUR"]
+    );
+}
+
+#[test]
+fn asserts_cleared_balances() {
+    let budget: BudgetDetail = budget();
+    let journal: String = journal(&LedgerExporter::new(&budget));
+
+    assert_eq!(
+        postings(&journal, "2023-06-10 * Balance assertion"),
+        vec!["Assets:Savings                            0.00 EUR = 200.00 EUR"]
+    );
+    assert!(journal.contains("; Assets:Checking cleared balance 1400.00 EUR not asserted, -85.00 EUR uncleared"));
+
+    let journal: String = self::journal(&LedgerExporter::new(&budget).balance_assertions(false));
+    assert!(!journal.contains("Balance assertion"));
+    assert!(!journal.contains("not asserted"));
+}
+
+#[test]
+fn exports_query_results() {
+    let mut budget: BudgetDetail = budget();
+    let transactions: Vec<TransactionDetail> = queried();
+
+    let mut output: Vec<u8> = vec![];
+    LedgerExporter::new(&budget).write_transactions(&transactions, &mut output).unwrap();
+    let journal: String = String::from_utf8(output).unwrap();
+    assert!(!journal.contains("transaction-deleted"));
+    assert!(!journal.contains("Balance assertion"));
+    assert_eq!(journal.matches("Transfer :").count(), 1);
+    assert_eq!(postings(&journal, "2023-06-05 ! Grocer").len(), 5);
+
+    // Without the budget's transactions the transfer pair is recognised by date and amount.
+    budget.transactions.clear();
+    let mut output: Vec<u8> = vec![];
+    LedgerExporter::new(&budget).write_transactions(&transactions, &mut output).unwrap();
+    let journal: String = String::from_utf8(output).unwrap();
+    assert_eq!(journal.matches("Transfer :").count(), 1);
+}
@@ -0,0 +1,96 @@
+mod common;
+
+use chrono::NaiveDate;
+use ynab_rs::builder::SaveTransactionBuilder;
+use ynab_rs::import::matcher::{match_transactions, similarity, MatchOptions};
+use ynab_rs::import::{BatchImporter, ImportOutcome};
+use ynab_rs::mock::MockServer;
+use ynab_rs::models::{ClearedStatus, PostTransactionsWrapper, SaveTransaction, TransactionDetail};
+
+fn transaction(day: u32, amount: i64, payee_name: &str) -> SaveTransaction {
+    SaveTransactionBuilder::new("account-checking", NaiveDate::from_ymd_opt(2023, 7, day).unwrap(), amount)
+        .payee_name(payee_name)
+        .build()
+        .unwrap()
+}
+
+fn imported(day: u32, amount: i64, payee_name: &str) -> SaveTransaction {
+    SaveTransaction { cleared: Some(ClearedStatus::Cleared), ..transaction(day, amount, payee_name) }
+}
+
+// Manual entries as a person would type them: uncleared and without an import id.
+async fn with_manual_entries() -> (MockServer, Vec<TransactionDetail>) {
+    let server = common::seeded_server();
+    let wrapper = PostTransactionsWrapper {
+        transaction:    None,
+        transactions:   Some(vec![
+            transaction(3, -4500, "Bakery"),
+            transaction(9, -4500, "Bakery"),
+            transaction(4, -32000, "Corner Bistro"),
+            transaction(30, -1000, "Kiosk")
+        ])
+    };
+    let created = server.client().create_transactions("budget-household", &wrapper).await.left().unwrap();
+    (server, created.data.transactions.unwrap())
+}
+
+#[test]
+fn scores_payee_similarity() {
+    assert_eq!(similarity("Bakery", "BAKERY"), 1.0);
+    assert_eq!(similarity("Bakery", "Kiosk"), 0.0);
+    assert!(similarity("CORNER BISTRO 4411", "Corner Bistro") > 0.8);
+    assert_eq!(similarity("", "Bakery"), 0.0);
+}
+
+#[tokio::test]
+async fn matches_closest_entry_once() {
+    let (_server, existing) = with_manual_entries().await;
+    let rows = vec![imported(5, -4500, "BAKERY"), imported(5, -4500, "BAKERY"), imported(5, -4500, "BAKERY"), imported(5, -12000, "Grocer")];
+
+    let pairs = match_transactions(&rows, &existing, &MatchOptions::new());
+    assert_eq!(pairs.len(), 2);
+    assert_eq!((pairs[0].row, pairs[0].transaction_id.as_str(), pairs[0].days_apart), (0, existing[0].id.as_str(), 2));
+    assert_eq!((pairs[1].row, pairs[1].transaction_id.as_str(), pairs[1].days_apart), (1, existing[1].id.as_str(), 4));
+    assert_eq!(pairs, match_transactions(&rows, &existing, &MatchOptions::new()));
+
+    let narrow = MatchOptions::new().date_window_days(3);
+    assert_eq!(match_transactions(&rows, &existing, &narrow).len(), 1);
+}
+
+#[tokio::test]
+async fn payee_similarity_rejects_different_payees() {
+    let (_server, existing) = with_manual_entries().await;
+    let rows = vec![imported(4, -32000, "CORNER BISTRO 4411"), imported(30, -1000, "Parking Garage")];
+
+    assert_eq!(match_transactions(&rows, &existing, &MatchOptions::new()).len(), 2);
+
+    let pairs = match_transactions(&rows, &existing, &MatchOptions::new().payee_similarity(0.6));
+    assert_eq!(pairs.len(), 1);
+    assert_eq!(pairs[0].transaction_id, existing[2].id);
+    assert!(pairs[0].payee_similarity.unwrap() > 0.8);
+}
+
+#[tokio::test]
+async fn importer_patches_matched_rows() {
+    let (server, existing) = with_manual_entries().await;
+    let client = server.client();
+    let statement = vec![imported(4, -4500, "BAKERY"), imported(20, -7000, "Cinema")];
+    let importer = BatchImporter::new(&client, "budget-household").match_existing(MatchOptions::new());
+
+    let outcomes = importer.import(statement.clone()).await;
+    match &outcomes[0] {
+        ImportOutcome::Matched(detail) => {
+            assert_eq!(detail.id, existing[0].id);
+            assert!(matches!(detail.cleared, ClearedStatus::Cleared));
+            assert_eq!(detail.import_id.as_deref(), Some("YNAB:-4500:2023-07-04:1"));
+        },
+        other => panic!("Unexpected outcome: {:?}", other)
+    }
+    assert!(matches!(outcomes[1], ImportOutcome::Created(_)));
+
+    let outcomes = importer.import(statement).await;
+    assert!(outcomes.iter().all(|outcome| matches!(outcome, ImportOutcome::Duplicate { .. })));
+
+    let budget = server.budget("budget-household").unwrap();
+    assert_eq!(budget.transactions.iter().filter(|transaction| transaction.amount == -4500).count(), 2);
+}
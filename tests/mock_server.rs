@@ -0,0 +1,102 @@
+mod common;
+
+use common::seeded_server;
+use either::{Left, Right};
+use ynab_rs::mock::MockError;
+use ynab_rs::models::ClearedStatus;
+
+#[tokio::test]
+async fn serves_seeded_budget() {
+    let server = seeded_server();
+    let client = server.client();
+
+    match client.get_budgets(true).await {
+        Left(response) => {
+            assert_eq!(response.data.budgets.len(), 1);
+            assert_eq!(response.data.budgets[0].accounts.as_ref().unwrap().len(), 2);
+        },
+        Right(error) => panic!("Unexpected error: {:?}", error)
+    }
+
+    match client.get_account("budget-household", "account-savings").await {
+        Left(response) => assert_eq!(response.data.account.balance, 200000),
+        Right(error) => panic!("Unexpected error: {:?}", error)
+    }
+}
+
+#[tokio::test]
+async fn denormalizes_transactions() {
+    let server = seeded_server();
+    let client = server.client();
+
+    match client.get_transaction("budget-household", "transaction-grocer").await {
+        Left(response) => {
+            let transaction = response.data.transaction;
+            assert_eq!(transaction.account_name, "Checking");
+            assert_eq!(transaction.payee_name.as_deref(), Some("Grocer"));
+            assert_eq!(transaction.subtransactions.len(), 2);
+        },
+        Right(error) => panic!("Unexpected error: {:?}", error)
+    }
+
+    match client.get_transaction_list("budget-household", Some("2023-06-04"), Some("unapproved"), None).await {
+        Left(response) => {
+            assert_eq!(response.data.transactions.len(), 1);
+            assert!(matches!(response.data.transactions[0].cleared, ClearedStatus::Uncleared));
+        },
+        Right(error) => panic!("Unexpected error: {:?}", error)
+    }
+}
+
+#[tokio::test]
+async fn returns_server_knowledge_deltas() {
+    let server = seeded_server();
+    let client = server.client();
+
+    let server_knowledge = server.update_budget("budget-household", |budget| {
+        budget.accounts[1].note = Some("Holiday fund".to_string());
+    });
+    assert_eq!(server_knowledge, 101);
+
+    match client.get_account_list("budget-household", Some(100)).await {
+        Left(response) => {
+            assert_eq!(response.data.server_knowledge, 101);
+            assert_eq!(response.data.accounts.len(), 1);
+            assert_eq!(response.data.accounts[0].id, "account-savings");
+        },
+        Right(error) => panic!("Unexpected error: {:?}", error)
+    }
+
+    match client.get_budget("budget-household", Some(101)).await {
+        Left(response) => {
+            assert!(response.data.budget.accounts.is_empty());
+            assert!(response.data.budget.transactions.is_empty());
+        },
+        Right(error) => panic!("Unexpected error: {:?}", error)
+    }
+}
+
+#[tokio::test]
+async fn injects_errors() {
+    let server = seeded_server();
+    let client = server.client();
+
+    server.inject_error("/budgets/budget-household/payees", MockError::TooManyRequests);
+    match client.get_payee_list("budget-household", None).await {
+        Left(_) => panic!("Expected an injected error."),
+        Right(error) => assert_eq!(error.error.id, "429")
+    }
+
+    assert!(client.get_payee_list("budget-household", None).await.is_left());
+
+    match client.get_payee("budget-household", "payee-unknown").await {
+        Left(_) => panic!("Expected a missing resource."),
+        Right(error) => assert_eq!(error.error.id, "404.2")
+    }
+
+    let unauthorized = ynab_rs::client::Client::with_base_url("wrong-token".to_string(), server.url());
+    match unauthorized.get_user().await {
+        Left(_) => panic!("Expected the token to be rejected."),
+        Right(error) => assert_eq!(error.error.id, "401")
+    }
+}
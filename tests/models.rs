@@ -0,0 +1,177 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use ynab_rs::models::*;
+
+fn parse<T: DeserializeOwned + Serialize>(fixture: &str) -> T {
+    let path = format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), fixture);
+    let contents = std::fs::read_to_string(&path).unwrap_or_else(|error| panic!("Could not read `{}`: {}", path, error));
+    let parsed: T = serde_json::from_str(&contents).unwrap_or_else(|error| panic!("Could not parse `{}`: {}", fixture, error));
+
+    let serialized: Value = serde_json::to_value(&parsed).unwrap();
+    let reparsed: T = serde_json::from_value(serialized.clone()).unwrap_or_else(|error| panic!("Could not reparse `{}`: {}", fixture, error));
+    assert_eq!(serde_json::to_value(&reparsed).unwrap(), serialized, "`{}` does not round-trip", fixture);
+
+    parsed
+}
+
+macro_rules! fixture_tests {
+    ($($name:ident: $model:ty => $fixture:literal,)*) => {
+        $(
+            #[test]
+            fn $name() {
+                parse::<$model>($fixture);
+            }
+        )*
+    };
+}
+
+fixture_tests! {
+    user_response: UserResponse => "user.json",
+    unauthorized_error: ErrorResponse => "error_unauthorized.json",
+    resource_not_found_error: ErrorResponse => "error_resource_not_found.json",
+    rate_limited_error: ErrorResponse => "error_rate_limited.json",
+    budget_summary_response: BudgetSummaryResponse => "budget_summaries.json",
+    budget_detail_response: BudgetDetailResponse => "budget_detail.json",
+    budget_settings_response: BudgetSettingsResponse => "budget_settings.json",
+    accounts_response: AccountsResponse => "accounts.json",
+    account_response: AccountResponse => "account.json",
+    categories_response: CategoriesResponse => "categories.json",
+    category_response: CategoryResponse => "category.json",
+    save_category_response: SaveCategoryResponse => "save_category.json",
+    payees_response: PayeesResponse => "payees.json",
+    payee_response: PayeeResponse => "payee.json",
+    payee_locations_response: PayeeLocationsResponse => "payee_locations.json",
+    payee_location_response: PayeeLocationResponse => "payee_location.json",
+    transactions_response: TransactionsResponse => "transactions.json",
+    transaction_response: TransactionResponse => "transaction.json",
+    hybrid_transactions_response: HybridTransactionsResponse => "hybrid_transactions.json",
+    save_transactions_response: SaveTransactionsResponse => "save_transactions.json",
+    transactions_import_response: TransactionsImportResponse => "transactions_import.json",
+    bulk_response: BulkResponse => "bulk.json",
+    scheduled_transactions_response: ScheduledTransactionsResponse => "scheduled_transactions.json",
+    scheduled_transaction_response: ScheduledTransactionResponse => "scheduled_transaction.json",
+    month_summaries_response: MonthSummariesResponse => "month_summaries.json",
+    month_detail_response: MonthDetailResponse => "month_detail.json",
+}
+
+#[test]
+fn loan_account_periodic_values() {
+    let response: AccountResponse = parse("account.json");
+    let account = response.data.account;
+
+    assert!(matches!(account.r#type, AccountType::Mortgage));
+    assert_eq!(account.debt_original_balance, Some(-300000000));
+    assert_eq!(account.debt_interest_rates.unwrap().map.get("2023-01-01"), Some(&3875));
+    assert_eq!(account.debt_escrow_amounts.unwrap().map.len(), 2);
+}
+
+#[test]
+fn missing_loan_fields_and_deleted_accounts() {
+    let response: AccountsResponse = parse("accounts.json");
+    let closed = response.data.accounts.iter().find(|account| account.id == "account-old-card").unwrap();
+
+    assert!(closed.closed && closed.deleted);
+    assert!(closed.debt_interest_rates.is_none());
+    assert!(closed.last_reconciled_at.is_none());
+}
+
+#[test]
+fn null_goal_fields() {
+    let response: CategoriesResponse = parse("categories.json");
+    let hidden_group = response.data.category_groups.iter().find(|group| group.id == "group-hidden").unwrap();
+    let gym = &hidden_group.categories[0];
+
+    assert!(gym.hidden);
+    assert_eq!(gym.original_category_group_id.as_deref(), Some("group-everyday"));
+    assert!(gym.goal_type.is_none());
+    assert!(gym.goal_percentage_complete.is_none());
+    assert_eq!(gym.goal_target, Some(0));
+
+    let everyday = response.data.category_groups.iter().find(|group| group.id == "group-everyday").unwrap();
+    assert!(everyday.categories.iter().any(|category| category.deleted));
+}
+
+#[test]
+fn split_and_deleted_transactions() {
+    let response: TransactionsResponse = parse("transactions.json");
+    let transactions = response.data.transactions;
+
+    let split = transactions.iter().find(|transaction| transaction.id == "transaction-grocer").unwrap();
+    assert_eq!(split.subtransactions.iter().map(|subtransaction| subtransaction.amount).sum::<i64>(), split.amount);
+    assert!(split.category_id.is_none());
+
+    let deleted = transactions.iter().find(|transaction| transaction.id == "transaction-deleted").unwrap();
+    assert!(deleted.deleted);
+    assert!(matches!(deleted.flag_color, Some(FlagColor::Purple)));
+}
+
+#[test]
+fn hybrid_subtransaction_rows() {
+    let response: HybridTransactionsResponse = parse("hybrid_transactions.json");
+    let subtransactions: Vec<_> = response.data.transactions
+        .iter()
+        .filter(|transaction| matches!(transaction.r#type, TransactionType::SubTransaction))
+        .collect();
+
+    assert!(response.data.server_knowledge.is_none());
+    assert_eq!(subtransactions.len(), 2);
+    assert!(subtransactions.iter().all(|transaction| transaction.parent_transaction_id.as_deref() == Some("transaction-grocer")));
+}
+
+#[test]
+fn scheduled_splits_and_frequencies() {
+    let response: ScheduledTransactionsResponse = parse("scheduled_transactions.json");
+    let scheduled = response.data.scheduled_transactions;
+
+    assert!(matches!(scheduled[1].frequency, ScheduleFrequency::EveryOtherMonth));
+    assert_eq!(scheduled[1].subtransactions.len(), 2);
+    assert!(matches!(scheduled[2].frequency, ScheduleFrequency::Every4Weeks));
+    assert!(scheduled[2].deleted);
+}
+
+#[test]
+fn duplicate_import_ids() {
+    let response: SaveTransactionsResponse = parse("save_transactions.json");
+
+    assert!(response.data.transaction.is_none());
+    assert_eq!(response.data.duplicate_import_ids.unwrap(), vec!["YNAB:2500000:2023-06-01:1"]);
+}
+
+#[test]
+fn debt_transaction_types() {
+    let response: TransactionsResponse = parse("transactions.json");
+    let adjustment = response.data.transactions.iter().find(|transaction| transaction.id == "transaction-mortgage-adjustment").unwrap();
+
+    assert!(matches!(adjustment.debt_transaction_type, Some(DebtTransactionType::BalancedAdjustment)));
+}
+
+#[test]
+fn patch_omits_unset_fields() {
+    let save = SaveTransactionWithId { id: Some("transaction-grocer".to_string()), category_id: Some("category-groceries".to_string()), ..SaveTransactionWithId::default() };
+    let body: Value = serde_json::to_value(PatchTransactionsWrapper { transactions: vec![save] }).unwrap();
+
+    assert_eq!(body, serde_json::json!({ "transactions": [{ "id": "transaction-grocer", "category_id": "category-groceries" }] }));
+}
+
+#[test]
+fn save_omits_unset_fields() {
+    let save = SaveTransaction {
+        account_id:         "account-checking".to_string(),
+        date:               "2023-06-12".to_string(),
+        amount:             -4500,
+        payee_id:           None,
+        payee_name:         None,
+        category_id:        None,
+        memo:               Some("Coffee".to_string()),
+        cleared:            None,
+        approved:           None,
+        flag_color:         None,
+        import_id:          None,
+        subtransactions:    Some(vec![SaveSubTransaction { amount: -4500, payee_id: None, payee_name: None, category_id: None, memo: None }])
+    };
+    let body: Value = serde_json::to_value(&save).unwrap();
+
+    assert_eq!(body, serde_json::json!({ "account_id": "account-checking", "date": "2023-06-12", "amount": -4500, "memo": "Coffee", "subtransactions": [{ "amount": -4500 }] }));
+    assert_eq!(serde_json::from_value::<SaveTransaction>(body).unwrap().payee_id, None);
+}
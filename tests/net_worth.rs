@@ -0,0 +1,89 @@
+mod common;
+
+use common::budget;
+use ynab_rs::mock::MockServer;
+use ynab_rs::models::{Account, AccountType, BudgetDetail, TransactionSummary};
+use ynab_rs::reports::net_worth::{fetch_net_worth, AccountSeries, Interval, NetWorthPoint, NetWorthSeries};
+use ynab_rs::reports::ReportError;
+
+fn point(date: &str, assets: i64, liabilities: i64) -> NetWorthPoint {
+    NetWorthPoint { date: date.to_string(), assets, liabilities, net_worth: assets - liabilities }
+}
+
+// A settled and closed card with one purchase and its payment, and a deleted account.
+fn with_card(mut budget: BudgetDetail) -> BudgetDetail {
+    let mut card: Account = budget.accounts[0].clone();
+    card.id = "account-card".to_string();
+    card.name = "Card".to_string();
+    card.r#type = AccountType::CreditCard;
+    card.balance = 0;
+    card.closed = true;
+
+    let mut deleted: Account = budget.accounts[1].clone();
+    deleted.id = "account-deleted".to_string();
+    deleted.deleted = true;
+    budget.accounts.extend([card, deleted]);
+
+    for (id, date, amount) in [("transaction-card-purchase", "2023-06-02", -50000), ("transaction-card-payment", "2023-06-08", 50000)] {
+        let mut transaction: TransactionSummary = budget.transactions[1].clone();
+        transaction.id = id.to_string();
+        transaction.date = date.to_string();
+        transaction.account_id = "account-card".to_string();
+        transaction.amount = amount;
+        budget.transactions.push(transaction);
+    }
+    budget
+}
+
+#[test]
+fn walks_balances_back_day_by_day() {
+    let series: NetWorthSeries = NetWorthSeries::from_budget(&budget(), Interval::Daily, "2023-05-31", "2023-06-10").unwrap();
+
+    assert_eq!(series.points.len(), 11);
+    assert_eq!(series.points[0], point("2023-05-31", 0, 0));
+    assert_eq!(series.points[1], point("2023-06-01", 2500000, 0));
+    assert_eq!(series.points[3], point("2023-06-03", 1600000, 0));
+    assert_eq!(series.points[10], point("2023-06-10", 1515000, 0));
+    assert_eq!(series.account("account-checking").unwrap().balances[9..], [1515000, 1315000]);
+    assert_eq!(series.account("account-savings").unwrap().balances[9..], [0, 200000]);
+}
+
+#[test]
+fn ends_months_on_their_last_day() {
+    let series: NetWorthSeries = NetWorthSeries::from_budget(&budget(), Interval::Monthly, "2023-04-15", "2023-06-20").unwrap();
+
+    assert_eq!(series.points, vec![point("2023-04-30", 0, 0), point("2023-05-31", 0, 0), point("2023-06-20", 1515000, 0)]);
+    assert!(matches!(
+        NetWorthSeries::from_budget(&budget(), Interval::Monthly, "2023-06-20", "2023-06-01"),
+        Err(ReportError::EmptyRange { .. })
+    ));
+    assert!(matches!(NetWorthSeries::from_budget(&budget(), Interval::Daily, "2023-06", "2023-06-01"), Err(ReportError::InvalidDate(_))));
+}
+
+#[test]
+fn counts_debt_as_liabilities_until_a_closed_account_is_settled() {
+    let series: NetWorthSeries = NetWorthSeries::from_budget(&with_card(budget()), Interval::Daily, "2023-06-01", "2023-06-08").unwrap();
+
+    assert!(series.account("account-deleted").is_none());
+    let card: &AccountSeries = series.account("account-card").unwrap();
+    assert!(card.liability && card.closed);
+    assert_eq!(card.balances, vec![0, -50000, -50000, -50000, -50000, -50000, -50000, 0]);
+
+    assert_eq!(series.points[1], point("2023-06-02", 2500000, 50000));
+    assert_eq!(series.points[4], point("2023-06-05", 1515000, 50000));
+    assert_eq!(series.points[7], point("2023-06-08", 1515000, 0));
+}
+
+#[tokio::test]
+async fn fetches_transactions_since_the_first_day() {
+    let server = MockServer::start();
+    server.seed_budget(with_card(budget()), 1);
+    let client = server.client();
+
+    let fetched: NetWorthSeries = fetch_net_worth(&client, "budget-household", Interval::Daily, "2023-06-04", "2023-06-10").await.unwrap();
+    let local: NetWorthSeries = NetWorthSeries::from_budget(&with_card(budget()), Interval::Daily, "2023-06-04", "2023-06-10").unwrap();
+    assert_eq!(fetched.points, local.points);
+
+    let error: ReportError = fetch_net_worth(&client, "budget-missing", Interval::Daily, "2023-06-04", "2023-06-10").await.unwrap_err();
+    assert!(matches!(error, ReportError::Request { endpoint: "get_account_list", .. }));
+}
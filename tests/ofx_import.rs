@@ -0,0 +1,143 @@
+mod common;
+
+use common::{date, seeded_server};
+use ynab_rs::import::ofx::{fitid_import_id, import_ofx, parse_ofx, OfxImportError};
+use ynab_rs::import::ImportOutcome;
+
+const SGML_STATEMENT: &str = "OFXHEADER:100\r\n\
+DATA:OFXSGML\r\n\
+VERSION:102\r\n\
+CHARSET:1252\r\n\
+\r\n\
+<OFX>\r\n\
+<SIGNONMSGSRSV1><SONRS><STATUS><CODE>0<SEVERITY>INFO</STATUS><DTSERVER>20230616120000</SONRS></SIGNONMSGSRSV1>\r\n\
+<BANKMSGSRSV1><STMTTRNRS><TRNUID>1<STMTRS>\r\n\
+<CURDEF>USD\r\n\
+<BANKACCTFROM><BANKID>121000248<ACCTID>1234567890<ACCTTYPE>CHECKING</BANKACCTFROM>\r\n\
+<BANKTRANLIST><DTSTART>20230601<DTEND>20230615\r\n\
+<STMTTRN><TRNTYPE>DEBIT<DTPOSTED>20230612120000.000[-5:EST]<TRNAMT>-4.50<FITID>20230612-001<NAME>BAKERY &amp; CAFE<MEMO>POS PURCHASE</STMTTRN>\r\n\
+<STMTTRN><TRNTYPE>CHECK<DTPOSTED>20230613<TRNAMT>-120.00<FITID>20230613-001<CHECKNUM>1042<PAYEE><NAME>Landlord<CITY>Springfield</PAYEE></STMTTRN>\r\n\
+<STMTTRN><TRNTYPE>CREDIT<DTPOSTED>20230615<TRNAMT>1234,56<FITID>2023061500000000000000000000000000000001<NAME>EMPLOYER<MEMO></STMTTRN>\r\n\
+</BANKTRANLIST>\r\n\
+<LEDGERBAL><BALAMT>1400.00<DTASOF>20230615</LEDGERBAL>\r\n\
+</STMTRS></STMTTRNRS></BANKMSGSRSV1>\r\n\
+</OFX>\r\n";
+
+const XML_STATEMENT: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="no"?>
+<?OFX OFXHEADER="200" VERSION="220" SECURITY="NONE" OLDFILEUID="NONE" NEWFILEUID="NONE"?>
+<OFX>
+  <CREDITCARDMSGSRSV1>
+    <CCSTMTTRNRS>
+      <TRNUID>1</TRNUID>
+      <CCSTMTRS>
+        <CURDEF>EUR</CURDEF>
+        <CCACCTFROM><ACCTID>4111111111111111</ACCTID></CCACCTFROM>
+        <BANKTRANLIST>
+          <STMTTRN>
+            <TRNTYPE>DEBIT</TRNTYPE>
+            <DTPOSTED>20230612</DTPOSTED>
+            <TRNAMT>-12.50</TRNAMT>
+            <FITID>cc-1</FITID>
+            <NAME>Caf&#233; Central</NAME>
+          </STMTTRN>
+        </BANKTRANLIST>
+        <LEDGERBAL><BALAMT>-12.50</BALAMT><DTASOF>20230615</DTASOF></LEDGERBAL>
+      </CCSTMTRS>
+    </CCSTMTTRNRS>
+  </CREDITCARDMSGSRSV1>
+</OFX>
+"#;
+
+#[test]
+fn parses_sgml_statements() {
+    let statements = parse_ofx(SGML_STATEMENT.as_bytes()).unwrap();
+    assert_eq!(statements.len(), 1);
+
+    let statement = &statements[0];
+    assert_eq!(statement.account_id.as_deref(), Some("1234567890"));
+    assert_eq!(statement.currency.as_deref(), Some("USD"));
+    assert_eq!(statement.ledger_balance.as_ref().map(|balance| (balance.amount, balance.as_of)), Some((1400000, date(2023, 6, 15))));
+
+    let transactions = &statement.transactions;
+    assert_eq!(transactions.len(), 3);
+    assert_eq!(transactions[0].posted, date(2023, 6, 12));
+    assert_eq!(transactions[0].amount, -4500);
+    assert_eq!(transactions[0].name.as_deref(), Some("BAKERY & CAFE"));
+    assert_eq!(transactions[0].memo.as_deref(), Some("POS PURCHASE"));
+    assert_eq!(transactions[1].name.as_deref(), Some("Landlord"));
+    assert_eq!(transactions[1].check_number.as_deref(), Some("1042"));
+    assert_eq!(transactions[2].amount, 1234560);
+    assert_eq!(transactions[2].memo, None);
+}
+
+#[test]
+fn keeps_siblings_of_empty_sgml_leaves() {
+    let statement = "<OFX><BANKMSGSRSV1><STMTTRNRS><STMTRS><CURDEF>USD<BANKTRANLIST>\r\n\
+<STMTTRN><TRNTYPE>DEBIT<DTPOSTED>20230612<MEMO><NAME><TRNAMT>-4.50<FITID>20230612-001</STMTTRN>\r\n\
+<STMTTRN><TRNTYPE>DEBIT<DTPOSTED>20230613<TRNAMT>-7.00<FITID>20230613-001<MEMO></STMTTRN>\r\n\
+</BANKTRANLIST><LEDGERBAL><BALAMT>-11.50<DTASOF>20230613</LEDGERBAL></STMTRS></STMTTRNRS></BANKMSGSRSV1></OFX>";
+    let statement = parse_ofx(statement.as_bytes()).unwrap().remove(0);
+
+    assert_eq!(statement.transactions.len(), 2);
+    assert_eq!((statement.transactions[0].amount, statement.transactions[0].fitid.as_str()), (-4500, "20230612-001"));
+    assert_eq!((statement.transactions[0].name.as_deref(), statement.transactions[0].memo.as_deref()), (None, None));
+    assert_eq!(statement.transactions[1].amount, -7000);
+    assert_eq!(statement.ledger_balance.unwrap().amount, -11500);
+}
+
+#[test]
+fn parses_xml_statements() {
+    let statements = parse_ofx(XML_STATEMENT.as_bytes()).unwrap();
+    assert_eq!(statements.len(), 1);
+    assert_eq!(statements[0].account_id.as_deref(), Some("4111111111111111"));
+    assert_eq!(statements[0].currency.as_deref(), Some("EUR"));
+    assert_eq!(statements[0].transactions[0].name.as_deref(), Some("Café Central"));
+    assert_eq!(statements[0].transactions[0].amount, -12500);
+    assert_eq!(statements[0].ledger_balance.as_ref().unwrap().amount, -12500);
+}
+
+#[test]
+fn maps_fitids_to_import_ids() {
+    let statement = parse_ofx(SGML_STATEMENT.as_bytes()).unwrap().remove(0);
+    let transactions = statement.to_save_transactions("account-checking");
+
+    assert_eq!(transactions[0].import_id.as_deref(), Some("20230612-001"));
+    assert_eq!(transactions[0].payee_name.as_deref(), Some("BAKERY & CAFE"));
+    assert_eq!(transactions[0].date, "2023-06-12");
+
+    let long_fitid = "2023061500000000000000000000000000000001";
+    assert_eq!(transactions[2].import_id, Some(fitid_import_id(long_fitid)));
+    assert!(fitid_import_id(long_fitid).len() <= 36);
+    assert_ne!(fitid_import_id(long_fitid), fitid_import_id("2023061500000000000000000000000000000002"));
+}
+
+#[test]
+fn rejects_malformed_input() {
+    assert!(matches!(parse_ofx("date,amount\n".as_bytes()), Err(OfxImportError::NotOfx)));
+
+    let missing_fitid = "<OFX><STMTRS><STMTTRN><DTPOSTED>20230612<TRNAMT>-1.00</STMTTRN></STMTRS></OFX>";
+    assert!(matches!(parse_ofx(missing_fitid.as_bytes()), Err(OfxImportError::MissingField { field: "FITID", .. })));
+
+    let bad_date = "<OFX><STMTRS><STMTTRN><DTPOSTED>June 12<TRNAMT>-1.00<FITID>1</STMTTRN></STMTRS></OFX>";
+    assert!(matches!(parse_ofx(bad_date.as_bytes()), Err(OfxImportError::InvalidDate { field: "DTPOSTED", .. })));
+}
+
+#[tokio::test]
+async fn imports_and_reconciles_statement() {
+    let server = seeded_server();
+    let client = server.client();
+    let statement = parse_ofx(SGML_STATEMENT.as_bytes()).unwrap().remove(0);
+
+    let outcomes = import_ofx(&client, "budget-household", "account-checking", &statement).await;
+    assert!(outcomes.iter().all(|outcome| matches!(outcome, ImportOutcome::Created(_))));
+
+    let outcomes = import_ofx(&client, "budget-household", "account-checking", &statement).await;
+    assert!(outcomes.iter().all(|outcome| matches!(outcome, ImportOutcome::Duplicate { .. })));
+
+    let budget = server.budget("budget-household").unwrap();
+    let account = budget.accounts.iter().find(|account| account.id == "account-checking").unwrap();
+    let reconciliation = statement.reconcile(account).unwrap();
+    assert_eq!(reconciliation.statement_balance, 1400000);
+    assert_eq!(reconciliation.cleared_balance, account.cleared_balance);
+    assert_eq!(reconciliation.difference(), 1400000 - account.cleared_balance);
+}
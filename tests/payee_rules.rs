@@ -0,0 +1,147 @@
+mod common;
+
+use chrono::NaiveDate;
+use common::seeded_server;
+use ynab_rs::builder::SaveTransactionBuilder;
+use ynab_rs::import::payee_rules::{suggest_rules, PayeeRuleError, PayeeRules, PayeeTarget, RuleSuggestion};
+use ynab_rs::import::{BatchImporter, ImportOutcome};
+use ynab_rs::models::{Payee, SaveTransaction};
+
+const RULES: &str = r#"
+[[rule]]
+name = "Amazon"
+regex = "^AMZN (MKTP|DIGITAL)"
+payee_name = "Amazon"
+
+[[rule]]
+name = "Card purchases"
+regex = "^SQ \\*(?P<merchant>[A-Z ]+?)\\s+\\d"
+payee_name = "$merchant"
+
+[[rule]]
+contains = "grocer"
+payee_id = "payee-grocer"
+"#;
+
+fn transaction(payee_name: &str) -> SaveTransaction {
+    SaveTransactionBuilder::new("account-checking", NaiveDate::from_ymd_opt(2023, 6, 12).unwrap(), -4500)
+        .payee_name(payee_name)
+        .build()
+        .unwrap()
+}
+
+fn statement() -> Vec<SaveTransaction> {
+    vec![
+        transaction("AMZN MKTP US*2K3 SEATTLE"),
+        transaction("SQ *BLUE BOTTLE 4412"),
+        transaction("CITY GROCER #112"),
+        transaction("ACME EMPLOYER PAYROLL"),
+        SaveTransactionBuilder::new("account-checking", NaiveDate::from_ymd_opt(2023, 6, 13).unwrap(), -1000).payee_id("payee-landlord").build().unwrap()
+    ]
+}
+
+#[test]
+fn rewrites_matching_rows() {
+    let rules = PayeeRules::from_toml(RULES).unwrap();
+    let mut transactions = statement();
+    let report = rules.apply(&mut transactions);
+
+    assert_eq!(transactions[0].payee_name.as_deref(), Some("Amazon"));
+    assert_eq!(transactions[1].payee_name.as_deref(), Some("BLUE BOTTLE"));
+    assert_eq!((transactions[2].payee_id.as_deref(), transactions[2].payee_name.as_deref()), (Some("payee-grocer"), None));
+    assert_eq!(transactions[3].payee_name.as_deref(), Some("ACME EMPLOYER PAYROLL"));
+    assert_eq!(transactions[4].payee_id.as_deref(), Some("payee-landlord"));
+
+    assert_eq!(report.rows.len(), 4);
+    assert_eq!(report.matched().map(|row| row.rule.unwrap()).collect::<Vec<usize>>(), vec![0, 1, 2]);
+    assert_eq!(report.unmatched().map(|row| row.row).collect::<Vec<usize>>(), vec![3]);
+}
+
+#[test]
+fn dry_run_leaves_rows_alone() {
+    let rules = PayeeRules::from_toml(RULES).unwrap();
+    let transactions = statement();
+    let report = rules.dry_run(&transactions);
+
+    assert_eq!(transactions[0].payee_name.as_deref(), Some("AMZN MKTP US*2K3 SEATTLE"));
+    assert_eq!(report.rows[0].rule_name.as_deref(), Some("Amazon"));
+    assert_eq!(report.rows[0].target, Some(PayeeTarget::PayeeName("Amazon".to_string())));
+    assert_eq!(report.to_string().lines().next(), Some("row 0: \"AMZN MKTP US*2K3 SEATTLE\" rule `Amazon` -> \"Amazon\""));
+    assert_eq!(report.to_string().lines().nth(2), Some("row 2: \"CITY GROCER #112\" rule 3 -> payee payee-grocer"));
+}
+
+#[test]
+fn rejects_ambiguous_rules() {
+    let both = "[[rule]]\ncontains = \"A\"\nregex = \"B\"\npayee_name = \"C\"\n";
+    assert!(matches!(PayeeRules::from_toml(both), Err(PayeeRuleError::InvalidRule { rule: 1, .. })));
+
+    let no_target = "[[rule]]\ncontains = \"A\"\n";
+    assert!(matches!(PayeeRules::from_toml(no_target), Err(PayeeRuleError::InvalidRule { rule: 1, .. })));
+
+    let bad_regex = "[[rule]]\nregex = \"(\"\npayee_name = \"C\"\n";
+    assert!(matches!(PayeeRules::from_toml(bad_regex), Err(PayeeRuleError::Regex { rule: 1, .. })));
+}
+
+#[test]
+fn suggests_rules_from_existing_payees() {
+    let payees = vec![
+        Payee { id: "payee-employer".to_string(), name: "Employer".to_string(), transfer_account_id: None, deleted: false },
+        Payee { id: "payee-blue-bottle".to_string(), name: "Blue Bottle".to_string(), transfer_account_id: None, deleted: false },
+        Payee { id: "payee-old".to_string(), name: "Acme".to_string(), transfer_account_id: None, deleted: true }
+    ];
+    let rules = PayeeRules::new();
+    let report = rules.dry_run(&statement());
+    let suggestions = suggest_rules(&report, &payees);
+
+    assert_eq!(suggestions.len(), 2);
+    assert_eq!((suggestions[0].pattern.as_str(), suggestions[0].payee_id.as_str()), ("BLUE BOTTLE", "payee-blue-bottle"));
+    assert_eq!((suggestions[1].pattern.as_str(), suggestions[1].payee_id.as_str()), ("EMPLOYER", "payee-employer"));
+    assert_eq!(suggestions[1].examples, vec!["ACME EMPLOYER PAYROLL".to_string()]);
+
+    let rules = PayeeRules::from_toml(&suggestions[1].to_toml()).unwrap();
+    assert_eq!(rules.rewrite("ACME EMPLOYER PAYROLL"), Some((0, PayeeTarget::PayeeId("payee-employer".to_string()))));
+}
+
+#[test]
+fn suggestions_with_odd_characters_read_back() {
+    let suggestion = RuleSuggestion {
+        pattern:    "O'HARA \"CAFE\"\u{1b}\t\\".to_string(),
+        payee_id:   "payee-cafe".to_string(),
+        payee_name: "O'Hara's\u{7f}".to_string(),
+        examples:   vec![]
+    };
+
+    let rules = PayeeRules::from_toml(&suggestion.to_toml()).unwrap();
+    assert_eq!(rules.rules[0].name.as_deref(), Some("O'Hara's\u{7f}"));
+    assert_eq!(rules.rewrite("POS o'hara \"cafe\"\u{1b}\t\\ 12"), Some((0, PayeeTarget::PayeeId("payee-cafe".to_string()))));
+}
+
+#[tokio::test]
+async fn batch_importer_applies_rules() {
+    let server = seeded_server();
+    let client = server.client();
+    let rules = PayeeRules::from_toml(RULES).unwrap();
+
+    let outcomes = BatchImporter::new(&client, "budget-household").payee_rules(&rules).import(statement()).await;
+    match &outcomes[2] {
+        ImportOutcome::Created(detail) => assert_eq!(detail.payee_id.as_deref(), Some("payee-grocer")),
+        other => panic!("Unexpected outcome: {:?}", other)
+    }
+    match &outcomes[0] {
+        ImportOutcome::Created(detail) => assert_eq!(detail.payee_name.as_deref(), Some("Amazon")),
+        other => panic!("Unexpected outcome: {:?}", other)
+    }
+}
+
+#[tokio::test]
+async fn batch_importer_truncates_rewritten_names() {
+    let server = seeded_server();
+    let client = server.client();
+    let rules = PayeeRules::new().regex("^POS (?P<merchant>.+)$", PayeeTarget::PayeeName("$merchant $merchant".to_string())).unwrap();
+
+    let outcomes = BatchImporter::new(&client, "budget-household").payee_rules(&rules).import(vec![transaction("POS NORTHERN HARDWARE AND GARDEN")]).await;
+    match &outcomes[0] {
+        ImportOutcome::Created(detail) => assert_eq!(detail.payee_name.as_deref(), Some("NORTHERN HARDWARE AND GARDEN NORTHERN HARDWARE AND")),
+        other => panic!("Unexpected outcome: {:?}", other)
+    }
+}
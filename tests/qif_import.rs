@@ -0,0 +1,128 @@
+mod common;
+
+use common::{date, seeded_server};
+use ynab_rs::import::qif::{import_qif, parse_date, read_transactions, DateOrder, QifImportError, QifOptions};
+use ynab_rs::import::ImportOutcome;
+use ynab_rs::models::ClearedStatus;
+
+const STATEMENT: &str = "!Type:Cat
+NFood:Groceries
+E
+^
+!Type:Bank
+D6/12'23
+T-45.00
+PBakery
+MCroissants
+LFood:Dining
+C*
+^
+D6/13'23
+T-120.00
+PGrocer
+SFood:Groceries
+EWeekly shop
+$-100.00
+SHousehold/Kitchen
+$-15.00
+E
+$-5.00
+^
+D6/15'23
+U1,234.56
+PEmployer
+L[Savings]
+CX
+^
+!Type:Invst
+D6/16'23
+NBuy
+T-500.00
+^
+!Type:CCard
+D6/17'23
+T-8.50
+PCafe
+^
+";
+
+fn options() -> QifOptions {
+    QifOptions::new()
+        .category("Food:Groceries", "category-groceries")
+        .category("Food:Dining", "category-dining")
+        .category("Household", "category-rent")
+}
+
+#[test]
+fn parses_common_date_formats() {
+    assert_eq!(parse_date("6/12/2023", DateOrder::MonthDayYear), Some(date(2023, 6, 12)));
+    assert_eq!(parse_date("6/12/23", DateOrder::MonthDayYear), Some(date(2023, 6, 12)));
+    assert_eq!(parse_date(" 6/12'23", DateOrder::MonthDayYear), Some(date(2023, 6, 12)));
+    assert_eq!(parse_date("6/12/98", DateOrder::MonthDayYear), Some(date(1998, 6, 12)));
+    assert_eq!(parse_date("12.06.2023", DateOrder::DayMonthYear), Some(date(2023, 6, 12)));
+    assert_eq!(parse_date("2023-06-12", DateOrder::MonthDayYear), Some(date(2023, 6, 12)));
+    assert_eq!(parse_date("23-06-12", DateOrder::YearMonthDay), Some(date(2023, 6, 12)));
+    assert_eq!(parse_date("13/12/2023", DateOrder::MonthDayYear), None);
+    assert_eq!(parse_date("June 12", DateOrder::MonthDayYear), None);
+}
+
+#[test]
+fn reads_supported_sections() {
+    let transactions = read_transactions(STATEMENT.as_bytes(), &options(), "account-checking").unwrap();
+    assert_eq!(transactions.iter().map(|transaction| transaction.amount).collect::<Vec<i64>>(), vec![-45000, -120000, 1234560, -8500]);
+
+    let bakery = &transactions[0];
+    assert_eq!(bakery.date, "2023-06-12");
+    assert_eq!(bakery.payee_name.as_deref(), Some("Bakery"));
+    assert_eq!(bakery.memo.as_deref(), Some("Croissants"));
+    assert_eq!(bakery.category_id.as_deref(), Some("category-dining"));
+    assert!(matches!(bakery.cleared, Some(ClearedStatus::Cleared)));
+    assert_eq!(bakery.import_id.as_deref(), Some("YNAB:-45000:2023-06-12:1"));
+
+    let transfer = &transactions[2];
+    assert_eq!(transfer.category_id, None);
+    assert!(matches!(transfer.cleared, Some(ClearedStatus::Reconciled)));
+    assert!(matches!(transactions[3].cleared, Some(ClearedStatus::Uncleared)));
+}
+
+#[test]
+fn maps_split_lines_to_subtransactions() {
+    let transactions = read_transactions(STATEMENT.as_bytes(), &options(), "account-checking").unwrap();
+    let grocer = &transactions[1];
+    assert_eq!(grocer.category_id, None);
+
+    let subtransactions = grocer.subtransactions.as_ref().unwrap();
+    assert_eq!(subtransactions.len(), 3);
+    assert_eq!(subtransactions[0].amount, -100000);
+    assert_eq!(subtransactions[0].category_id.as_deref(), Some("category-groceries"));
+    assert_eq!(subtransactions[0].memo.as_deref(), Some("Weekly shop"));
+    assert_eq!(subtransactions[1].category_id.as_deref(), Some("category-rent"));
+    assert_eq!(subtransactions[2].amount, -5000);
+    assert_eq!(subtransactions[2].category_id, None);
+    assert_eq!(subtransactions.iter().map(|subtransaction| subtransaction.amount).sum::<i64>(), grocer.amount);
+}
+
+#[test]
+fn reports_broken_records() {
+    let error = read_transactions("!Type:Bank\nD6/12/23\nPBakery\n^\n".as_bytes(), &options(), "account-checking").unwrap_err();
+    assert!(matches!(error, QifImportError::MissingAmount { line: 4 }));
+
+    let error = read_transactions("!Type:Bank\nDyesterday\nT-1.00\n^\n".as_bytes(), &options(), "account-checking").unwrap_err();
+    assert!(matches!(error, QifImportError::InvalidDate { line: 2, ref value } if value == "yesterday"));
+
+    let day_first = QifOptions::new().date_order(DateOrder::DayMonthYear).decimal_separator(',');
+    let transactions = read_transactions("!Type:Bank\nD12.06.2023\nT-1.234,50\n^\n".as_bytes(), &day_first, "account-checking").unwrap();
+    assert_eq!((transactions[0].date.as_str(), transactions[0].amount), ("2023-06-12", -1234500));
+}
+
+#[tokio::test]
+async fn imports_statement_with_splits() {
+    let server = seeded_server();
+    let client = server.client();
+
+    let outcomes = import_qif(&client, "budget-household", "account-checking", STATEMENT.as_bytes(), &options()).await.unwrap();
+    assert!(outcomes.iter().all(|outcome| matches!(outcome, ImportOutcome::Created(_))));
+
+    let outcomes = import_qif(&client, "budget-household", "account-checking", STATEMENT.as_bytes(), &options()).await.unwrap();
+    assert!(outcomes.iter().all(|outcome| matches!(outcome, ImportOutcome::Duplicate { .. })));
+}
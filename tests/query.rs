@@ -0,0 +1,45 @@
+mod common;
+
+use chrono::NaiveDate;
+use common::seeded_server;
+use ynab_rs::query::{TransactionFilter, TransactionQuery};
+
+#[tokio::test]
+async fn filters_by_scope_and_type() {
+    let server = seeded_server();
+    let client = server.client();
+
+    let query = TransactionQuery::new("budget-household")
+        .account("account-checking")
+        .since_date(NaiveDate::from_ymd_opt(2023, 6, 2).unwrap())
+        .filter(TransactionFilter::Unapproved);
+
+    let response = client.get_transactions(&query).await.left().unwrap();
+    assert_eq!(response.data.transactions.len(), 1);
+    assert_eq!(response.data.transactions[0].id, "transaction-grocer");
+}
+
+#[tokio::test]
+async fn month_and_payee_scopes() {
+    let server = seeded_server();
+    let client = server.client();
+
+    let month = TransactionQuery::new("budget-household").month(NaiveDate::from_ymd_opt(2023, 6, 15).unwrap());
+    assert_eq!(client.get_transactions(&month).await.left().unwrap().data.transactions.len(), 5);
+    assert_eq!(client.get_hybrid_transactions(&month).await.right().unwrap().error.id, "400");
+
+    let payee = TransactionQuery::new("budget-household").payee("payee-grocer").last_knowledge(0);
+    assert_eq!(client.get_hybrid_transactions(&payee).await.left().unwrap().data.transactions.len(), 1);
+    assert_eq!(client.get_transactions(&payee).await.right().unwrap().error.id, "400");
+}
+
+#[tokio::test]
+async fn uncategorized_filter_skips_transfers() {
+    let server = seeded_server();
+    let client = server.client();
+
+    let query = TransactionQuery::new("budget-household").filter(TransactionFilter::Uncategorized);
+    let response = client.get_transactions(&query).await.left().unwrap();
+    let ids: Vec<&str> = response.data.transactions.iter().map(|transaction| transaction.id.as_str()).collect();
+    assert_eq!(ids, vec!["transaction-grocer"]);
+}
@@ -0,0 +1,122 @@
+mod common;
+
+use common::{budget, seeded_server};
+use ynab_rs::category_tree::CategoryTree;
+use ynab_rs::models::{
+    BudgetDetail, CategoriesResponse, Category, CategoryGroupWithCategories, MonthDetail, MonthDetailResponse
+};
+use ynab_rs::reports::spending::{fetch_spending_report, Amounts, CategoryRow, SpendingReport};
+use ynab_rs::reports::{month_range, ReportError};
+
+fn category_groups() -> Vec<CategoryGroupWithCategories> {
+    let response: CategoriesResponse = serde_json::from_str(include_str!("fixtures/categories.json")).unwrap();
+    response.data.category_groups
+}
+
+fn set(month: &mut MonthDetail, category_id: &str, budgeted: i64, activity: i64, balance: i64) {
+    let category: &mut Category = month.categories.iter_mut().find(|category| category.id == category_id).unwrap();
+    category.budgeted = budgeted;
+    category.activity = activity;
+    category.balance = balance;
+}
+
+// June from the fixture and a May before it in which the now hidden gym category was still used.
+fn months() -> Vec<MonthDetail> {
+    let response: MonthDetailResponse = serde_json::from_str(include_str!("fixtures/month_detail.json")).unwrap();
+    let june: MonthDetail = response.data.month;
+
+    let mut may: MonthDetail = june.clone();
+    may.month = "2023-05-01".to_string();
+    set(&mut may, "category-groceries", 300000, -280000, 20000);
+    set(&mut may, "category-old-gym", 0, -15000, -15000);
+
+    vec![june, may]
+}
+
+fn amounts(budgeted: i64, activity: i64, balance: i64) -> Amounts {
+    Amounts { budgeted, activity, balance }
+}
+
+#[test]
+fn lists_months_with_totals_and_averages() {
+    let groups: Vec<CategoryGroupWithCategories> = category_groups();
+    let report: SpendingReport = SpendingReport::from_months(&CategoryTree::from_groups(&groups), &months());
+
+    assert_eq!(report.months, vec!["2023-05-01", "2023-06-01"]);
+    assert_eq!(report.groups.iter().map(|group| group.name.as_str()).collect::<Vec<&str>>(), vec!["Bills", "Everyday Expenses"]);
+
+    let groceries: &CategoryRow = report.category("category-groceries").unwrap();
+    assert_eq!(groceries.figures.months, vec![amounts(300000, -280000, 20000), amounts(400000, -60000, 340000)]);
+    assert_eq!(groceries.figures.total, amounts(700000, -340000, 340000));
+    assert_eq!(groceries.figures.average, amounts(350000, -170000, 180000));
+
+    assert_eq!(report.group("group-everyday").unwrap().figures.months[0], amounts(300000, -295000, 5000));
+    assert_eq!(report.figures.total, amounts(2500000, -2155000, 340000));
+    assert_eq!(report.figures.average, amounts(1250000, -1077500, 172500));
+}
+
+#[test]
+fn keeps_hidden_and_deleted_categories_only_when_used() {
+    let groups: Vec<CategoryGroupWithCategories> = category_groups();
+    let tree: CategoryTree = CategoryTree::from_groups(&groups);
+
+    let report: SpendingReport = SpendingReport::from_months(&tree, &months()[..1]);
+    assert!(report.category("category-old-gym").is_none());
+    assert!(report.category("category-vacation").is_some());
+
+    let mut months: Vec<MonthDetail> = months();
+    let mut deleted: Category = months[1].categories[1].clone();
+    deleted.id = "category-deleted".to_string();
+    deleted.name = "Deleted Category".to_string();
+    deleted.deleted = true;
+    months[1].categories.push(deleted);
+
+    let report: SpendingReport = SpendingReport::from_months(&tree, &months);
+    let gym: &CategoryRow = report.category("category-old-gym").unwrap();
+    assert!(gym.hidden);
+    assert_eq!(report.group("group-everyday").unwrap().categories.last().unwrap().category_id, "category-deleted");
+    assert_eq!(report.category("category-deleted").unwrap().figures.months[0], amounts(300000, -280000, 20000));
+}
+
+#[test]
+fn builds_from_a_budget_snapshot() {
+    let budget: BudgetDetail = budget();
+
+    let report: SpendingReport = SpendingReport::from_budget(&budget, "2023-06", "2023-06-30").unwrap();
+    assert_eq!(report.figures.total, amounts(1300000, -960000, 340000));
+    assert!(report.group("group-internal").is_none());
+
+    let error: ReportError = SpendingReport::from_budget(&budget, "2023-05", "2023-06").unwrap_err();
+    assert!(matches!(error, ReportError::MissingMonth(month) if month == "2023-05-01"));
+    assert_eq!(month_range("2022-11-15", "2023-02").unwrap(), vec!["2022-11-01", "2022-12-01", "2023-01-01", "2023-02-01"]);
+    assert!(matches!(month_range("2023-06", "2023-05"), Err(ReportError::EmptyRange { .. })));
+    assert!(matches!(month_range("June", "2023-05"), Err(ReportError::InvalidMonth(_))));
+}
+
+#[test]
+fn writes_one_csv_line_per_group_and_category() {
+    let budget: BudgetDetail = budget();
+    let report: SpendingReport = SpendingReport::from_budget(&budget, "2023-06", "2023-06").unwrap();
+    let csv: String = String::from_utf8(report.write_csv(vec![], &budget.currency_format).unwrap()).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+
+    assert_eq!(
+        lines[0],
+        "group,category,2023-06 budgeted,2023-06 activity,2023-06 balance,total budgeted,total activity,total balance,average budgeted,average activity,average balance"
+    );
+    assert_eq!(lines[1], "Bills,,900.00,-900.00,0.00,900.00,-900.00,0.00,900.00,-900.00,0.00");
+    assert_eq!(lines[5], "Everyday Expenses,Dining Out,0.00,0.00,0.00,0.00,0.00,0.00,0.00,0.00,0.00");
+    assert_eq!(lines.last().copied(), Some("Total,,1300.00,-960.00,340.00,1300.00,-960.00,340.00,1300.00,-960.00,340.00"));
+}
+
+#[tokio::test]
+async fn fetches_months_from_the_api() {
+    let server = seeded_server();
+    let client = server.client();
+
+    let report: SpendingReport = fetch_spending_report(&client, "budget-household", "2023-06", "2023-06").await.unwrap();
+    assert_eq!(report, SpendingReport::from_budget(&budget(), "2023-06", "2023-06").unwrap());
+
+    let error: ReportError = fetch_spending_report(&client, "budget-household", "2023-05", "2023-06").await.unwrap_err();
+    assert!(matches!(error, ReportError::Request { endpoint: "get_month", .. }));
+}
@@ -0,0 +1,188 @@
+mod common;
+
+use common::{date, seeded_server};
+use ynab_rs::import::camt::{parse_camt053, CamtImportError};
+use ynab_rs::import::mt940::{parse_mt940, Mt940ImportError};
+use ynab_rs::import::statement::{import_statement, EntryStatus};
+use ynab_rs::import::ImportOutcome;
+use ynab_rs::models::ClearedStatus;
+
+const CAMT_053: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Document xmlns="urn:iso:std:iso:20022:tech:xsd:camt.053.001.08">
+  <BkToCstmrStmt>
+    <GrpHdr><MsgId>MSG-2023-06</MsgId><CreDtTm>2023-06-16T06:00:00+02:00</CreDtTm></GrpHdr>
+    <Stmt>
+      <Id>STMT-2023-06</Id>
+      <Acct><Id><IBAN>DE89370400440532013000</IBAN></Id><Ccy>EUR</Ccy></Acct>
+      <Bal><Tp><CdOrPrtry><Cd>CLBD</Cd></CdOrPrtry></Tp><Amt Ccy="EUR">1400.00</Amt><CdtDbtInd>CRDT</CdtDbtInd><Dt><Dt>2023-06-15</Dt></Dt></Bal>
+      <Ntry>
+        <NtryRef>1</NtryRef>
+        <Amt Ccy="EUR">4.50</Amt>
+        <CdtDbtInd>DBIT</CdtDbtInd>
+        <Sts><Cd>BOOK</Cd></Sts>
+        <BookgDt><Dt>2023-06-12</Dt></BookgDt>
+        <ValDt><Dt>2023-06-12</Dt></ValDt>
+        <AcctSvcrRef>2023061200001</AcctSvcrRef>
+        <NtryDtls><TxDtls>
+          <RltdPties>
+            <Dbtr><Pty><Nm>Max Mustermann</Nm></Pty></Dbtr>
+            <Cdtr><Pty><Nm>B&#228;ckerei M&#252;ller</Nm></Pty></Cdtr>
+          </RltdPties>
+          <RmtInf><Ustrd>Broetchen</Ustrd><Ustrd>Kartenzahlung</Ustrd></RmtInf>
+        </TxDtls></NtryDtls>
+      </Ntry>
+      <Ntry>
+        <Amt Ccy="EUR">1234.56</Amt>
+        <CdtDbtInd>CRDT</CdtDbtInd>
+        <Sts><Cd>PDNG</Cd></Sts>
+        <ValDt><DtTm>2023-06-15T09:30:00+02:00</DtTm></ValDt>
+        <AcctSvcrRef>2023061500007</AcctSvcrRef>
+        <NtryDtls><TxDtls>
+          <RltdPties><Dbtr><Nm>Arbeitgeber GmbH</Nm></Dbtr></RltdPties>
+        </TxDtls></NtryDtls>
+        <AddtlNtryInf>Gehalt Juni</AddtlNtryInf>
+      </Ntry>
+    </Stmt>
+  </BkToCstmrStmt>
+</Document>
+"#;
+
+const MT940: &str = "{1:F01BANKDEFFAXXX0000000000}{2:O9401200230616BANKDEFFAXXX00000000002306161200N}{4:
+:20:STARTUMS
+:25:37040044/0532013000
+:28C:00001/001
+:60F:C230601EUR1234,56
+:61:2306120612DR4,50NMSCNONREF//2023061200001
+:86:106?00KARTENZAHLUNG?20SVWZ+Broetchen Filiale?21 12?32BAECKEREI MUELLER
+:61:2312290102CR100,00NTRFNONREF
+:86:Neujahrsgeld
+:61:2306150615RC20,00NMSCREF-42
+:62F:C230615EUR1310,06
+-}
+";
+
+const MT942: &str = ":20:INTERIM
+:25:37040044/0532013000
+:34F:EURD0,
+:61:230616D8,50NMSCNONREF//P-77
+:86:Cafe Central
+-
+";
+
+#[test]
+fn parses_camt_053_entries() {
+    let statements = parse_camt053(CAMT_053.as_bytes()).unwrap();
+    assert_eq!(statements.len(), 1);
+
+    let statement = &statements[0];
+    assert_eq!(statement.id.as_deref(), Some("STMT-2023-06"));
+    assert_eq!(statement.account.as_deref(), Some("DE89370400440532013000"));
+    assert_eq!(statement.currency.as_deref(), Some("EUR"));
+
+    let bakery = &statement.entries[0];
+    assert_eq!(bakery.amount, -4500);
+    assert_eq!(bakery.reference.as_deref(), Some("2023061200001"));
+    assert_eq!(bakery.status, EntryStatus::Booked);
+    assert_eq!(bakery.booking_date, date(2023, 6, 12));
+    assert_eq!(bakery.counterparty.as_deref(), Some("Bäckerei Müller"));
+    assert_eq!(bakery.remittance.as_deref(), Some("Broetchen Kartenzahlung"));
+
+    let salary = &statement.entries[1];
+    assert_eq!(salary.amount, 1234560);
+    assert_eq!(salary.status, EntryStatus::Pending);
+    assert_eq!(salary.booking_date, date(2023, 6, 15));
+    assert_eq!(salary.counterparty.as_deref(), Some("Arbeitgeber GmbH"));
+    assert_eq!(salary.remittance.as_deref(), Some("Gehalt Juni"));
+}
+
+#[test]
+fn maps_entries_to_transactions() {
+    let statement = parse_camt053(CAMT_053.as_bytes()).unwrap().remove(0);
+    let transactions = statement.to_save_transactions("account-checking");
+
+    assert_eq!(transactions[0].import_id.as_deref(), Some("2023061200001"));
+    assert_eq!(transactions[0].payee_name.as_deref(), Some("Bäckerei Müller"));
+    assert_eq!(transactions[0].memo.as_deref(), Some("Broetchen Kartenzahlung"));
+    assert!(matches!(transactions[0].cleared, Some(ClearedStatus::Cleared)));
+    assert!(matches!(transactions[1].cleared, Some(ClearedStatus::Uncleared)));
+}
+
+#[test]
+fn rejects_other_xml() {
+    assert!(matches!(parse_camt053("<Document><pain.001/></Document>".as_bytes()), Err(CamtImportError::NotCamt)));
+
+    let missing_indicator = "<Document><BkToCstmrStmt><Stmt><Ntry><Amt>1.00</Amt><BookgDt><Dt>2023-06-12</Dt></BookgDt></Ntry></Stmt></BkToCstmrStmt></Document>";
+    assert!(matches!(parse_camt053(missing_indicator.as_bytes()), Err(CamtImportError::MissingField { field: "CdtDbtInd" })));
+}
+
+#[test]
+fn reads_camt_that_is_not_utf8() {
+    let latin1: Vec<u8> = CAMT_053.replace("B&#228;ckerei", "B\u{e4}ckerei").chars().map(|character| character as u8).collect();
+    let statement = parse_camt053(latin1.as_slice()).unwrap().remove(0);
+    assert_eq!(statement.entries[0].counterparty.as_deref(), Some("B\u{fffd}ckerei M\u{fc}ller"));
+}
+
+#[test]
+fn parses_mt940_statements() {
+    let statements = parse_mt940(MT940.as_bytes()).unwrap();
+    assert_eq!(statements.len(), 1);
+
+    let statement = &statements[0];
+    assert_eq!(statement.id.as_deref(), Some("STARTUMS"));
+    assert_eq!(statement.account.as_deref(), Some("37040044/0532013000"));
+    assert_eq!(statement.currency.as_deref(), Some("EUR"));
+    assert_eq!(statement.entries.iter().map(|entry| entry.amount).collect::<Vec<i64>>(), vec![-4500, 100000, -20000]);
+
+    let bakery = &statement.entries[0];
+    assert_eq!(bakery.reference.as_deref(), Some("2023061200001"));
+    assert_eq!(bakery.counterparty.as_deref(), Some("BAECKEREI MUELLER"));
+    assert_eq!(bakery.remittance.as_deref(), Some("Broetchen Filiale 12"));
+    assert_eq!(bakery.status, EntryStatus::Booked);
+
+    let gift = &statement.entries[1];
+    assert_eq!((gift.value_date, gift.booking_date), (Some(date(2023, 12, 29)), date(2024, 1, 2)));
+    assert_eq!(gift.reference, None);
+    assert_eq!(gift.remittance.as_deref(), Some("Neujahrsgeld"));
+
+    // A customer reference alone doesn't identify the entry.
+    assert_eq!(statement.entries[2].reference, None);
+}
+
+#[test]
+fn numbers_entries_sharing_a_customer_reference() {
+    let standing_orders = ":20:STANDING\n:61:2306010601DR50,00NSTODAUERAUFTRAG\n:61:2306010601DR50,00NSTODAUERAUFTRAG\n-\n";
+    let statement = parse_mt940(standing_orders.as_bytes()).unwrap().remove(0);
+
+    let import_ids: Vec<String> = statement.to_save_transactions("account-checking").into_iter().map(|transaction| transaction.import_id.unwrap()).collect();
+    assert_eq!(import_ids, vec!["YNAB:-50000:2023-06-01:1", "YNAB:-50000:2023-06-01:2"]);
+}
+
+#[test]
+fn marks_mt942_entries_pending() {
+    let statement = parse_mt940(MT942.as_bytes()).unwrap().remove(0);
+    assert_eq!(statement.currency.as_deref(), Some("EUR"));
+    assert_eq!(statement.entries[0].status, EntryStatus::Pending);
+    assert_eq!(statement.entries[0].booking_date, date(2023, 6, 16));
+    assert_eq!(statement.entries[0].amount, -8500);
+}
+
+#[test]
+fn reports_broken_mt940_fields() {
+    assert!(matches!(parse_mt940(":61:2306120612D4,50NMSC\n".as_bytes()), Err(Mt940ImportError::OrphanedField { line: 1, .. })));
+
+    let error = parse_mt940(":20:X\n:61:230612X4,50NMSC\n".as_bytes()).unwrap_err();
+    assert!(matches!(error, Mt940ImportError::InvalidField { line: 2, ref tag, .. } if tag == "61"));
+}
+
+#[tokio::test]
+async fn imports_statements_once() {
+    let server = seeded_server();
+    let client = server.client();
+
+    let statement = parse_mt940(MT940.as_bytes()).unwrap().remove(0);
+    let outcomes = import_statement(&client, "budget-household", "account-checking", &statement).await;
+    assert!(outcomes.iter().all(|outcome| matches!(outcome, ImportOutcome::Created(_))));
+
+    let outcomes = import_statement(&client, "budget-household", "account-checking", &statement).await;
+    assert!(outcomes.iter().all(|outcome| matches!(outcome, ImportOutcome::Duplicate { .. })));
+}